@@ -15,6 +15,10 @@
 //! with the [`spawn_ok`](ThreadPool::spawn_ok) function will run ambiently on
 //! the created threads.
 //!
+//! If tasks are `!Send`, or should stay on one thread for cache locality, a
+//! [`LocalPoolGroup`] runs a separate [`LocalPool`] on each worker thread
+//! instead (thread-per-core).
+//!
 //! # Spawning additional tasks
 //!
 //! Tasks can be spawned onto a spawner by calling its [`spawn_obj`] method
@@ -33,6 +37,24 @@
 //! There is also a convenience function [`block_on`] for simply running a
 //! future to completion on the current thread.
 //!
+//! # Asynchronous I/O
+//!
+//! On Linux, the [`net`] module (behind the `net` feature) provides an
+//! epoll-based reactor that drives sockets and pipes, and integrates with the
+//! park loop of [`block_on`] and [`LocalPool`].
+//!
+//! # Scoped tasks
+//!
+//! Tasks spawned onto a pool must be `'static`. To spawn tasks that borrow
+//! data from the stack, use [`scope`] (or [`LocalPool::scope`]), which runs
+//! all tasks spawned on the scope to completion before returning.
+//!
+//! # Task-local storage
+//!
+//! Values that should follow a task across executor threads, such as a
+//! request id, can be declared with the [`task_local!`] macro and installed
+//! for the duration of a future with [`LocalKey::scope`].
+//!
 //! [`spawn_obj`]: https://docs.rs/futures/0.3/futures/task/trait.Spawn.html#tymethod.spawn_obj
 //! [`spawn_local_obj`]: https://docs.rs/futures/0.3/futures/task/trait.LocalSpawn.html#tymethod.spawn_local_obj
 
@@ -53,11 +75,32 @@
 ))]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+#[cfg(all(feature = "net", target_os = "linux"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "net", target_os = "linux"))))]
+pub mod net;
+
 #[cfg(feature = "std")]
 mod local_pool;
 #[cfg(feature = "std")]
-pub use crate::local_pool::{block_on, block_on_stream, BlockingStream, LocalPool, LocalSpawner};
+pub use crate::local_pool::{
+    block_on, block_on_stream, block_on_timeout, block_on_until, try_block_on, BlockingStream,
+    Elapsed, LocalPool, LocalSpawner, Priority, RemoteSpawner, Stalled,
+};
+
+#[cfg(feature = "std")]
+mod scope;
+#[cfg(feature = "std")]
+pub use crate::scope::{scope, Scope};
 
+#[cfg(feature = "std")]
+mod task_local;
+#[cfg(feature = "std")]
+pub use crate::task_local::{AccessError, LocalKey, TaskLocalFuture};
+
+#[cfg(feature = "thread-pool")]
+#[cfg_attr(docsrs, doc(cfg(feature = "thread-pool")))]
+#[cfg(feature = "std")]
+mod local_pool_group;
 #[cfg(feature = "thread-pool")]
 #[cfg_attr(docsrs, doc(cfg(feature = "thread-pool")))]
 #[cfg(feature = "std")]
@@ -68,6 +111,10 @@ mod unpark_mutex;
 #[cfg(feature = "thread-pool")]
 #[cfg_attr(docsrs, doc(cfg(feature = "thread-pool")))]
 #[cfg(feature = "std")]
+pub use crate::local_pool_group::LocalPoolGroup;
+#[cfg(feature = "thread-pool")]
+#[cfg_attr(docsrs, doc(cfg(feature = "thread-pool")))]
+#[cfg(feature = "std")]
 pub use crate::thread_pool::{ThreadPool, ThreadPoolBuilder};
 
 #[cfg(feature = "std")]
@@ -1,20 +1,27 @@
 use crate::enter;
+use crate::scope::Scope;
 use futures_core::future::Future;
 use futures_core::stream::Stream;
-use futures_core::task::{Context, Poll};
+use futures_core::task::{Context, Poll, Waker};
+use futures_task::{set_current, TaskInfo};
 use futures_task::{waker_ref, ArcWake};
-use futures_task::{FutureObj, LocalFutureObj, LocalSpawn, Spawn, SpawnError};
+use futures_task::{FutureObj, LocalFutureObj, LocalSpawn, Spawn, SpawnError, SpawnOptions};
+use futures_util::future::FutureExt;
 use futures_util::pin_mut;
 use futures_util::stream::FuturesUnordered;
 use futures_util::stream::StreamExt;
 use std::cell::RefCell;
+use std::fmt;
+use std::mem;
 use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
 use std::rc::{Rc, Weak};
 use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc,
+    atomic::{self, AtomicBool, Ordering},
+    Arc, Mutex, Weak as ArcWeak,
 };
 use std::thread::{self, Thread};
+use std::time::{Duration, Instant};
 
 /// A single-threaded task pool for polling futures to completion.
 ///
@@ -27,10 +34,40 @@ use std::thread::{self, Thread};
 /// [`spawner()`](LocalPool::spawner) method. Because the executor is
 /// single-threaded, it supports a special form of task spawning for non-`Send`
 /// futures, via [`spawn_local_obj`](futures_task::LocalSpawn::spawn_local_obj).
+///
+/// If a task panics, the panic propagates to the caller of the run method.
+/// The panic hook runs while the panicking task is the [current
+/// task](futures_task::current), so it can name the task in its report.
+///
+/// # Stall detection
+///
+/// The pool keeps track of whether a [`Waker`](std::task::Waker) for each
+/// of its tasks is still alive. If every task is pending and no waker for any
+/// of them (or for the future passed to
+/// [`try_run_until`](LocalPool::try_run_until)) exists anymore, no wakeup can
+/// ever arrive. Instead of blocking the thread forever,
+/// [`try_run`](LocalPool::try_run) and
+/// [`try_run_until`](LocalPool::try_run_until) then return a [`Stalled`]
+/// error listing the stalled tasks and where they were spawned.
+/// [`try_block_on`] does the same for a single future.
+///
+/// [`run`](LocalPool::run), [`run_until`](LocalPool::run_until) and
+/// [`block_on`] check for stalls as well, and panic with the same report
+/// instead of returning it.
+///
+/// # Priorities
+///
+/// Tasks can be spawned with a [`Priority`] via
+/// [`spawn_local_with_priority`](LocalSpawner::spawn_local_with_priority);
+/// all other tasks have [`Priority::Normal`]. Whenever tasks of several
+/// priorities are ready, the pool polls the ready tasks of higher priority
+/// first. To avoid starving lower priorities, a priority whose ready tasks
+/// have been passed over several times in a row is polled first next time.
 #[derive(Debug)]
 pub struct LocalPool {
-    pool: FuturesUnordered<LocalFutureObj<'static, ()>>,
+    levels: [Level; 3],
     incoming: Rc<Incoming>,
+    remote: Arc<RemoteIncoming>,
 }
 
 /// A handle to a [`LocalPool`](LocalPool) that implements
@@ -40,7 +77,238 @@ pub struct LocalSpawner {
     incoming: Weak<Incoming>,
 }
 
-type Incoming = RefCell<Vec<LocalFutureObj<'static, ()>>>;
+type Incoming = RefCell<Vec<(LocalFutureObj<'static, ()>, Priority, TaskInfo)>>;
+
+/// The priority of a task spawned onto a [`LocalPool`](LocalPool).
+///
+/// See the [priorities](LocalPool#priorities) section of the [`LocalPool`]
+/// documentation for details.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// For bulk work that should only run when nothing else is ready.
+    Low,
+    /// The priority of tasks spawned without an explicit priority.
+    Normal,
+    /// For latency-sensitive tasks, such as UI or control tasks.
+    High,
+}
+
+impl Default for Priority {
+    fn default() -> Self {
+        Self::Normal
+    }
+}
+
+/// How many times in a row the ready tasks of a priority may be passed over
+/// in favor of higher priorities before they are polled first.
+const MAX_PASSED_OVER: usize = 8;
+
+/// The tasks of one priority.
+#[derive(Debug)]
+struct Level {
+    tasks: FuturesUnordered<TrackedTask>,
+    /// Set when a task of this priority is woken or spawned, so that levels
+    /// without ready tasks can be skipped.
+    woken: Arc<AtomicBool>,
+    /// The waker `tasks` is polled with.
+    waker: Option<Arc<LevelWaker>>,
+    /// How many times in a row this level was ready but not polled.
+    passed_over: usize,
+}
+
+/// Sets the `woken` flag of a level, then wakes the pool.
+///
+/// The `FuturesUnordered` of the level calls this only once the woken task
+/// is in its ready queue, so the pool can't poll the level in between and
+/// miss the task.
+#[derive(Debug)]
+struct LevelWaker {
+    woken: Arc<AtomicBool>,
+    waker: Waker,
+}
+
+impl ArcWake for LevelWaker {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.woken.store(true, Ordering::Release);
+        arc_self.waker.wake_by_ref();
+    }
+}
+
+impl Level {
+    fn new() -> Self {
+        Self {
+            tasks: FuturesUnordered::new(),
+            woken: Arc::new(AtomicBool::new(false)),
+            waker: None,
+            passed_over: 0,
+        }
+    }
+
+    fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<()>> {
+        let waker = match self.waker.take() {
+            Some(waker) if waker.waker.will_wake(cx.waker()) => waker,
+            _ => Arc::new(LevelWaker { woken: self.woken.clone(), waker: cx.waker().clone() }),
+        };
+        let waker = self.waker.get_or_insert(waker);
+        self.tasks.poll_next_unpin(&mut Context::from_waker(&waker_ref(waker)))
+    }
+}
+
+/// A handle to a [`LocalPool`](LocalPool) that can be sent to other threads.
+///
+/// This handle implements [`Spawn`](futures_task::Spawn) for `Send` futures,
+/// and can also spawn `!Send` futures by sending a function that constructs
+/// the future on the pool's thread, see
+/// [`spawn_local_fn`](RemoteSpawner::spawn_local_fn).
+///
+/// Spawned futures are queued until the pool is run. If the pool is
+/// currently blocked in one of its run methods, it is woken up.
+#[derive(Clone)]
+pub struct RemoteSpawner {
+    incoming: ArcWeak<RemoteIncoming>,
+}
+
+type RemoteTask = (Box<dyn FnOnce() -> LocalFutureObj<'static, ()> + Send>, TaskInfo);
+
+struct RemoteIncoming {
+    queue: Mutex<Vec<RemoteTask>>,
+    /// Whether `queue` is non-empty, to avoid locking on each turn.
+    has_tasks: AtomicBool,
+    /// The notifier of the thread the pool was created on.
+    thread_notify: Arc<ThreadNotify>,
+}
+
+/// An error returned when a pool is stalled, i.e. when its tasks are all
+/// pending, but no waker for any of them is alive.
+///
+/// See the [stall detection](LocalPool#stall-detection) section of the
+/// [`LocalPool`] documentation for details.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Stalled {
+    tasks: Vec<TaskInfo>,
+}
+
+impl Stalled {
+    /// Returns the stalled tasks in the pool, ordered by their
+    /// [`TaskId`](futures_task::TaskId).
+    pub fn tasks(&self) -> &[TaskInfo] {
+        &self.tasks
+    }
+}
+
+impl fmt::Display for Stalled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "executor stalled: all tasks are pending but no waker is alive")?;
+        for (i, task) in self.tasks.iter().enumerate() {
+            let sep = if i == 0 { " (stalled tasks: " } else { ", " };
+            write!(f, "{}{}", sep, task)?;
+            if let Some(location) = task.location() {
+                write!(f, " spawned at {}", location)?;
+            }
+        }
+        if !self.tasks.is_empty() {
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Stalled {}
+
+/// A task in the pool, together with the tracking of its waker.
+struct TrackedTask {
+    info: TaskInfo,
+    future: LocalFutureObj<'static, ()>,
+    tracker: WakerTracker,
+}
+
+impl Future for TrackedTask {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = &mut *self;
+        let future = &mut this.future;
+        let tracker = &mut this.tracker;
+        set_current(&this.info, || tracker.poll(cx, |cx| future.poll_unpin(cx)))
+    }
+}
+
+impl fmt::Debug for TrackedTask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TrackedTask").field("info", &self.info).finish()
+    }
+}
+
+/// Detects whether a clone of the waker passed to a future is still alive.
+///
+/// The future is polled with a waker that wraps the original one, of which
+/// the tracker only keeps a weak reference, so the wrapper is alive exactly
+/// as long as the future (or anything it handed its waker to) holds a clone
+/// of it. When the last clone is dropped without waking, the wrapper wakes
+/// the original waker anyway, so that the executor checks for a stall again
+/// instead of waiting for a wakeup that can't arrive anymore.
+#[derive(Default)]
+struct WakerTracker {
+    waker: Option<ArcWeak<TrackedWaker>>,
+}
+
+struct TrackedWaker {
+    waker: Waker,
+    /// Cleared when the tracker drops the wrapper itself.
+    wake_on_drop: AtomicBool,
+}
+
+impl ArcWake for TrackedWaker {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.waker.wake_by_ref()
+    }
+}
+
+impl Drop for TrackedWaker {
+    fn drop(&mut self) {
+        if *self.wake_on_drop.get_mut() {
+            self.waker.wake_by_ref();
+        }
+    }
+}
+
+impl WakerTracker {
+    // The waker in `cx` must stay the same on every call, as clones of the
+    // wrapper handed out earlier keep waking the original waker.
+    fn poll<T>(&mut self, cx: &mut Context<'_>, f: impl FnOnce(&mut Context<'_>) -> T) -> T {
+        let tracked = match self.waker.as_ref().and_then(ArcWeak::upgrade) {
+            Some(tracked) => tracked,
+            None => {
+                let tracked = Arc::new(TrackedWaker {
+                    waker: cx.waker().clone(),
+                    wake_on_drop: AtomicBool::new(true),
+                });
+                self.waker = Some(Arc::downgrade(&tracked));
+                tracked
+            }
+        };
+
+        let result = f(&mut Context::from_waker(&waker_ref(&tracked)));
+
+        // If this is the last reference, the future didn't keep a clone, and
+        // as no one else can clone the wrapper anymore, the executor doesn't
+        // need to be woken when it is dropped below.
+        if Arc::strong_count(&tracked) == 1 {
+            tracked.wake_on_drop.store(false, Ordering::Relaxed);
+        }
+        result
+    }
+
+    fn has_waker(&self) -> bool {
+        match &self.waker {
+            // If the last clone is dropped meanwhile, dropping the upgraded
+            // reference wakes the original waker.
+            Some(waker) => waker.upgrade().is_some(),
+            // not polled yet
+            None => true,
+        }
+    }
+}
 
 pub(crate) struct ThreadNotify {
     /// The (single) executor thread.
@@ -51,19 +319,25 @@ pub(crate) struct ThreadNotify {
     /// park / unpark calls of its own, i.e. we cannot assume that no other
     /// code uses park / unpark on the executing `thread`.
     unparked: AtomicBool,
+    /// Whether the thread waits for I/O events instead of parking, in which
+    /// case a wakeup must also interrupt the reactor.
+    #[cfg(all(feature = "net", target_os = "linux"))]
+    driving: AtomicBool,
 }
 
 thread_local! {
     static CURRENT_THREAD_NOTIFY: Arc<ThreadNotify> = Arc::new(ThreadNotify {
         thread: thread::current(),
         unparked: AtomicBool::new(false),
+        #[cfg(all(feature = "net", target_os = "linux"))]
+        driving: AtomicBool::new(false),
     });
 }
 
 impl ArcWake for ThreadNotify {
     fn wake_by_ref(arc_self: &Arc<Self>) {
         // Make sure the wakeup is remembered until the next `park()`.
-        let unparked = arc_self.unparked.swap(true, Ordering::Relaxed);
+        let unparked = arc_self.unparked.swap(true, Ordering::SeqCst);
         if !unparked {
             // If the thread has not been unparked yet, it must be done
             // now. If it was actually parked, it will run again,
@@ -71,13 +345,41 @@ impl ArcWake for ThreadNotify {
             // may be consumed before reaching `park()`, but `unparked`
             // ensures it is not forgotten.
             arc_self.thread.unpark();
+            #[cfg(all(feature = "net", target_os = "linux"))]
+            {
+                if arc_self.driving.load(Ordering::SeqCst) {
+                    if let Some(reactor) = crate::net::reactor::try_get() {
+                        reactor.notify();
+                    }
+                }
+            }
         }
     }
 }
 
+// Whether the current thread has been woken since the last call to `f` in
+// `run_executor`.
+fn wakeup_pending() -> bool {
+    // Synchronize with the threads whose wakers were seen to be dropped, so
+    // that their wakeups before dropping them are seen as well.
+    atomic::fence(Ordering::Acquire);
+    CURRENT_THREAD_NOTIFY.with(|thread_notify| thread_notify.unparked.load(Ordering::Acquire))
+}
+
 // Set up and run a basic single-threaded spawner loop, invoking `f` on each
 // turn.
-fn run_executor<T, F: FnMut(&mut Context<'_>) -> Poll<T>>(mut f: F) -> T {
+fn run_executor<T, F: FnMut(&mut Context<'_>) -> Poll<T>>(f: F) -> T {
+    match run_executor_until(None, f) {
+        Some(t) => t,
+        None => unreachable!(),
+    }
+}
+
+// Like `run_executor`, but returns `None` once `deadline` has passed.
+fn run_executor_until<T, F: FnMut(&mut Context<'_>) -> Poll<T>>(
+    deadline: Option<Instant>,
+    mut f: F,
+) -> Option<T> {
     let _enter = enter().expect(
         "cannot execute `LocalPool` executor from within \
          another executor",
@@ -88,15 +390,27 @@ fn run_executor<T, F: FnMut(&mut Context<'_>) -> Poll<T>>(mut f: F) -> T {
         let mut cx = Context::from_waker(&waker);
         loop {
             if let Poll::Ready(t) = f(&mut cx) {
-                return t;
+                return Some(t);
             }
+            // Checked on every turn, as futures that keep being woken never
+            // let the thread park.
+            let timeout = match deadline {
+                None => None,
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return None;
+                    }
+                    Some(deadline - now)
+                }
+            };
             // Consume the wakeup that occurred while executing `f`, if any.
             let unparked = thread_notify.unparked.swap(false, Ordering::Acquire);
             if !unparked {
                 // No wakeup occurred. It may occur now, right before parking,
                 // but in that case the token made available by `unpark()`
                 // is guaranteed to still be available and `park()` is a no-op.
-                thread::park();
+                park(thread_notify, timeout);
                 // When the thread is unparked, `unparked` will have been set
                 // and needs to be unset before the next call to `f` to avoid
                 // a redundant loop iteration.
@@ -106,6 +420,30 @@ fn run_executor<T, F: FnMut(&mut Context<'_>) -> Poll<T>>(mut f: F) -> T {
     })
 }
 
+// Blocks the current thread until it is woken or `timeout` has elapsed.
+fn park(thread_notify: &ThreadNotify, timeout: Option<Duration>) {
+    // Wait for I/O events instead, so that tasks waiting for them are woken
+    // without a detour through the background thread of the reactor.
+    #[cfg(all(feature = "net", target_os = "linux"))]
+    {
+        if let Some(reactor) = crate::net::reactor::try_get() {
+            thread_notify.driving.store(true, Ordering::SeqCst);
+            let drove = reactor.drive(timeout, &thread_notify.unparked);
+            thread_notify.driving.store(false, Ordering::SeqCst);
+            if drove {
+                return;
+            }
+        }
+    }
+    #[cfg(not(all(feature = "net", target_os = "linux")))]
+    let _ = thread_notify;
+
+    match timeout {
+        None => thread::park(),
+        Some(timeout) => thread::park_timeout(timeout),
+    }
+}
+
 fn poll_executor<T, F: FnMut(&mut Context<'_>) -> T>(mut f: F) -> T {
     let _enter = enter().expect(
         "cannot execute `LocalPool` executor from within \
@@ -122,7 +460,15 @@ fn poll_executor<T, F: FnMut(&mut Context<'_>) -> T>(mut f: F) -> T {
 impl LocalPool {
     /// Create a new, empty pool of tasks.
     pub fn new() -> Self {
-        Self { pool: FuturesUnordered::new(), incoming: Default::default() }
+        Self {
+            levels: [Level::new(), Level::new(), Level::new()],
+            incoming: Default::default(),
+            remote: Arc::new(RemoteIncoming {
+                queue: Mutex::new(Vec::new()),
+                has_tasks: AtomicBool::new(false),
+                thread_notify: CURRENT_THREAD_NOTIFY.with(Arc::clone),
+            }),
+        }
     }
 
     /// Get a clonable handle to the pool as a [`Spawn`].
@@ -130,6 +476,38 @@ impl LocalPool {
         LocalSpawner { incoming: Rc::downgrade(&self.incoming) }
     }
 
+    /// Get a clonable handle to the pool that is `Send` and `Sync`.
+    ///
+    /// Unlike [`spawner`](LocalPool::spawner), the returned
+    /// [`RemoteSpawner`] can be used to hand work to the pool from other
+    /// threads.
+    ///
+    /// ```
+    /// use futures::executor::LocalPool;
+    /// use futures::task::SpawnExt;
+    /// use std::rc::Rc;
+    /// use std::thread;
+    ///
+    /// let mut pool = LocalPool::new();
+    /// let remote = pool.remote_spawner();
+    ///
+    /// thread::spawn(move || {
+    ///     remote.spawn(async { /* a `Send` future */ }).unwrap();
+    ///     // `!Send` futures are constructed on the pool's thread.
+    ///     remote.spawn_local_fn(|| {
+    ///         let rc = Rc::new(());
+    ///         async move { drop(rc) }
+    ///     }).unwrap();
+    /// })
+    /// .join()
+    /// .unwrap();
+    ///
+    /// pool.run();
+    /// ```
+    pub fn remote_spawner(&self) -> RemoteSpawner {
+        RemoteSpawner { incoming: Arc::downgrade(&self.remote) }
+    }
+
     /// Run all tasks in the pool to completion.
     ///
     /// ```
@@ -145,8 +523,45 @@ impl LocalPool {
     ///
     /// The function will block the calling thread until *all* tasks in the pool
     /// are complete, including any spawned while running existing tasks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pool is [stalled](LocalPool#stall-detection), listing
+    /// the stalled tasks and where they were spawned. Use
+    /// [`try_run`](LocalPool::try_run) to handle a stall instead.
     pub fn run(&mut self) {
-        run_executor(|cx| self.poll_pool(cx))
+        if let Err(stalled) = self.try_run() {
+            panic!("{}", stalled)
+        }
+    }
+
+    /// Run all tasks in the pool to completion, or until the pool is
+    /// [stalled](LocalPool#stall-detection).
+    ///
+    /// ```
+    /// use futures::executor::LocalPool;
+    /// use futures::future::pending;
+    /// use futures::task::LocalSpawnExt;
+    ///
+    /// let mut pool = LocalPool::new();
+    /// let spawner = pool.spawner();
+    ///
+    /// // nothing can ever wake this task
+    /// spawner.spawn_local(pending()).unwrap();
+    ///
+    /// let stalled = pool.try_run().unwrap_err();
+    /// assert_eq!(stalled.tasks().len(), 1);
+    /// ```
+    pub fn try_run(&mut self) -> Result<(), Stalled> {
+        run_executor(|cx| {
+            if self.poll_pool(cx).is_ready() {
+                return Poll::Ready(Ok(()));
+            }
+            match self.stalled(true) {
+                Some(stalled) => Poll::Ready(Err(stalled)),
+                None => Poll::Pending,
+            }
+        })
     }
 
     /// Runs all the tasks in the pool until the given future completes.
@@ -166,23 +581,167 @@ impl LocalPool {
     /// be inert after the call completes, but can continue with further use of
     /// one of the pool's run or poll methods. While the function is running,
     /// however, all tasks in the pool will try to make progress.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the future and the pool are
+    /// [stalled](LocalPool#stall-detection). Use
+    /// [`try_run_until`](LocalPool::try_run_until) to handle a stall instead.
     pub fn run_until<F: Future>(&mut self, future: F) -> F::Output {
+        match self.try_run_until(future) {
+            Ok(output) => output,
+            Err(stalled) => panic!("{}", stalled),
+        }
+    }
+
+    /// Runs all the tasks in the pool until the given future completes, or
+    /// until the future and the pool are [stalled](LocalPool#stall-detection).
+    ///
+    /// If the pool is stalled, the tasks in the pool and the future itself are
+    /// all pending and can never be woken again.
+    ///
+    /// ```
+    /// use futures::executor::LocalPool;
+    /// use futures::future::pending;
+    ///
+    /// let mut pool = LocalPool::new();
+    ///
+    /// assert!(pool.try_run_until(pending::<()>()).is_err());
+    /// ```
+    pub fn try_run_until<F: Future>(&mut self, future: F) -> Result<F::Output, Stalled> {
         pin_mut!(future);
+        let mut tracker = WakerTracker::default();
 
         run_executor(|cx| {
             {
                 // if our main task is done, so are we
-                let result = future.as_mut().poll(cx);
+                let result = tracker.poll(cx, |cx| future.as_mut().poll(cx));
                 if let Poll::Ready(output) = result {
-                    return Poll::Ready(output);
+                    return Poll::Ready(Ok(output));
                 }
             }
 
             let _ = self.poll_pool(cx);
-            Poll::Pending
+            match self.stalled(!tracker.has_waker()) {
+                Some(stalled) => Poll::Ready(Err(stalled)),
+                None => Poll::Pending,
+            }
         })
     }
 
+    /// Runs all the tasks in the pool until the given future completes or
+    /// the timeout elapses.
+    ///
+    /// Returns the output of the future, or the unfinished future if the
+    /// timeout elapsed first, so that it can be run again later.
+    ///
+    /// ```
+    /// use futures::executor::LocalPool;
+    /// use futures::future::pending;
+    /// use std::time::Duration;
+    ///
+    /// let mut pool = LocalPool::new();
+    ///
+    /// assert!(pool.run_until_timeout(pending::<()>(), Duration::from_millis(10)).is_err());
+    /// ```
+    pub fn run_until_timeout<F: Future + Unpin>(
+        &mut self,
+        future: F,
+        timeout: Duration,
+    ) -> Result<F::Output, F> {
+        self.run_until_inner(future, deadline_after(timeout))
+    }
+
+    /// Runs all the tasks in the pool until the given future completes or
+    /// the deadline is reached.
+    ///
+    /// Returns the output of the future, or the unfinished future if the
+    /// deadline was reached first, so that it can be run again later.
+    pub fn run_until_deadline<F: Future + Unpin>(
+        &mut self,
+        future: F,
+        deadline: Instant,
+    ) -> Result<F::Output, F> {
+        self.run_until_inner(future, Some(deadline))
+    }
+
+    fn run_until_inner<F: Future + Unpin>(
+        &mut self,
+        mut future: F,
+        deadline: Option<Instant>,
+    ) -> Result<F::Output, F> {
+        let output = run_executor_until(deadline, |cx| {
+            // if our main task is done, so are we
+            if let Poll::Ready(output) = Pin::new(&mut future).poll(cx) {
+                return Poll::Ready(output);
+            }
+
+            let _ = self.poll_pool(cx);
+            Poll::Pending
+        });
+
+        match output {
+            Some(output) => Ok(output),
+            None => Err(future),
+        }
+    }
+
+    /// Create a scope for spawning tasks that may borrow non-`'static` data,
+    /// and run them to completion together with the tasks in the pool.
+    ///
+    /// After `f` returns, the calling thread is blocked until every task
+    /// spawned on the [`Scope`] has completed. While waiting, the tasks in
+    /// the pool also make progress, but they are not required to complete.
+    ///
+    /// See [`scope`](crate::scope()) for more details.
+    ///
+    /// ```
+    /// use futures::executor::LocalPool;
+    /// use futures::task::LocalSpawnExt;
+    ///
+    /// let mut pool = LocalPool::new();
+    /// let spawner = pool.spawner();
+    /// let mut words = Vec::new();
+    ///
+    /// pool.scope(|s| {
+    ///     spawner.spawn_local(async { /* runs alongside the scoped tasks */ }).unwrap();
+    ///     s.spawn(async {
+    ///         words.push("hello");
+    ///     });
+    /// });
+    ///
+    /// assert_eq!(words, ["hello"]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If any of the scoped tasks panics, the remaining scoped tasks are
+    /// dropped and the panic is propagated to the caller.
+    pub fn scope<'env, F, R>(&mut self, f: F) -> R
+    where
+        F: for<'scope> FnOnce(&'scope Scope<'scope, 'env>) -> R,
+    {
+        struct CancelOnDrop<'a, 'scope, 'env>(&'a Scope<'scope, 'env>);
+
+        impl Drop for CancelOnDrop<'_, '_, '_> {
+            fn drop(&mut self) {
+                self.0.cancel_all();
+            }
+        }
+
+        let scope = Scope::new();
+        let _guard = CancelOnDrop(&scope);
+        let result = f(&scope);
+
+        // Scoped tasks are not tracked, so the scope never reports a stall.
+        run_executor(|cx| {
+            let _ = self.poll_pool(cx);
+            scope.poll_tasks(cx)
+        });
+
+        result
+    }
+
     /// Runs all tasks and returns after completing one future or until no more progress
     /// can be made. Returns `true` if one future was completed, `false` otherwise.
     ///
@@ -224,7 +783,7 @@ impl LocalPool {
                 // if there are no new incoming futures
                 // then there is no feature that can make progress
                 // and we can return without having completed a single future
-                if self.incoming.borrow().is_empty() {
+                if !self.has_incoming() {
                     return false;
                 }
             }
@@ -270,7 +829,7 @@ impl LocalPool {
             let ret = self.poll_pool_once(cx);
 
             // we queued up some new tasks; add them and poll again
-            if !self.incoming.borrow().is_empty() {
+            if self.has_incoming() {
                 continue;
             }
 
@@ -287,14 +846,89 @@ impl LocalPool {
     fn poll_pool_once(&mut self, cx: &mut Context<'_>) -> Poll<Option<()>> {
         // empty the incoming queue of newly-spawned tasks
         {
-            let mut incoming = self.incoming.borrow_mut();
-            for task in incoming.drain(..) {
-                self.pool.push(task)
+            let incoming = mem::replace(&mut *self.incoming.borrow_mut(), Vec::new());
+            for (task, priority, info) in incoming {
+                self.push(task, priority, info)
             }
         }
 
-        // try to execute the next ready future
-        self.pool.poll_next_unpin(cx)
+        // construct the tasks spawned from other threads
+        if self.remote.has_tasks.swap(false, Ordering::Acquire) {
+            let remote = mem::replace(&mut *self.remote.queue.lock().unwrap(), Vec::new());
+            for (make_task, info) in remote {
+                self.push(make_task(), Priority::Normal, info)
+            }
+        }
+
+        // try to execute the next ready future, visiting the levels that
+        // have been passed over too often first, and then by priority
+        let mut order = [2, 1, 0];
+        order.sort_by_key(|&idx| self.levels[idx].passed_over < MAX_PASSED_OVER);
+
+        let mut is_empty = true;
+        for (i, &idx) in order.iter().enumerate() {
+            let level = &mut self.levels[idx];
+            if !level.woken.swap(false, Ordering::Acquire) {
+                is_empty &= level.tasks.is_empty();
+                continue;
+            }
+            level.passed_over = 0;
+            match level.poll_next(cx) {
+                Poll::Ready(Some(())) => {
+                    // other tasks of this level may still be ready
+                    level.woken.store(true, Ordering::Release);
+                    for &idx in &order[i + 1..] {
+                        let level = &mut self.levels[idx];
+                        if level.woken.load(Ordering::Acquire) {
+                            level.passed_over += 1;
+                        }
+                    }
+                    return Poll::Ready(Some(()));
+                }
+                Poll::Ready(None) => {}
+                Poll::Pending => is_empty = false,
+            }
+        }
+
+        if is_empty {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+
+    fn push(&mut self, future: LocalFutureObj<'static, ()>, priority: Priority, info: TaskInfo) {
+        let level = &mut self.levels[priority as usize];
+        level.tasks.push(TrackedTask { info, future, tracker: WakerTracker::default() });
+        level.woken.store(true, Ordering::Release);
+    }
+
+    fn tasks(&self) -> impl Iterator<Item = &TrackedTask> {
+        self.levels.iter().flat_map(|level| level.tasks.iter())
+    }
+
+    // Called after `poll_pool` returned `Pending`. Returns the stalled tasks if
+    // no wakeup has occurred, no waker for any task in the pool is alive and
+    // no task can be spawned from another thread. `main_stalled` tells whether
+    // the future the pool is run for (if any) is stalled as well.
+    fn stalled(&self, main_stalled: bool) -> Option<Stalled> {
+        // The wakers are checked before the wakeups, as a waker may be woken
+        // right before its last clone is dropped.
+        if !main_stalled
+            || self.tasks().any(|task| task.tracker.has_waker())
+            || Arc::weak_count(&self.remote) > 0
+            || wakeup_pending()
+            || self.has_incoming()
+        {
+            return None;
+        }
+        let mut tasks: Vec<_> = self.tasks().map(|task| task.info.clone()).collect();
+        tasks.sort_unstable_by_key(|task| task.id());
+        Some(Stalled { tasks })
+    }
+
+    fn has_incoming(&self) -> bool {
+        !self.incoming.borrow().is_empty() || self.remote.has_tasks.load(Ordering::Acquire)
     }
 }
 
@@ -310,9 +944,91 @@ impl Default for LocalPool {
 ///
 /// Use a [`LocalPool`](LocalPool) if you need finer-grained control over
 /// spawned tasks.
+///
+/// # Panics
+///
+/// Panics if the future is pending and no waker for it is alive anymore, as
+/// it can then never complete. Use [`try_block_on`] to handle that case
+/// instead.
 pub fn block_on<F: Future>(f: F) -> F::Output {
+    match try_block_on(f) {
+        Ok(output) => output,
+        Err(stalled) => panic!("{}", stalled),
+    }
+}
+
+/// Run a future to completion on the current thread, or until it is
+/// [stalled](LocalPool#stall-detection).
+///
+/// This function will block the caller until the given future has completed,
+/// or until the future is pending and no waker for it is alive anymore, as
+/// the future can then never complete.
+///
+/// ```
+/// use futures::executor::try_block_on;
+/// use futures::future::{pending, ready};
+///
+/// assert_eq!(try_block_on(ready(1)), Ok(1));
+/// assert!(try_block_on(pending::<()>()).is_err());
+/// ```
+pub fn try_block_on<F: Future>(f: F) -> Result<F::Output, Stalled> {
     pin_mut!(f);
-    run_executor(|cx| f.as_mut().poll(cx))
+    let mut tracker = WakerTracker::default();
+    run_executor(|cx| {
+        if let Poll::Ready(t) = tracker.poll(cx, |cx| f.as_mut().poll(cx)) {
+            return Poll::Ready(Ok(t));
+        }
+        // The waker is checked before the wakeups, see `LocalPool::stalled`.
+        if !tracker.has_waker() && !wakeup_pending() {
+            return Poll::Ready(Err(Stalled { tasks: Vec::new() }));
+        }
+        Poll::Pending
+    })
+}
+
+/// Run a future to completion on the current thread, or until the timeout
+/// elapses.
+///
+/// This function will block the caller until the given future has completed
+/// or the timeout has elapsed. In the latter case, the unfinished future is
+/// returned, so that it can be polled again later.
+///
+/// ```
+/// use futures::executor::block_on_timeout;
+/// use futures::future::{pending, ready};
+/// use std::time::Duration;
+///
+/// assert_eq!(block_on_timeout(ready(1), Duration::from_secs(1)).ok(), Some(1));
+/// assert!(block_on_timeout(pending::<()>(), Duration::from_millis(10)).is_err());
+/// ```
+///
+/// Futures that are not [`Unpin`] can be pinned with
+/// [`Box::pin`](Box::pin) first.
+pub fn block_on_timeout<F: Future + Unpin>(f: F, timeout: Duration) -> Result<F::Output, F> {
+    block_on_inner(f, deadline_after(timeout))
+}
+
+/// Run a future to completion on the current thread, or until the deadline
+/// is reached.
+///
+/// This function will block the caller until the given future has completed
+/// or the deadline has been reached. In the latter case, the unfinished
+/// future is returned, so that it can be polled again later.
+pub fn block_on_until<F: Future + Unpin>(f: F, deadline: Instant) -> Result<F::Output, F> {
+    block_on_inner(f, Some(deadline))
+}
+
+fn block_on_inner<F: Future + Unpin>(mut f: F, deadline: Option<Instant>) -> Result<F::Output, F> {
+    match run_executor_until(deadline, |cx| Pin::new(&mut f).poll(cx)) {
+        Some(output) => Ok(output),
+        None => Err(f),
+    }
+}
+
+// Returns the deadline after `timeout` from now. If the deadline cannot be
+// represented, the timeout is treated as infinite.
+fn deadline_after(timeout: Duration) -> Option<Instant> {
+    Instant::now().checked_add(timeout)
 }
 
 /// Turn a stream into a blocking iterator.
@@ -347,8 +1063,53 @@ impl<S: Stream + Unpin> BlockingStream<S> {
     pub fn into_inner(self) -> S {
         self.stream
     }
+
+    /// Blocks until the next element of the stream becomes available, or
+    /// until the timeout elapses.
+    ///
+    /// Returns `Ok(None)` if the stream has terminated, and
+    /// [`Err(Elapsed)`](Elapsed) if no element became available in time. In
+    /// the latter case, no element is lost and `next_timeout` can be called
+    /// again.
+    ///
+    /// ```
+    /// use futures::executor::block_on_stream;
+    /// use futures::stream::{self, StreamExt};
+    /// use std::time::Duration;
+    ///
+    /// let mut iter = block_on_stream(stream::iter(vec![1]).chain(stream::pending()));
+    ///
+    /// assert_eq!(iter.next_timeout(Duration::from_secs(1)), Ok(Some(1)));
+    /// assert!(iter.next_timeout(Duration::from_millis(10)).is_err());
+    /// ```
+    pub fn next_timeout(&mut self, timeout: Duration) -> Result<Option<S::Item>, Elapsed> {
+        block_on_inner(self.stream.next(), deadline_after(timeout))
+            .map_err(|_| Elapsed { _priv: () })
+    }
+}
+
+/// An error returned by
+/// [`BlockingStream::next_timeout`](BlockingStream::next_timeout) if the
+/// timeout elapsed before the next element became available.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Elapsed {
+    _priv: (),
+}
+
+impl fmt::Debug for Elapsed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Elapsed").finish()
+    }
 }
 
+impl fmt::Display for Elapsed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "deadline has elapsed")
+    }
+}
+
+impl std::error::Error for Elapsed {}
+
 impl<S: Stream + Unpin> Iterator for BlockingStream<S> {
     type Item = S::Item;
 
@@ -363,16 +1124,69 @@ impl<S: Stream + Unpin> Iterator for BlockingStream<S> {
 
 impl Spawn for LocalSpawner {
     fn spawn_obj(&self, future: FutureObj<'static, ()>) -> Result<(), SpawnError> {
-        if let Some(incoming) = self.incoming.upgrade() {
-            incoming.borrow_mut().push(future.into());
+        self.spawn_obj_with(future, SpawnOptions::new())
+    }
+
+    fn spawn_obj_with(
+        &self,
+        future: FutureObj<'static, ()>,
+        options: SpawnOptions<'_>,
+    ) -> Result<(), SpawnError> {
+        self.push(future.into(), Priority::Normal, options)
+    }
+
+    fn status(&self) -> Result<(), SpawnError> {
+        if self.incoming.upgrade().is_some() {
             Ok(())
         } else {
             Err(SpawnError::shutdown())
         }
     }
+}
 
-    fn status(&self) -> Result<(), SpawnError> {
-        if self.incoming.upgrade().is_some() {
+impl LocalSpawner {
+    /// Spawns a task with the given priority onto the pool.
+    ///
+    /// ```
+    /// use futures::executor::{LocalPool, Priority};
+    /// use futures::task::LocalSpawnExt;
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    ///
+    /// let mut pool = LocalPool::new();
+    /// let spawner = pool.spawner();
+    /// let order = Rc::new(RefCell::new(Vec::new()));
+    ///
+    /// let o = order.clone();
+    /// spawner.spawn_local_with_priority(Priority::Low, async move { o.borrow_mut().push("low") }).unwrap();
+    /// let o = order.clone();
+    /// spawner.spawn_local(async move { o.borrow_mut().push("normal") }).unwrap();
+    /// let o = order.clone();
+    /// spawner.spawn_local_with_priority(Priority::High, async move { o.borrow_mut().push("high") }).unwrap();
+    ///
+    /// pool.run();
+    /// assert_eq!(*order.borrow(), ["high", "normal", "low"]);
+    /// ```
+    #[cfg_attr(track_caller, track_caller)]
+    pub fn spawn_local_with_priority<Fut>(
+        &self,
+        priority: Priority,
+        future: Fut,
+    ) -> Result<(), SpawnError>
+    where
+        Fut: Future<Output = ()> + 'static,
+    {
+        self.push(LocalFutureObj::new(Box::new(future)), priority, SpawnOptions::new().at_caller())
+    }
+
+    fn push(
+        &self,
+        future: LocalFutureObj<'static, ()>,
+        priority: Priority,
+        options: SpawnOptions<'_>,
+    ) -> Result<(), SpawnError> {
+        if let Some(incoming) = self.incoming.upgrade() {
+            incoming.borrow_mut().push((future, priority, TaskInfo::from_options(options)));
             Ok(())
         } else {
             Err(SpawnError::shutdown())
@@ -382,15 +1196,87 @@ impl Spawn for LocalSpawner {
 
 impl LocalSpawn for LocalSpawner {
     fn spawn_local_obj(&self, future: LocalFutureObj<'static, ()>) -> Result<(), SpawnError> {
+        self.spawn_local_obj_with(future, SpawnOptions::new())
+    }
+
+    fn spawn_local_obj_with(
+        &self,
+        future: LocalFutureObj<'static, ()>,
+        options: SpawnOptions<'_>,
+    ) -> Result<(), SpawnError> {
+        self.push(future, Priority::Normal, options)
+    }
+
+    fn status_local(&self) -> Result<(), SpawnError> {
+        if self.incoming.upgrade().is_some() {
+            Ok(())
+        } else {
+            Err(SpawnError::shutdown())
+        }
+    }
+}
+
+impl RemoteSpawner {
+    /// Spawns a `!Send` future onto the pool.
+    ///
+    /// The function `f` is sent to the pool's thread, where it is called to
+    /// construct the future the next time the pool is run.
+    #[cfg_attr(track_caller, track_caller)]
+    pub fn spawn_local_fn<F, Fut>(&self, f: F) -> Result<(), SpawnError>
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + 'static,
+    {
+        self.spawn_local_fn_with(SpawnOptions::new(), f)
+    }
+
+    /// Spawns a `!Send` future onto the pool, configured by the given
+    /// [`SpawnOptions`].
+    ///
+    /// See [`spawn_local_fn`](RemoteSpawner::spawn_local_fn) for details.
+    #[cfg_attr(track_caller, track_caller)]
+    pub fn spawn_local_fn_with<F, Fut>(
+        &self,
+        options: SpawnOptions<'_>,
+        f: F,
+    ) -> Result<(), SpawnError>
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + 'static,
+    {
+        self.push(Box::new(move || LocalFutureObj::new(Box::new(f()))), options.at_caller())
+    }
+
+    fn push(
+        &self,
+        make_task: Box<dyn FnOnce() -> LocalFutureObj<'static, ()> + Send>,
+        options: SpawnOptions<'_>,
+    ) -> Result<(), SpawnError> {
         if let Some(incoming) = self.incoming.upgrade() {
-            incoming.borrow_mut().push(future);
+            incoming.queue.lock().unwrap().push((make_task, TaskInfo::from_options(options)));
+            incoming.has_tasks.store(true, Ordering::Release);
+            ArcWake::wake_by_ref(&incoming.thread_notify);
             Ok(())
         } else {
             Err(SpawnError::shutdown())
         }
     }
+}
 
-    fn status_local(&self) -> Result<(), SpawnError> {
+impl Spawn for RemoteSpawner {
+    fn spawn_obj(&self, future: FutureObj<'static, ()>) -> Result<(), SpawnError> {
+        self.spawn_obj_with(future, SpawnOptions::new())
+    }
+
+    fn spawn_obj_with(
+        &self,
+        future: FutureObj<'static, ()>,
+        options: SpawnOptions<'_>,
+    ) -> Result<(), SpawnError> {
+        self.push(Box::new(move || future.into()), options)
+    }
+
+    fn status(&self) -> Result<(), SpawnError> {
         if self.incoming.upgrade().is_some() {
             Ok(())
         } else {
@@ -398,3 +1284,15 @@ impl LocalSpawn for LocalSpawner {
         }
     }
 }
+
+impl fmt::Debug for RemoteSpawner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RemoteSpawner").finish()
+    }
+}
+
+impl fmt::Debug for RemoteIncoming {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RemoteIncoming").field("has_tasks", &self.has_tasks).finish()
+    }
+}
@@ -0,0 +1,235 @@
+use crate::local_pool::{LocalPool, RemoteSpawner};
+use crate::thread_pool::{OnPanic, ThreadPoolBuilder};
+use futures_core::future::Future;
+use futures_core::task::Poll;
+use futures_task::{FutureObj, Spawn, SpawnError, SpawnOptions};
+use futures_util::future::{self, poll_fn, FutureExt};
+use std::fmt;
+use std::io;
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread::{self, JoinHandle};
+
+/// A group of worker threads, each running its own [`LocalPool`].
+///
+/// Unlike a [`ThreadPool`](crate::ThreadPool), which moves tasks freely
+/// between its worker threads, a task spawned onto a `LocalPoolGroup` stays
+/// on the worker it was spawned on for its whole lifetime (thread-per-core).
+/// This means tasks do not need to be `Send`: they are spawned by sending a
+/// function that constructs the future to the worker thread, either to a
+/// specific worker with [`spawn_on`](LocalPoolGroup::spawn_on) or to the
+/// next worker in round-robin order with
+/// [`spawn_local_anywhere`](LocalPoolGroup::spawn_local_anywhere).
+///
+/// As in a [`ThreadPool`](crate::ThreadPool), a panic in a task does not
+/// take down its worker: the panic is caught, the task is dropped, and the
+/// [panic handler](ThreadPoolBuilder::panic_handler) and
+/// [`abort_on_panic`](ThreadPoolBuilder::abort_on_panic) setting of the
+/// builder apply. This includes panics of the function constructing the
+/// future.
+///
+/// Dropping the group signals all workers to stop; tasks that have not
+/// completed by then are dropped. Use [`shutdown`](LocalPoolGroup::shutdown)
+/// to also wait for the worker threads to exit.
+///
+/// A group is configured and created with a
+/// [`ThreadPoolBuilder`](ThreadPoolBuilder) via
+/// [`create_local_group`](ThreadPoolBuilder::create_local_group).
+///
+/// This type is only available when the `thread-pool` feature of this
+/// library is activated.
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor::LocalPoolGroup;
+/// use std::rc::Rc;
+///
+/// let group = LocalPoolGroup::new().unwrap();
+///
+/// group.spawn_on(0, || {
+///     // `Rc` is `!Send`, but the future is constructed on worker 0.
+///     let rc = Rc::new(1);
+///     async move { assert_eq!(*rc, 1) }
+/// }).unwrap();
+///
+/// group.shutdown();
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "thread-pool")))]
+pub struct LocalPoolGroup {
+    workers: Vec<Worker>,
+    next: AtomicUsize,
+    on_panic: OnPanic,
+}
+
+struct Worker {
+    spawner: RemoteSpawner,
+    shutdown: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl LocalPoolGroup {
+    /// Creates a new group of local pools with the default configuration.
+    ///
+    /// See documentation for the methods in
+    /// [`ThreadPoolBuilder`](ThreadPoolBuilder) for details on the default
+    /// configuration.
+    pub fn new() -> Result<Self, io::Error> {
+        ThreadPoolBuilder::new().create_local_group()
+    }
+
+    pub(crate) fn start(
+        size: usize,
+        mut thread_builder: impl FnMut(usize) -> thread::Builder,
+        after_start: Option<Arc<dyn Fn(usize) + Send + Sync>>,
+        before_stop: Option<Arc<dyn Fn(usize) + Send + Sync>>,
+        on_panic: OnPanic,
+    ) -> Result<Self, io::Error> {
+        // If spawning a thread fails, dropping the group stops the workers
+        // that have already been started.
+        let mut group =
+            Self { workers: Vec::with_capacity(size), next: AtomicUsize::new(0), on_panic };
+
+        for idx in 0..size {
+            let (tx, rx) = mpsc::sync_channel(1);
+            let shutdown = Arc::new(AtomicBool::new(false));
+            let after_start = after_start.clone();
+            let before_stop = before_stop.clone();
+            let shutdown2 = shutdown.clone();
+            let thread = thread_builder(idx).spawn(move || {
+                let mut pool = LocalPool::new();
+                tx.send(pool.remote_spawner()).unwrap();
+                if let Some(after_start) = after_start {
+                    after_start(idx);
+                }
+                pool.run_until(poll_fn(|_| {
+                    if shutdown2.load(Ordering::Acquire) {
+                        Poll::Ready(())
+                    } else {
+                        Poll::Pending
+                    }
+                }));
+                if let Some(before_stop) = before_stop {
+                    before_stop(idx);
+                }
+            })?;
+            let spawner = rx.recv().unwrap();
+            group.workers.push(Worker { spawner, shutdown, thread: Some(thread) });
+        }
+
+        Ok(group)
+    }
+
+    /// Returns the number of worker threads in the group.
+    pub fn size(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Spawns a task onto the worker with the given index.
+    ///
+    /// The function `f` is sent to the worker thread, where it is called to
+    /// construct the future.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `worker >= self.size()`.
+    #[cfg_attr(track_caller, track_caller)]
+    pub fn spawn_on<F, Fut>(&self, worker: usize, f: F) -> Result<(), SpawnError>
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + 'static,
+    {
+        let on_panic = self.on_panic.clone();
+        // `f` is called when the task is first polled, so that a panic in
+        // it is caught as well.
+        self.workers[worker]
+            .spawner
+            .spawn_local_fn(move || catch_panic(future::lazy(|_| f()).flatten(), on_panic))
+    }
+
+    /// Spawns a task onto the next worker in round-robin order.
+    ///
+    /// The function `f` is sent to the worker thread, where it is called to
+    /// construct the future.
+    #[cfg_attr(track_caller, track_caller)]
+    pub fn spawn_local_anywhere<F, Fut>(&self, f: F) -> Result<(), SpawnError>
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + 'static,
+    {
+        self.spawn_on(self.next_worker(), f)
+    }
+
+    /// Stops all worker threads and waits for them to exit.
+    ///
+    /// Tasks that have not completed are dropped on their worker threads.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a worker thread has panicked.
+    pub fn shutdown(mut self) {
+        self.signal_shutdown();
+        for worker in &mut self.workers {
+            if let Some(thread) = worker.thread.take() {
+                thread.join().unwrap();
+            }
+        }
+    }
+
+    fn signal_shutdown(&self) {
+        for worker in &self.workers {
+            worker.shutdown.store(true, Ordering::Release);
+            // Wake the worker so it notices the shutdown.
+            let _ = worker.spawner.spawn_obj(FutureObj::new(Box::new(future::ready(()))));
+        }
+    }
+
+    fn next_worker(&self) -> usize {
+        self.next.fetch_add(1, Ordering::Relaxed) % self.workers.len()
+    }
+}
+
+// Catches a panic of `future`, which runs as a task of a worker, and
+// reports it like a `ThreadPool` does.
+fn catch_panic<Fut>(future: Fut, on_panic: OnPanic) -> impl Future<Output = ()>
+where
+    Fut: Future<Output = ()>,
+{
+    AssertUnwindSafe(future).catch_unwind().map(move |res| {
+        if let Err(payload) = res {
+            // The worker polls the task as the current task.
+            let task = futures_task::current().expect("task polled outside of its worker");
+            on_panic.task_panicked(payload, &task);
+        }
+    })
+}
+
+impl Spawn for LocalPoolGroup {
+    fn spawn_obj(&self, future: FutureObj<'static, ()>) -> Result<(), SpawnError> {
+        self.spawn_obj_with(future, SpawnOptions::new())
+    }
+
+    fn spawn_obj_with(
+        &self,
+        future: FutureObj<'static, ()>,
+        options: SpawnOptions<'_>,
+    ) -> Result<(), SpawnError> {
+        let future = catch_panic(future, self.on_panic.clone());
+        self.workers[self.next_worker()]
+            .spawner
+            .spawn_obj_with(FutureObj::new(Box::new(future)), options)
+    }
+}
+
+impl Drop for LocalPoolGroup {
+    fn drop(&mut self) {
+        self.signal_shutdown();
+    }
+}
+
+impl fmt::Debug for LocalPoolGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalPoolGroup").field("size", &self.size()).finish()
+    }
+}
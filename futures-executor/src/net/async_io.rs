@@ -0,0 +1,419 @@
+use super::reactor::{self, Direction, Source};
+use futures_core::future::Future;
+use futures_core::ready;
+use futures_core::task::{Context, Poll};
+use futures_io::{AsyncRead, AsyncWrite, IoSlice, IoSliceMut};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::mem;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+use std::os::unix::net::UnixStream;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// An I/O object driven by the epoll reactor.
+///
+/// `Async<T>` puts the file descriptor of `T` into nonblocking mode and
+/// registers it with the reactor. I/O operations are attempted directly on
+/// `T`; if they would block, the task is woken once the reactor receives a
+/// readiness event for the file descriptor.
+///
+/// [`AsyncRead`] and [`AsyncWrite`] are implemented for `Async<T>` if `T`
+/// implements [`Read`] and [`Write`], e.g. for [`TcpStream`], [`UnixStream`]
+/// and pipes (see [`pipe`](super::pipe)), and for `&Async<T>` if `&T`
+/// implements them, so that a stream can be read from and written to by
+/// different tasks at the same time. Only one task at a time should read
+/// from (or write to) the same `Async<T>`.
+///
+/// Closing an `Async<T>` with
+/// [`poll_close`](futures_io::AsyncWrite::poll_close) only flushes it; the
+/// file descriptor is closed when the `Async<T>` is dropped.
+pub struct Async<T> {
+    source: Arc<Source>,
+    io: Option<T>,
+}
+
+impl<T> Unpin for Async<T> {}
+
+impl<T: AsRawFd> Async<T> {
+    /// Puts the I/O object into nonblocking mode and registers it with the
+    /// reactor.
+    pub fn new(io: T) -> io::Result<Self> {
+        let fd = io.as_raw_fd();
+        set_nonblocking(fd)?;
+        let source = reactor::get()?.register(fd)?;
+        Ok(Self { source, io: Some(io) })
+    }
+}
+
+impl<T> Async<T> {
+    /// Gets a reference to the underlying I/O object.
+    pub fn get_ref(&self) -> &T {
+        self.io.as_ref().unwrap()
+    }
+
+    /// Gets a mutable reference to the underlying I/O object.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.io.as_mut().unwrap()
+    }
+
+    /// Deregisters the I/O object from the reactor and returns it.
+    ///
+    /// The I/O object is left in nonblocking mode.
+    pub fn into_inner(mut self) -> io::Result<T> {
+        let io = self.io.take().unwrap();
+        reactor::get()?.deregister(&self.source)?;
+        Ok(io)
+    }
+
+    /// Waits until the I/O object may be readable.
+    ///
+    /// Readiness is only a hint: the next read may still fail with
+    /// [`WouldBlock`](io::ErrorKind::WouldBlock).
+    pub fn poll_readable(&self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.source.poll_ready(Direction::Read, cx)
+    }
+
+    /// Waits until the I/O object may be writable.
+    ///
+    /// Readiness is only a hint: the next write may still fail with
+    /// [`WouldBlock`](io::ErrorKind::WouldBlock).
+    pub fn poll_writable(&self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.source.poll_ready(Direction::Write, cx)
+    }
+
+    /// Performs a read operation, waiting for the I/O object to become
+    /// readable as long as `op` fails with
+    /// [`WouldBlock`](io::ErrorKind::WouldBlock).
+    pub fn poll_read_with<R>(
+        &self,
+        cx: &mut Context<'_>,
+        mut op: impl FnMut(&T) -> io::Result<R>,
+    ) -> Poll<io::Result<R>> {
+        let io = self.io.as_ref().unwrap();
+        poll_io(&self.source, Direction::Read, cx, || op(io))
+    }
+
+    /// Performs a write operation, waiting for the I/O object to become
+    /// writable as long as `op` fails with
+    /// [`WouldBlock`](io::ErrorKind::WouldBlock).
+    pub fn poll_write_with<R>(
+        &self,
+        cx: &mut Context<'_>,
+        mut op: impl FnMut(&T) -> io::Result<R>,
+    ) -> Poll<io::Result<R>> {
+        let io = self.io.as_ref().unwrap();
+        poll_io(&self.source, Direction::Write, cx, || op(io))
+    }
+
+    fn poll_read_mut<R>(
+        &mut self,
+        cx: &mut Context<'_>,
+        mut op: impl FnMut(&mut T) -> io::Result<R>,
+    ) -> Poll<io::Result<R>> {
+        let io = self.io.as_mut().unwrap();
+        poll_io(&self.source, Direction::Read, cx, || op(&mut *io))
+    }
+
+    fn poll_write_mut<R>(
+        &mut self,
+        cx: &mut Context<'_>,
+        mut op: impl FnMut(&mut T) -> io::Result<R>,
+    ) -> Poll<io::Result<R>> {
+        let io = self.io.as_mut().unwrap();
+        poll_io(&self.source, Direction::Write, cx, || op(&mut *io))
+    }
+}
+
+fn poll_io<R>(
+    source: &Source,
+    dir: Direction,
+    cx: &mut Context<'_>,
+    mut op: impl FnMut() -> io::Result<R>,
+) -> Poll<io::Result<R>> {
+    loop {
+        match op() {
+            Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {}
+            res => return Poll::Ready(res),
+        }
+        ready!(source.poll_ready(dir, cx))?;
+    }
+}
+
+impl<T> Drop for Async<T> {
+    fn drop(&mut self) {
+        if self.io.is_some() {
+            // Deregister before the file descriptor is closed by dropping `io`.
+            if let Some(reactor) = reactor::try_get() {
+                let _ = reactor.deregister(&self.source);
+            }
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Async<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Async").field("io", &self.io).finish()
+    }
+}
+
+impl<T> AsRawFd for Async<T> {
+    fn as_raw_fd(&self) -> RawFd {
+        self.source.fd
+    }
+}
+
+impl<T: Read> AsyncRead for Async<T> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut().poll_read_mut(cx, |io| io.read(buf))
+    }
+
+    fn poll_read_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &mut [IoSliceMut<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut().poll_read_mut(cx, |io| io.read_vectored(bufs))
+    }
+}
+
+impl<T: Write> AsyncWrite for Async<T> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut().poll_write_mut(cx, |io| io.write(buf))
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut().poll_write_mut(cx, |io| io.write_vectored(bufs))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().poll_write_mut(cx, |io| io.flush())
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_flush(cx)
+    }
+}
+
+impl<T> AsyncRead for &Async<T>
+where
+    for<'a> &'a T: Read,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        self.poll_read_with(cx, |mut io| io.read(buf))
+    }
+
+    fn poll_read_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &mut [IoSliceMut<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.poll_read_with(cx, |mut io| io.read_vectored(bufs))
+    }
+}
+
+impl<T> AsyncWrite for &Async<T>
+where
+    for<'a> &'a T: Write,
+{
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.poll_write_with(cx, |mut io| io.write(buf))
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.poll_write_with(cx, |mut io| io.write_vectored(bufs))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_write_with(cx, |mut io| io.flush())
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_flush(cx)
+    }
+}
+
+impl Async<TcpListener> {
+    /// Creates a TCP listener bound to the given address.
+    pub fn bind(addr: SocketAddr) -> io::Result<Self> {
+        Self::new(TcpListener::bind(addr)?)
+    }
+
+    /// Accepts a new incoming connection.
+    pub fn poll_accept(
+        &self,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<(Async<TcpStream>, SocketAddr)>> {
+        let (stream, addr) = ready!(self.poll_read_with(cx, TcpListener::accept))?;
+        Poll::Ready(Ok((Async::new(stream)?, addr)))
+    }
+
+    /// Accepts a new incoming connection.
+    pub fn accept(&self) -> Accept<'_> {
+        Accept { listener: self }
+    }
+}
+
+/// Future for the [`accept`](Async::accept) method.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Accept<'a> {
+    listener: &'a Async<TcpListener>,
+}
+
+impl Future for Accept<'_> {
+    type Output = io::Result<(Async<TcpStream>, SocketAddr)>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.listener.poll_accept(cx)
+    }
+}
+
+impl Async<TcpStream> {
+    /// Opens a TCP connection to the given address.
+    ///
+    /// Unlike [`TcpStream::connect`], this does not block the thread while
+    /// the connection is being established.
+    pub fn connect(addr: SocketAddr) -> Connect {
+        Connect { stream: Some(connect_nonblocking(&addr)) }
+    }
+}
+
+/// Future for the [`connect`](Async::connect) method.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Connect {
+    stream: Option<io::Result<Async<TcpStream>>>,
+}
+
+impl Future for Connect {
+    type Output = io::Result<Async<TcpStream>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let stream = match self.stream.as_ref().expect("`Connect` polled after completion") {
+            Ok(stream) => stream,
+            Err(_) => return Poll::Ready(self.stream.take().unwrap()),
+        };
+        ready!(stream.poll_write_with(cx, |stream| {
+            if let Some(e) = stream.take_error()? {
+                return Err(e);
+            }
+            match stream.peer_addr() {
+                Ok(_) => Ok(()),
+                // the connection is still in progress
+                Err(ref e) if e.raw_os_error() == Some(libc::ENOTCONN) => {
+                    Err(io::ErrorKind::WouldBlock.into())
+                }
+                Err(e) => Err(e),
+            }
+        }))?;
+        Poll::Ready(self.stream.take().unwrap())
+    }
+}
+
+fn connect_nonblocking(addr: &SocketAddr) -> io::Result<Async<TcpStream>> {
+    let domain = match addr {
+        SocketAddr::V4(_) => libc::AF_INET,
+        SocketAddr::V6(_) => libc::AF_INET6,
+    };
+    let fd = unsafe {
+        libc::socket(domain, libc::SOCK_STREAM | libc::SOCK_NONBLOCK | libc::SOCK_CLOEXEC, 0)
+    };
+    if fd == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    // Safety: `fd` is a newly created socket owned by nobody else.
+    let stream = unsafe { TcpStream::from_raw_fd(fd) };
+
+    let (storage, len) = sockaddr(addr);
+    let ret = unsafe { libc::connect(fd, &storage as *const _ as *const libc::sockaddr, len) };
+    if ret == -1 {
+        let err = io::Error::last_os_error();
+        if err.raw_os_error() != Some(libc::EINPROGRESS) {
+            return Err(err);
+        }
+    }
+    Async::new(stream)
+}
+
+fn sockaddr(addr: &SocketAddr) -> (libc::sockaddr_storage, libc::socklen_t) {
+    // Safety: all-zero is a valid `sockaddr_storage`, `sockaddr_in` and
+    // `sockaddr_in6`, and both fit into `sockaddr_storage`.
+    unsafe {
+        let mut storage: libc::sockaddr_storage = mem::zeroed();
+        let len = match addr {
+            SocketAddr::V4(addr) => {
+                let sin = &mut *(&mut storage as *mut _ as *mut libc::sockaddr_in);
+                sin.sin_family = libc::AF_INET as libc::sa_family_t;
+                sin.sin_port = addr.port().to_be();
+                sin.sin_addr.s_addr = u32::from_ne_bytes(addr.ip().octets());
+                mem::size_of::<libc::sockaddr_in>()
+            }
+            SocketAddr::V6(addr) => {
+                let sin6 = &mut *(&mut storage as *mut _ as *mut libc::sockaddr_in6);
+                sin6.sin6_family = libc::AF_INET6 as libc::sa_family_t;
+                sin6.sin6_port = addr.port().to_be();
+                sin6.sin6_flowinfo = addr.flowinfo();
+                sin6.sin6_addr.s6_addr = addr.ip().octets();
+                sin6.sin6_scope_id = addr.scope_id();
+                mem::size_of::<libc::sockaddr_in6>()
+            }
+        };
+        (storage, len as libc::socklen_t)
+    }
+}
+
+impl Async<UnixStream> {
+    /// Creates an unnamed pair of connected Unix domain sockets.
+    pub fn pair() -> io::Result<(Self, Self)> {
+        let (a, b) = UnixStream::pair()?;
+        Ok((Self::new(a)?, Self::new(b)?))
+    }
+}
+
+/// Creates an anonymous pipe, returning its reading and writing ends.
+pub fn pipe() -> io::Result<(Async<File>, Async<File>)> {
+    let mut fds = [0; 2];
+    if unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC | libc::O_NONBLOCK) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    // Safety: both file descriptors were just created and are owned by us.
+    let (reader, writer) = unsafe { (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1])) };
+    Ok((Async::new(reader)?, Async::new(writer)?))
+}
+
+fn set_nonblocking(fd: RawFd) -> io::Result<()> {
+    unsafe {
+        let flags = libc::fcntl(fd, libc::F_GETFL);
+        if flags == -1 || libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK) == -1 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
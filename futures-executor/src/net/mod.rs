@@ -0,0 +1,49 @@
+//! An epoll-based reactor for asynchronous I/O on Linux.
+//!
+//! [`Async<T>`](Async) wraps a nonblocking file descriptor, such as a
+//! [`TcpStream`](std::net::TcpStream), a
+//! [`UnixStream`](std::os::unix::net::UnixStream) or a [`pipe`], and
+//! implements [`AsyncRead`](futures_io::AsyncRead) and
+//! [`AsyncWrite`](futures_io::AsyncWrite) for it.
+//!
+//! The readiness events of all `Async` objects are received by a single,
+//! process-wide epoll instance (the reactor). It is started when the first
+//! `Async` object is created and is driven by a background thread. While a
+//! thread running [`block_on`](crate::block_on) or a
+//! [`LocalPool`](crate::LocalPool) has nothing to do, it waits for I/O
+//! events itself instead of parking, so that the tasks it runs are woken
+//! without a detour through another thread. Tasks running on any other
+//! executor, such as a [`ThreadPool`](crate::ThreadPool), are woken by the
+//! background thread.
+//!
+//! This module is only available on Linux, when the `net` feature of this
+//! library is activated.
+//!
+//! # Examples
+//!
+//! ```
+//! use futures::executor::block_on;
+//! use futures::executor::net::Async;
+//! use futures::io::{AsyncReadExt, AsyncWriteExt};
+//! use std::net::TcpListener;
+//!
+//! block_on(async {
+//!     let listener = Async::<TcpListener>::bind(([127, 0, 0, 1], 0).into())?;
+//!     let addr = listener.get_ref().local_addr()?;
+//!
+//!     let mut client = Async::connect(addr).await?;
+//!     let (mut server, _) = listener.accept().await?;
+//!
+//!     client.write_all(b"ping").await?;
+//!     let mut buf = [0; 4];
+//!     server.read_exact(&mut buf).await?;
+//!     assert_eq!(&buf, b"ping");
+//!     # Ok::<(), std::io::Error>(())
+//! })?;
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+mod async_io;
+pub(crate) mod reactor;
+
+pub use self::async_io::{pipe, Accept, Async, Connect};
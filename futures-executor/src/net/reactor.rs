@@ -0,0 +1,372 @@
+use futures_core::task::{Context, Poll, Waker};
+use std::collections::HashMap;
+use std::io;
+use std::os::raw::c_int;
+use std::os::unix::io::RawFd;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, Once};
+use std::thread;
+use std::time::Duration;
+
+/// The key of the eventfd used to interrupt `epoll_wait`.
+const NOTIFY_KEY: u64 = u64::max_value();
+
+static REACTOR: AtomicPtr<Reactor> = AtomicPtr::new(ptr::null_mut());
+/// The error that prevented the reactor from starting, if any.
+static INIT_ERROR: AtomicPtr<io::Error> = AtomicPtr::new(ptr::null_mut());
+static INIT: Once = Once::new();
+
+/// The process-wide epoll instance.
+///
+/// At any time, at most one thread waits for events and dispatches them
+/// (drives the reactor). By default, this is a background thread, but a
+/// thread running `block_on` or a `LocalPool` drives the reactor itself
+/// instead of parking while it has nothing else to do; see `drive`.
+pub(crate) struct Reactor {
+    epoll_fd: RawFd,
+    event_fd: RawFd,
+    sources: Mutex<HashMap<u64, Arc<Source>>>,
+    next_key: AtomicUsize,
+    driver: Mutex<DriverState>,
+    driver_changed: Condvar,
+    /// Set once waiting for events failed. The reactor stops, and every
+    /// source reports the error from then on.
+    failed: AtomicBool,
+    error: Mutex<Option<io::Error>>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Driver {
+    None,
+    Background,
+    Executor,
+}
+
+struct DriverState {
+    driver: Driver,
+    /// The number of executor threads waiting to drive the reactor.
+    requests: usize,
+}
+
+/// A file descriptor registered with the reactor.
+pub(crate) struct Source {
+    pub(crate) fd: RawFd,
+    key: u64,
+    state: Mutex<SourceState>,
+}
+
+#[derive(Default)]
+struct SourceState {
+    /// Readiness events that occurred since the last failed attempt.
+    readable: bool,
+    writable: bool,
+    reader: Option<Waker>,
+    writer: Option<Waker>,
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum Direction {
+    Read,
+    Write,
+}
+
+/// Returns the reactor, starting it if necessary.
+///
+/// If the reactor could not be started, the error is returned, now and on
+/// every later call.
+pub(crate) fn get() -> io::Result<&'static Reactor> {
+    INIT.call_once(|| match start() {
+        Ok(reactor) => {
+            REACTOR.store(reactor as *const Reactor as *mut Reactor, Ordering::Release);
+        }
+        Err(e) => {
+            INIT_ERROR.store(Box::into_raw(Box::new(e)), Ordering::Release);
+        }
+    });
+    if let Some(reactor) = try_get() {
+        return Ok(reactor);
+    }
+    // Safety: `INIT` has completed without storing the reactor, so the
+    // pointer points to the leaked error.
+    Err(copy_error(unsafe { &*INIT_ERROR.load(Ordering::Acquire) }))
+}
+
+fn start() -> io::Result<&'static Reactor> {
+    let reactor: &'static Reactor = Box::leak(Box::new(Reactor::new()?));
+    thread::Builder::new()
+        .name("futures-reactor".to_string())
+        .spawn(move || reactor.background_loop())?;
+    Ok(reactor)
+}
+
+/// Returns the reactor if it has been started, i.e. if any I/O source has
+/// been registered.
+pub(crate) fn try_get() -> Option<&'static Reactor> {
+    // Safety: the pointer is either null or points to the leaked reactor.
+    unsafe { REACTOR.load(Ordering::Acquire).as_ref() }
+}
+
+// `io::Error` isn't `Clone`.
+fn copy_error(e: &io::Error) -> io::Error {
+    match e.raw_os_error() {
+        Some(code) => io::Error::from_raw_os_error(code),
+        None => io::Error::new(e.kind(), e.to_string()),
+    }
+}
+
+fn cvt(ret: c_int) -> io::Result<c_int> {
+    if ret == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(ret)
+    }
+}
+
+impl Reactor {
+    fn new() -> io::Result<Self> {
+        let epoll_fd = cvt(unsafe { libc::epoll_create1(libc::EPOLL_CLOEXEC) })?;
+        let event_fd = cvt(unsafe { libc::eventfd(0, libc::EFD_CLOEXEC | libc::EFD_NONBLOCK) })?;
+        let mut event = libc::epoll_event { events: libc::EPOLLIN as u32, u64: NOTIFY_KEY };
+        cvt(unsafe { libc::epoll_ctl(epoll_fd, libc::EPOLL_CTL_ADD, event_fd, &mut event) })?;
+        Ok(Self {
+            epoll_fd,
+            event_fd,
+            sources: Mutex::new(HashMap::new()),
+            next_key: AtomicUsize::new(0),
+            driver: Mutex::new(DriverState { driver: Driver::None, requests: 0 }),
+            driver_changed: Condvar::new(),
+            failed: AtomicBool::new(false),
+            error: Mutex::new(None),
+        })
+    }
+
+    /// Registers a nonblocking file descriptor.
+    pub(crate) fn register(&self, fd: RawFd) -> io::Result<Arc<Source>> {
+        self.check()?;
+        let key = self.next_key.fetch_add(1, Ordering::Relaxed) as u64;
+        let source = Arc::new(Source {
+            fd,
+            key,
+            // Assume the descriptor is ready, so that the first operation is
+            // attempted before waiting for an event.
+            state: Mutex::new(SourceState { readable: true, writable: true, ..Default::default() }),
+        });
+        self.sources.lock().unwrap().insert(key, source.clone());
+
+        let events = libc::EPOLLIN | libc::EPOLLOUT | libc::EPOLLRDHUP | libc::EPOLLET;
+        let mut event = libc::epoll_event { events: events as u32, u64: key };
+        if let Err(e) =
+            cvt(unsafe { libc::epoll_ctl(self.epoll_fd, libc::EPOLL_CTL_ADD, fd, &mut event) })
+        {
+            self.sources.lock().unwrap().remove(&key);
+            return Err(e);
+        }
+        Ok(source)
+    }
+
+    /// Deregisters a file descriptor registered with `register`.
+    pub(crate) fn deregister(&self, source: &Source) -> io::Result<()> {
+        self.sources.lock().unwrap().remove(&source.key);
+        let mut event = libc::epoll_event { events: 0, u64: 0 };
+        cvt(unsafe { libc::epoll_ctl(self.epoll_fd, libc::EPOLL_CTL_DEL, source.fd, &mut event) })?;
+        Ok(())
+    }
+
+    /// Interrupts the thread that is currently waiting for events.
+    pub(crate) fn notify(&self) {
+        let one: u64 = 1;
+        unsafe { libc::write(self.event_fd, &one as *const u64 as *const libc::c_void, 8) };
+    }
+
+    /// Drives the reactor on an executor thread that would otherwise park,
+    /// waiting for at most `timeout`.
+    ///
+    /// Returns without waiting for events if `woken` is set once this thread
+    /// drives the reactor; a waker setting it must call `notify` afterwards.
+    /// Returns `false` without waiting if another executor thread is already
+    /// driving the reactor; the caller should park instead.
+    pub(crate) fn drive(&self, timeout: Option<Duration>, woken: &AtomicBool) -> bool {
+        let mut state = self.driver.lock().unwrap();
+        if state.driver == Driver::Executor || self.failed.load(Ordering::SeqCst) {
+            return false;
+        }
+        state.requests += 1;
+        if state.driver == Driver::Background {
+            // Take over from the background thread. It only waits for
+            // events, so it gives up the reactor quickly once notified.
+            self.notify();
+            while state.driver == Driver::Background {
+                state = self.driver_changed.wait(state).unwrap();
+            }
+        }
+        state.requests -= 1;
+        if state.driver != Driver::None {
+            // another executor thread was faster
+            self.driver_changed.notify_all();
+            return false;
+        }
+        state.driver = Driver::Executor;
+        drop(state);
+
+        // A notification sent before this thread took over may have been
+        // consumed by the background thread.
+        let res = if woken.load(Ordering::SeqCst) { Ok(()) } else { self.poll_events(timeout) };
+
+        self.driver.lock().unwrap().driver = Driver::None;
+        self.driver_changed.notify_all();
+        if let Err(e) = res {
+            self.fail(e);
+        }
+        true
+    }
+
+    // Drives the reactor whenever no executor thread does.
+    fn background_loop(&self) {
+        loop {
+            let mut state = self.driver.lock().unwrap();
+            while state.driver != Driver::None || state.requests > 0 {
+                state = self.driver_changed.wait(state).unwrap();
+            }
+            if self.failed.load(Ordering::SeqCst) {
+                return;
+            }
+            state.driver = Driver::Background;
+            drop(state);
+
+            let res = self.poll_events(None);
+
+            self.driver.lock().unwrap().driver = Driver::None;
+            self.driver_changed.notify_all();
+            if let Err(e) = res {
+                return self.fail(e);
+            }
+        }
+    }
+
+    // Stops the reactor after waiting for events failed with `e`: the
+    // tasks waiting for events are woken, and get the error.
+    fn fail(&self, e: io::Error) {
+        {
+            let mut error = self.error.lock().unwrap();
+            if error.is_none() {
+                *error = Some(e);
+            }
+            self.failed.store(true, Ordering::SeqCst);
+        }
+        let mut wakers = Vec::new();
+        for source in self.sources.lock().unwrap().values() {
+            let mut state = source.state.lock().unwrap();
+            wakers.extend(state.reader.take());
+            wakers.extend(state.writer.take());
+        }
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    // Returns the error that stopped the reactor, if any.
+    fn check(&self) -> io::Result<()> {
+        if !self.failed.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        match &*self.error.lock().unwrap() {
+            Some(e) => Err(copy_error(e)),
+            None => Ok(()),
+        }
+    }
+
+    // Waits for events and wakes the tasks waiting for them. Must only be
+    // called by the thread driving the reactor.
+    fn poll_events(&self, timeout: Option<Duration>) -> io::Result<()> {
+        let timeout_ms = match timeout {
+            None => -1,
+            Some(timeout) => {
+                // round up, so that the timeout has elapsed on return
+                let ms = timeout.as_millis() + u128::from(timeout.subsec_nanos() % 1_000_000 != 0);
+                ms.min(c_int::max_value() as u128) as c_int
+            }
+        };
+
+        let mut events = [libc::epoll_event { events: 0, u64: 0 }; 64];
+        let n = loop {
+            match cvt(unsafe {
+                libc::epoll_wait(
+                    self.epoll_fd,
+                    events.as_mut_ptr(),
+                    events.len() as c_int,
+                    timeout_ms,
+                )
+            }) {
+                Ok(n) => break n as usize,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        };
+
+        let mut wakers = Vec::new();
+        {
+            let sources = self.sources.lock().unwrap();
+            for event in &events[..n] {
+                let (flags, key) = (event.events as c_int, event.u64);
+                if key == NOTIFY_KEY {
+                    let mut buf = [0u8; 8];
+                    unsafe { libc::read(self.event_fd, buf.as_mut_ptr() as *mut libc::c_void, 8) };
+                    continue;
+                }
+                let source = match sources.get(&key) {
+                    Some(source) => source,
+                    None => continue,
+                };
+                let mut state = source.state.lock().unwrap();
+                let err = libc::EPOLLERR | libc::EPOLLHUP;
+                if flags & (libc::EPOLLIN | libc::EPOLLRDHUP | err) != 0 {
+                    state.readable = true;
+                    wakers.extend(state.reader.take());
+                }
+                if flags & (libc::EPOLLOUT | err) != 0 {
+                    state.writable = true;
+                    wakers.extend(state.writer.take());
+                }
+            }
+        }
+        for waker in wakers {
+            waker.wake();
+        }
+        Ok(())
+    }
+}
+
+impl Source {
+    /// Returns `Ready` if a readiness event in the given direction occurred
+    /// since the last call, and registers the task to be woken by the next
+    /// event otherwise.
+    ///
+    /// This is called after an operation failed with `WouldBlock`; if an
+    /// event occurred in the meantime, the operation should be retried.
+    ///
+    /// Returns the error that stopped the reactor, if waiting for events
+    /// failed, as no event will arrive anymore.
+    pub(crate) fn poll_ready(&self, dir: Direction, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let mut guard = self.state.lock().unwrap();
+        let state = &mut *guard;
+        let (ready, waker) = match dir {
+            Direction::Read => (&mut state.readable, &mut state.reader),
+            Direction::Write => (&mut state.writable, &mut state.writer),
+        };
+        if *ready {
+            *ready = false;
+            return Poll::Ready(Ok(()));
+        }
+        // Checked with the state locked, so that either the waker is taken
+        // by `Reactor::fail`, or the error is seen here.
+        if let Some(reactor) = try_get() {
+            reactor.check()?;
+        }
+        match waker {
+            Some(waker) if waker.will_wake(cx.waker()) => {}
+            _ => *waker = Some(cx.waker().clone()),
+        }
+        Poll::Pending
+    }
+}
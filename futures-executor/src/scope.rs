@@ -0,0 +1,167 @@
+use crate::local_pool::LocalPool;
+use futures_core::future::Future;
+use futures_core::task::{Context, Poll};
+use futures_task::LocalFutureObj;
+use futures_util::future::{CatchUnwind, FutureExt};
+use futures_util::stream::{FuturesUnordered, StreamExt};
+use std::cell::RefCell;
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+
+// The lifetime of scoped tasks is erased, as the tasks are owned by the
+// `Scope`, which is in turn borrowed for `'scope`. See `Scope::spawn`.
+type ScopedTask = CatchUnwind<AssertUnwindSafe<LocalFutureObj<'static, ()>>>;
+
+/// A scope to spawn tasks that may borrow non-`'static` data.
+///
+/// See [`scope`] for details.
+pub struct Scope<'scope, 'env: 'scope> {
+    pool: RefCell<FuturesUnordered<ScopedTask>>,
+    incoming: RefCell<Vec<ScopedTask>>,
+    /// Invariance over 'scope, to make sure 'scope cannot shrink,
+    /// which is necessary for soundness.
+    ///
+    /// Without invariance, this would compile fine but be unsound:
+    ///
+    /// ```compile_fail
+    /// futures::executor::scope(|s| {
+    ///     s.spawn(async {
+    ///         let a = String::from("abcd");
+    ///         s.spawn(async { println!("{:?}", a) }); // might run after `a` is dropped
+    ///     });
+    /// });
+    /// ```
+    _scope: PhantomData<&'scope mut &'scope ()>,
+    _env: PhantomData<&'env mut &'env ()>,
+}
+
+/// Create a scope for spawning tasks that may borrow non-`'static` data and
+/// run them to completion on the current thread.
+///
+/// The function passed to `scope` will be provided a [`Scope`] object,
+/// through which scoped tasks can be [spawned](Scope::spawn).
+///
+/// Unlike tasks spawned onto a [`LocalPool`] or a `ThreadPool`, scoped tasks
+/// can borrow non-`'static` data, as the scope guarantees all tasks will be
+/// finished at the end of the scope: after `f` returns, `scope` blocks the
+/// calling thread until every task spawned on the scope (including tasks
+/// spawned by other scoped tasks) has completed.
+///
+/// This is equivalent to `LocalPool::new().scope(f)`; see
+/// [`LocalPool::scope`] to also make progress on the tasks of an existing
+/// pool while waiting.
+///
+/// # Panics
+///
+/// If any of the scoped tasks panics, the remaining tasks are dropped
+/// (cancelled) and the panic is propagated to the caller of `scope` once
+/// they have been dropped.
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor;
+///
+/// let mut a = vec![1, 2, 3];
+/// let mut x = 0;
+///
+/// executor::scope(|s| {
+///     s.spawn(async {
+///         println!("hello from the first scoped task");
+///         // We can borrow `a` here.
+///         dbg!(&a);
+///     });
+///     s.spawn(async {
+///         println!("hello from the second scoped task");
+///         // We can even mutably borrow `x` here,
+///         // because no other tasks are using it.
+///         x += a[0] + a[2];
+///     });
+///     println!("hello from the main thread");
+/// });
+///
+/// // After the scope, we can modify and access our variables again:
+/// a.push(4);
+/// assert_eq!(x, a.len());
+/// ```
+pub fn scope<'env, F, R>(f: F) -> R
+where
+    F: for<'scope> FnOnce(&'scope Scope<'scope, 'env>) -> R,
+{
+    LocalPool::new().scope(f)
+}
+
+impl<'scope> Scope<'scope, '_> {
+    pub(crate) fn new() -> Self {
+        Self {
+            pool: RefCell::new(FuturesUnordered::new()),
+            incoming: RefCell::new(Vec::new()),
+            _scope: PhantomData,
+            _env: PhantomData,
+        }
+    }
+
+    /// Spawns a new task within the scope.
+    ///
+    /// Unlike [`LocalSpawnExt::spawn_local`](futures_util::task::LocalSpawnExt::spawn_local),
+    /// this method allows the future to borrow any non-`'static` data that
+    /// outlives the scope, including the scope itself, so that scoped tasks
+    /// can spawn further scoped tasks.
+    ///
+    /// The future is not polled until the scope function has returned, or,
+    /// if it is spawned by another scoped task, until that task yields.
+    pub fn spawn<Fut>(&'scope self, future: Fut)
+    where
+        Fut: Future<Output = ()> + 'scope,
+    {
+        let future: LocalFutureObj<'scope, ()> = LocalFutureObj::new(Box::new(future));
+        // Safety: The task is owned by this scope, and `LocalPool::scope`
+        // makes sure that all tasks have completed or have been dropped by
+        // `cancel_all` before it returns or unwinds, i.e. before `'scope` ends.
+        let future = unsafe {
+            mem::transmute::<LocalFutureObj<'scope, ()>, LocalFutureObj<'static, ()>>(future)
+        };
+        self.incoming.borrow_mut().push(AssertUnwindSafe(future).catch_unwind());
+    }
+
+    // Make maximal progress on the scoped tasks, returning `Ready` once all of
+    // them have completed and `Pending` if no further progress can be made.
+    pub(crate) fn poll_tasks(&self, cx: &mut Context<'_>) -> Poll<()> {
+        let mut pool = self.pool.borrow_mut();
+        loop {
+            pool.extend(self.incoming.borrow_mut().drain(..));
+
+            match pool.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(()))) => {}
+                // The remaining tasks are cancelled by `cancel_all` while
+                // the panic propagates to the owner of the scope.
+                Poll::Ready(Some(Err(payload))) => panic::resume_unwind(payload),
+                Poll::Ready(None) | Poll::Pending if !self.incoming.borrow().is_empty() => {}
+                Poll::Ready(None) => return Poll::Ready(()),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+
+    // Drop all tasks that have not completed yet. Tasks may spawn new tasks
+    // from their destructors, so repeat until the scope is empty.
+    pub(crate) fn cancel_all(&self) {
+        loop {
+            let pool = mem::replace(&mut *self.pool.borrow_mut(), FuturesUnordered::new());
+            let incoming = mem::replace(&mut *self.incoming.borrow_mut(), Vec::new());
+            if pool.is_empty() && incoming.is_empty() {
+                return;
+            }
+            drop(pool);
+            drop(incoming);
+        }
+    }
+}
+
+impl fmt::Debug for Scope<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Scope").finish()
+    }
+}
@@ -0,0 +1,226 @@
+use futures_core::future::Future;
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+use std::cell::RefCell;
+use std::fmt;
+use std::mem;
+use std::pin::Pin;
+use std::thread;
+
+/// Declares a new task-local key of type [`LocalKey`](LocalKey).
+///
+/// # Syntax
+///
+/// The macro wraps any number of static declarations and makes them
+/// task-local. Publicity and attributes for each static are allowed.
+///
+/// ```
+/// use futures::executor::task_local;
+///
+/// task_local! {
+///     pub static REQUEST_ID: u32;
+///
+///     static TENANT: String;
+/// }
+/// # fn main() {}
+/// ```
+///
+/// See [`LocalKey` documentation](LocalKey) for more information.
+#[macro_export]
+macro_rules! task_local {
+    // empty (base case for the recursion)
+    () => {};
+
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $t:ty; $($rest:tt)*) => {
+        $crate::__task_local_inner!($(#[$attr])* $vis $name, $t);
+        $crate::task_local!($($rest)*);
+    };
+
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $t:ty) => {
+        $crate::__task_local_inner!($(#[$attr])* $vis $name, $t);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __task_local_inner {
+    ($(#[$attr:meta])* $vis:vis $name:ident, $t:ty) => {
+        $(#[$attr])*
+        $vis static $name: $crate::LocalKey<$t> = {
+            ::std::thread_local! {
+                static __KEY: ::std::cell::RefCell<::std::option::Option<$t>> =
+                    ::std::cell::RefCell::new(::std::option::Option::None);
+            }
+
+            $crate::LocalKey { inner: __KEY }
+        };
+    };
+}
+
+/// A key for task-local data.
+///
+/// This type is generated by the [`task_local!`](crate::task_local) macro.
+///
+/// Unlike a [`std::thread::LocalKey`], a task-local value is bound to a
+/// future rather than to a thread: [`LocalKey::scope`] wraps a future so that
+/// the value is installed every time the future is polled and removed again
+/// once `poll` returns. The value therefore follows the task when it migrates
+/// between the worker threads of a `ThreadPool`, and
+/// works the same way under [`LocalPool`](crate::LocalPool),
+/// [`block_on`](crate::block_on) or any other executor.
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor::{block_on, task_local};
+///
+/// task_local! {
+///     static REQUEST_ID: u32;
+/// }
+///
+/// block_on(REQUEST_ID.scope(1, async move {
+///     assert_eq!(REQUEST_ID.with(|v| *v), 1);
+///
+///     REQUEST_ID.scope(2, async move {
+///         assert_eq!(REQUEST_ID.with(|v| *v), 2);
+///     }).await;
+///
+///     assert_eq!(REQUEST_ID.with(|v| *v), 1);
+/// }));
+///
+/// assert!(REQUEST_ID.try_with(|_| ()).is_err());
+/// ```
+pub struct LocalKey<T: 'static> {
+    #[doc(hidden)]
+    pub inner: thread::LocalKey<RefCell<Option<T>>>,
+}
+
+impl<T: 'static> LocalKey<T> {
+    /// Sets a value `T` as the task-local value for the future `F`.
+    ///
+    /// On completion of `scope`, the task-local will be dropped.
+    pub fn scope<F>(&'static self, value: T, future: F) -> TaskLocalFuture<T, F>
+    where
+        F: Future,
+    {
+        TaskLocalFuture { local: self, slot: Some(value), future }
+    }
+
+    /// Sets a value `T` as the task-local value for the closure `f`.
+    ///
+    /// On completion of `sync_scope`, the task-local will be dropped.
+    pub fn sync_scope<F, R>(&'static self, value: T, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        let mut slot = Some(value);
+        self.scope_inner(&mut slot, f)
+    }
+
+    fn scope_inner<F, R>(&'static self, slot: &mut Option<T>, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        struct Reset<'a, T: 'static> {
+            local: &'static LocalKey<T>,
+            slot: &'a mut Option<T>,
+        }
+
+        impl<T: 'static> Drop for Reset<'_, T> {
+            fn drop(&mut self) {
+                // Put the value back into the future's slot and restore the
+                // previous value, even if `f` panicked.
+                self.local.inner.with(|c| mem::swap(self.slot, &mut *c.borrow_mut()));
+            }
+        }
+
+        self.inner.with(|c| mem::swap(slot, &mut *c.borrow_mut()));
+        let _reset = Reset { local: self, slot };
+        f()
+    }
+
+    /// Accesses the current task-local and runs the provided closure.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if not called within the context
+    /// of a future containing a task-local with the corresponding key.
+    pub fn with<F, R>(&'static self, f: F) -> R
+    where
+        F: FnOnce(&T) -> R,
+    {
+        self.try_with(f).expect(
+            "cannot access a task-local storage value \
+             without setting it via `LocalKey::scope`",
+        )
+    }
+
+    /// Accesses the current task-local and runs the provided closure.
+    ///
+    /// If the task-local with the associated key is not present, this
+    /// method will return an `AccessError`. For a panicking variant,
+    /// see `with`.
+    pub fn try_with<F, R>(&'static self, f: F) -> Result<R, AccessError>
+    where
+        F: FnOnce(&T) -> R,
+    {
+        match self.inner.try_with(|c| c.borrow().as_ref().map(f)) {
+            Ok(Some(r)) => Ok(r),
+            _ => Err(AccessError { _priv: () }),
+        }
+    }
+}
+
+impl<T: 'static> fmt::Debug for LocalKey<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("LocalKey { .. }")
+    }
+}
+
+pin_project! {
+    /// Future for the [`scope`](LocalKey::scope) method.
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct TaskLocalFuture<T: 'static, F> {
+        local: &'static LocalKey<T>,
+        slot: Option<T>,
+        #[pin]
+        future: F,
+    }
+}
+
+impl<T: 'static, F: Future> Future for TaskLocalFuture<T, F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let future = this.future;
+        this.local.scope_inner(this.slot, || future.poll(cx))
+    }
+}
+
+impl<T: 'static, F: fmt::Debug> fmt::Debug for TaskLocalFuture<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaskLocalFuture").field("future", &self.future).finish()
+    }
+}
+
+/// An error returned by [`LocalKey::try_with`](LocalKey::try_with) if the
+/// task-local value is not set for the current task.
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub struct AccessError {
+    _priv: (),
+}
+
+impl fmt::Debug for AccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AccessError").finish()
+    }
+}
+
+impl fmt::Display for AccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "task-local value not set")
+    }
+}
+
+impl std::error::Error for AccessError {}
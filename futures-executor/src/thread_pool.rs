@@ -1,13 +1,18 @@
 use crate::enter;
+use crate::local_pool_group::LocalPoolGroup;
 use crate::unpark_mutex::UnparkMutex;
 use futures_core::future::Future;
 use futures_core::task::{Context, Poll};
+use futures_task::{set_current, FutureObj, Spawn, SpawnError, SpawnOptions, TaskId, TaskInfo};
 use futures_task::{waker_ref, ArcWake};
-use futures_task::{FutureObj, Spawn, SpawnError};
 use futures_util::future::FutureExt;
+use std::any::Any;
 use std::cmp;
+use std::collections::BTreeMap;
 use std::fmt;
 use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::process;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::mpsc;
 use std::sync::{Arc, Mutex};
@@ -22,6 +27,18 @@ use std::thread;
 /// This type is a clonable handle to the threadpool itself.
 /// Cloning it will only create a new reference, not a new threadpool.
 ///
+/// A panic in a task does not take down the worker thread running it: the
+/// panic is caught, the task is dropped and the worker continues with the
+/// next task. See [`ThreadPoolBuilder::panic_handler`] to be notified of
+/// such panics, along with the id and name of the task, and
+/// [`ThreadPoolBuilder::abort_on_panic`] to abort the process instead.
+///
+/// Tasks can be named with [`SpawnExt::spawn_with`](futures_util::task::SpawnExt::spawn_with).
+/// The names of named tasks that have not completed yet are included in the
+/// `Debug` output of the pool, and are available to the running task, its
+/// panic hook and the panic handler through
+/// [`futures::task::current`](futures_task::current).
+///
 /// This type is only available when the `thread-pool` feature of this
 /// library is activated.
 #[cfg_attr(docsrs, doc(cfg(feature = "thread-pool")))]
@@ -40,8 +57,12 @@ pub struct ThreadPoolBuilder {
     name_prefix: Option<String>,
     after_start: Option<Arc<dyn Fn(usize) + Send + Sync>>,
     before_stop: Option<Arc<dyn Fn(usize) + Send + Sync>>,
+    panic_handler: Option<Arc<PanicHandler>>,
+    abort_on_panic: bool,
 }
 
+type PanicHandler = dyn Fn(Box<dyn Any + Send>, &TaskInfo) + Send + Sync;
+
 trait AssertSendSync: Send + Sync {}
 impl AssertSendSync for ThreadPool {}
 
@@ -50,11 +71,26 @@ struct PoolState {
     rx: Mutex<mpsc::Receiver<Message>>,
     cnt: AtomicUsize,
     size: usize,
+    on_panic: OnPanic,
+    /// The named tasks that have not completed yet, for `Debug` output.
+    named_tasks: Mutex<BTreeMap<TaskId, TaskInfo>>,
 }
 
 impl fmt::Debug for ThreadPool {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("ThreadPool").field("size", &self.state.size).finish()
+        struct NamedTasks<'a>(&'a BTreeMap<TaskId, TaskInfo>);
+
+        impl fmt::Debug for NamedTasks<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_map().entries(self.0.iter().map(|(id, task)| (id, task.name()))).finish()
+            }
+        }
+
+        let named_tasks = self.state.named_tasks.lock().unwrap();
+        f.debug_struct("ThreadPool")
+            .field("size", &self.state.size)
+            .field("named_tasks", &NamedTasks(&named_tasks))
+            .finish()
     }
 }
 
@@ -63,6 +99,7 @@ impl fmt::Debug for ThreadPoolBuilder {
         f.debug_struct("ThreadPoolBuilder")
             .field("pool_size", &self.pool_size)
             .field("name_prefix", &self.name_prefix)
+            .field("abort_on_panic", &self.abort_on_panic)
             .finish()
     }
 }
@@ -96,8 +133,16 @@ impl ThreadPool {
     /// > **Note**: This method is similar to `Spawn::spawn_obj`, except that
     /// >           it is guaranteed to always succeed.
     pub fn spawn_obj_ok(&self, future: FutureObj<'static, ()>) {
+        self.spawn_obj_ok_with(future, SpawnOptions::new())
+    }
+
+    fn spawn_obj_ok_with(&self, future: FutureObj<'static, ()>, options: SpawnOptions<'_>) {
+        let info = TaskInfo::from_options(options);
+        let named = info.name().map(|_| NamedTask::new(&self.state, &info));
         let task = Task {
             future,
+            info,
+            named,
             wake_handle: Arc::new(WakeHandle { exec: self.clone(), mutex: UnparkMutex::new() }),
             exec: self.clone(),
         };
@@ -131,6 +176,15 @@ impl Spawn for ThreadPool {
         self.spawn_obj_ok(future);
         Ok(())
     }
+
+    fn spawn_obj_with(
+        &self,
+        future: FutureObj<'static, ()>,
+        options: SpawnOptions<'_>,
+    ) -> Result<(), SpawnError> {
+        self.spawn_obj_ok_with(future, options);
+        Ok(())
+    }
 }
 
 impl PoolState {
@@ -161,6 +215,26 @@ impl PoolState {
     }
 }
 
+/// What to do once a task has panicked, as configured by
+/// `ThreadPoolBuilder::panic_handler` and `abort_on_panic`.
+#[derive(Clone)]
+pub(crate) struct OnPanic {
+    handler: Option<Arc<PanicHandler>>,
+    abort: bool,
+}
+
+impl OnPanic {
+    // Called on the worker thread after `task` panicked.
+    pub(crate) fn task_panicked(&self, payload: Box<dyn Any + Send>, task: &TaskInfo) {
+        if let Some(handler) = &self.handler {
+            handler(payload, task);
+        }
+        if self.abort {
+            process::abort();
+        }
+    }
+}
+
 impl Clone for ThreadPool {
     fn clone(&self) -> Self {
         self.state.cnt.fetch_add(1, Ordering::Relaxed);
@@ -189,6 +263,8 @@ impl ThreadPoolBuilder {
             name_prefix: None,
             after_start: None,
             before_stop: None,
+            panic_handler: None,
+            abort_on_panic: false,
         }
     }
 
@@ -258,6 +334,56 @@ impl ThreadPoolBuilder {
         self
     }
 
+    /// Execute closure `f` when a task panics.
+    ///
+    /// Panics in tasks are caught by the worker thread, which drops the
+    /// panicking task and keeps running other tasks. The closure `f`
+    /// receives the panic payload and the identity of the task (its id,
+    /// name and location), and runs on the worker thread the task panicked
+    /// on. The panic message itself is still reported by the [panic
+    /// hook](std::panic::set_hook) as usual.
+    ///
+    /// Both the panic hook and `f` run while the panicking task is the
+    /// [current task](futures_task::current), so the panic hook can include
+    /// the name of the task in its report as well.
+    ///
+    /// If `f` panics, the worker thread is taken down.
+    ///
+    /// This also applies to the tasks of a
+    /// [`LocalPoolGroup`](LocalPoolGroup) created by this builder.
+    ///
+    /// ```
+    /// use futures::executor::ThreadPool;
+    ///
+    /// let pool = ThreadPool::builder()
+    ///     .panic_handler(|payload, task| {
+    ///         let msg = payload.downcast_ref::<&str>().unwrap_or(&"<unknown>");
+    ///         eprintln!("task {} panicked: {}", task, msg);
+    ///     })
+    ///     .create()
+    ///     .unwrap();
+    /// ```
+    pub fn panic_handler<F>(&mut self, f: F) -> &mut Self
+    where
+        F: Fn(Box<dyn Any + Send>, &TaskInfo) + Send + Sync + 'static,
+    {
+        self.panic_handler = Some(Arc::new(f));
+        self
+    }
+
+    /// Abort the process when a task panics.
+    ///
+    /// This is intended for services that prefer to crash fast rather than
+    /// keep running after a bug was detected. The [panic
+    /// handler](ThreadPoolBuilder::panic_handler), if any, is executed before
+    /// aborting.
+    ///
+    /// By default, a panicking task is dropped and the pool keeps running.
+    pub fn abort_on_panic(&mut self, abort: bool) -> &mut Self {
+        self.abort_on_panic = abort;
+        self
+    }
+
     /// Create a [`ThreadPool`](ThreadPool) with the given configuration.
     pub fn create(&mut self) -> Result<ThreadPool, io::Error> {
         let (tx, rx) = mpsc::channel();
@@ -267,6 +393,8 @@ impl ThreadPoolBuilder {
                 rx: Mutex::new(rx),
                 cnt: AtomicUsize::new(1),
                 size: self.pool_size,
+                on_panic: self.on_panic(),
+                named_tasks: Mutex::new(BTreeMap::new()),
             }),
         };
 
@@ -274,17 +402,42 @@ impl ThreadPoolBuilder {
             let state = pool.state.clone();
             let after_start = self.after_start.clone();
             let before_stop = self.before_stop.clone();
-            let mut thread_builder = thread::Builder::new();
-            if let Some(ref name_prefix) = self.name_prefix {
-                thread_builder = thread_builder.name(format!("{}{}", name_prefix, counter));
-            }
-            if self.stack_size > 0 {
-                thread_builder = thread_builder.stack_size(self.stack_size);
-            }
-            thread_builder.spawn(move || state.work(counter, after_start, before_stop))?;
+            self.thread_builder(counter)
+                .spawn(move || state.work(counter, after_start, before_stop))?;
         }
         Ok(pool)
     }
+
+    /// Create a [`LocalPoolGroup`](LocalPoolGroup) with the given
+    /// configuration.
+    ///
+    /// Instead of sharing the tasks between all worker threads, each worker
+    /// thread runs its own [`LocalPool`](crate::LocalPool), so tasks do not
+    /// need to be `Send` and stay on the worker they were spawned on.
+    pub fn create_local_group(&mut self) -> Result<LocalPoolGroup, io::Error> {
+        LocalPoolGroup::start(
+            self.pool_size,
+            |idx| self.thread_builder(idx),
+            self.after_start.clone(),
+            self.before_stop.clone(),
+            self.on_panic(),
+        )
+    }
+
+    fn on_panic(&self) -> OnPanic {
+        OnPanic { handler: self.panic_handler.clone(), abort: self.abort_on_panic }
+    }
+
+    fn thread_builder(&self, idx: usize) -> thread::Builder {
+        let mut thread_builder = thread::Builder::new();
+        if let Some(ref name_prefix) = self.name_prefix {
+            thread_builder = thread_builder.name(format!("{}{}", name_prefix, idx));
+        }
+        if self.stack_size > 0 {
+            thread_builder = thread_builder.stack_size(self.stack_size);
+        }
+        thread_builder
+    }
 }
 
 impl Default for ThreadPoolBuilder {
@@ -296,10 +449,31 @@ impl Default for ThreadPoolBuilder {
 /// A task responsible for polling a future to completion.
 struct Task {
     future: FutureObj<'static, ()>,
+    info: TaskInfo,
+    named: Option<NamedTask>,
     exec: ThreadPool,
     wake_handle: Arc<WakeHandle>,
 }
 
+/// Lists a named task in the `Debug` output of its pool until it is dropped.
+struct NamedTask {
+    state: Arc<PoolState>,
+    id: TaskId,
+}
+
+impl NamedTask {
+    fn new(state: &Arc<PoolState>, info: &TaskInfo) -> Self {
+        state.named_tasks.lock().unwrap().insert(info.id(), info.clone());
+        Self { state: state.clone(), id: info.id() }
+    }
+}
+
+impl Drop for NamedTask {
+    fn drop(&mut self) {
+        self.state.named_tasks.lock().unwrap().remove(&self.id);
+    }
+}
+
 struct WakeHandle {
     mutex: UnparkMutex<Task>,
     exec: ThreadPool,
@@ -309,7 +483,7 @@ impl Task {
     /// Actually run the task (invoking `poll` on the future) on the current
     /// thread.
     fn run(self) {
-        let Self { mut future, wake_handle, mut exec } = self;
+        let Self { mut future, mut info, mut named, wake_handle, mut exec } = self;
         let waker = waker_ref(&wake_handle);
         let mut cx = Context::from_waker(&waker);
 
@@ -319,17 +493,29 @@ impl Task {
             wake_handle.mutex.start_poll();
 
             loop {
-                let res = future.poll_unpin(&mut cx);
+                let res = panic::catch_unwind(AssertUnwindSafe(|| {
+                    set_current(&info, || future.poll_unpin(&mut cx))
+                }));
                 match res {
-                    Poll::Pending => {}
-                    Poll::Ready(()) => return wake_handle.mutex.complete(),
+                    Ok(Poll::Pending) => {}
+                    Ok(Poll::Ready(())) => return wake_handle.mutex.complete(),
+                    Err(payload) => {
+                        wake_handle.mutex.complete();
+                        // The future may panic again while being dropped.
+                        let _ = panic::catch_unwind(AssertUnwindSafe(|| drop(future)));
+                        return set_current(&info, || {
+                            exec.state.on_panic.task_panicked(payload, &info)
+                        });
+                    }
                 }
-                let task = Self { future, wake_handle: wake_handle.clone(), exec };
+                let task = Self { future, info, named, wake_handle: wake_handle.clone(), exec };
                 match wake_handle.mutex.wait(task) {
                     Ok(()) => return, // we've waited
                     Err(task) => {
                         // someone's notified us
                         future = task.future;
+                        info = task.info;
+                        named = task.named;
                         exec = task.exec;
                     }
                 }
@@ -340,7 +526,7 @@ impl Task {
 
 impl fmt::Debug for Task {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("Task").field("contents", &"...").finish()
+        f.debug_struct("Task").field("info", &self.info).field("contents", &"...").finish()
     }
 }
 
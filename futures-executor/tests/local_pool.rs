@@ -1,7 +1,7 @@
 use futures::channel::oneshot;
-use futures::executor::LocalPool;
-use futures::future::{self, lazy, poll_fn, Future};
-use futures::task::{Context, LocalSpawn, Poll, Spawn, Waker};
+use futures::executor::{LocalPool, LocalSpawner};
+use futures::future::{self, lazy, poll_fn, Future, FutureExt};
+use futures::task::{self, Context, LocalSpawn, LocalSpawnExt, Poll, Spawn, SpawnOptions, Waker};
 use std::cell::{Cell, RefCell};
 use std::pin::Pin;
 use std::rc::Rc;
@@ -432,3 +432,399 @@ fn park_unpark_independence() {
 
     futures::executor::block_on(future)
 }
+
+#[test]
+fn remote_spawner_wakes_parked_run() {
+    let mut pool = LocalPool::new();
+    let remote = pool.remote_spawner();
+    let cnt = Rc::new(Cell::new(0));
+    let (tx, rx) = oneshot::channel::<()>();
+
+    let handle = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        remote.spawn_obj(Box::pin(async move { tx.send(()).unwrap() }).into()).unwrap();
+    });
+
+    // `run_until` parks until the remotely spawned task completes `rx`.
+    pool.run_until(rx).unwrap();
+    handle.join().unwrap();
+
+    let cnt2 = cnt.clone();
+    pool.spawner()
+        .spawn_local_obj(Box::pin(lazy(move |_| cnt2.set(cnt2.get() + 1))).into())
+        .unwrap();
+    pool.run();
+    assert_eq!(cnt.get(), 1);
+}
+
+#[test]
+fn remote_spawner_constructs_local_futures_on_pool_thread() {
+    let mut pool = LocalPool::new();
+    let remote = pool.remote_spawner();
+    let pool_thread = thread::current().id();
+    let ran = Arc::new(AtomicBool::new(false));
+    let ran2 = ran.clone();
+
+    thread::spawn(move || {
+        remote
+            .spawn_local_fn(move || {
+                assert_eq!(thread::current().id(), pool_thread);
+                let rc = Rc::new(ran2);
+                async move { rc.store(true, Ordering::SeqCst) }
+            })
+            .unwrap();
+    })
+    .join()
+    .unwrap();
+
+    assert!(!ran.load(Ordering::SeqCst));
+    pool.run_until_stalled();
+    assert!(ran.load(Ordering::SeqCst));
+}
+
+#[test]
+fn remote_spawner_errors_after_pool_dropped() {
+    let pool = LocalPool::new();
+    let remote = pool.remote_spawner();
+    assert!(remote.status().is_ok());
+    drop(pool);
+    assert!(remote.status().is_err());
+    assert!(remote.spawn_obj(Box::pin(async {}).into()).is_err());
+}
+
+#[test]
+fn try_run_reports_stalled_tasks() {
+    let mut pool = LocalPool::new();
+    let spawn = pool.spawner();
+    let (tx, rx) = oneshot::channel::<()>();
+
+    spawn.spawn_local_with(SpawnOptions::new().name("a"), pending()).unwrap();
+    spawn
+        .spawn_local_with(SpawnOptions::new().name("b"), async move {
+            rx.await.unwrap();
+        })
+        .unwrap();
+    spawn.spawn_local_obj(Box::pin(pending()).into()).unwrap();
+
+    // task "b" has registered its waker with `tx`, so the pool cannot be stalled yet
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        tx.send(()).unwrap();
+    });
+
+    let stalled = pool.try_run().unwrap_err();
+    let names: Vec<_> = stalled.tasks().iter().map(|task| task.name()).collect();
+    assert_eq!(names, [Some("a"), None]);
+    assert!(stalled.to_string().contains("\"a\""));
+}
+
+#[test]
+fn current_task_is_set_while_polling() {
+    let mut pool = LocalPool::new();
+    let spawn = pool.spawner();
+    let seen = Rc::new(RefCell::new(Vec::new()));
+
+    let s = seen.clone();
+    spawn
+        .spawn_local_with(SpawnOptions::new().name("named"), async move {
+            s.borrow_mut().push(task::current().unwrap());
+        })
+        .unwrap();
+    let s = seen.clone();
+    spawn
+        .spawn_local(async move {
+            s.borrow_mut().push(task::current().unwrap());
+        })
+        .unwrap();
+
+    pool.run_until(async {
+        assert!(task::current().is_none());
+    });
+    pool.run();
+    assert!(task::current().is_none());
+
+    let seen = seen.borrow();
+    assert_eq!(seen[0].name(), Some("named"));
+    assert_eq!(seen[1].name(), None);
+    assert_ne!(seen[0].id(), seen[1].id());
+}
+
+#[test]
+fn try_run_until_stalled_main_future() {
+    let mut pool = LocalPool::new();
+    assert!(pool.try_run_until(pending()).is_err());
+
+    let (tx, rx) = oneshot::channel();
+    pool.spawner().spawn_local_obj(Box::pin(pending()).into()).unwrap();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        tx.send(1).unwrap();
+    });
+    assert_eq!(pool.try_run_until(rx), Ok(Ok(1)));
+}
+
+#[test]
+fn no_stall_while_remote_spawner_alive() {
+    let mut pool = LocalPool::new();
+    let remote = pool.remote_spawner();
+    pool.spawner().spawn_local_obj(Box::pin(pending()).into()).unwrap();
+
+    let (tx, rx) = oneshot::channel();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        remote.spawn_obj(Box::pin(async move { tx.send(()).unwrap() }).into()).unwrap();
+    });
+    pool.try_run_until(rx).unwrap().unwrap();
+}
+
+#[test]
+fn try_block_on_reports_stall() {
+    let stalled = futures::executor::try_block_on(pending()).unwrap_err();
+    assert!(stalled.to_string().contains("executor stalled"));
+}
+
+#[test]
+fn try_block_on_self_wake_is_not_stalled() {
+    let mut polled = false;
+    let result = futures::executor::try_block_on(future::poll_fn(|cx| {
+        if polled {
+            return Poll::Ready(());
+        }
+        polled = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }));
+    assert_eq!(result, Ok(()));
+}
+
+#[test]
+fn try_run_reports_spawn_locations() {
+    let mut pool = LocalPool::new();
+    let line = line!() + 1;
+    pool.spawner().spawn_local(pending()).unwrap();
+
+    let stalled = pool.try_run().unwrap_err();
+    let location = stalled.tasks()[0].location().unwrap();
+    assert_eq!((location.file(), location.line()), (file!(), line));
+    assert!(stalled.to_string().contains(&format!("spawned at {}", location)));
+}
+
+#[test]
+fn run_panics_with_stalled_tasks() {
+    let mut pool = LocalPool::new();
+    let line = line!() + 1;
+    pool.spawner().spawn_local(pending()).unwrap();
+
+    let payload =
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| pool.run())).unwrap_err();
+    let message = payload.downcast::<String>().unwrap();
+    assert!(message.starts_with("executor stalled"));
+    assert!(message.contains(&format!("spawned at {}:{}", file!(), line)));
+}
+
+#[test]
+#[should_panic(expected = "executor stalled")]
+fn run_until_panics_when_stalled() {
+    LocalPool::new().run_until(pending());
+}
+
+#[test]
+#[should_panic(expected = "executor stalled")]
+fn block_on_panics_when_stalled() {
+    futures::executor::block_on(pending());
+}
+
+#[test]
+fn try_run_detects_waker_dropped_on_other_thread() {
+    let mut pool = LocalPool::new();
+    let (tx, rx) = std::sync::mpsc::channel::<Waker>();
+
+    // The waker is alive when the pool is first checked for a stall, and
+    // dropped without being woken while the pool waits.
+    let mut tx = Some(tx);
+    pool.spawner()
+        .spawn_local(poll_fn(move |cx| {
+            if let Some(tx) = tx.take() {
+                tx.send(cx.waker().clone()).unwrap();
+            }
+            Poll::<()>::Pending
+        }))
+        .unwrap();
+    thread::spawn(move || {
+        let waker = rx.recv().unwrap();
+        thread::sleep(Duration::from_millis(10));
+        drop(waker);
+    });
+
+    assert_eq!(pool.try_run().unwrap_err().tasks().len(), 1);
+}
+
+#[test]
+fn higher_priority_tasks_run_first() {
+    use futures::executor::Priority;
+
+    let mut pool = LocalPool::new();
+    let spawn = pool.spawner();
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let (tx, rx) = oneshot::channel::<()>();
+
+    // Both tasks wait for the same event; once it happens, the high
+    // priority task must be polled first although it was spawned last.
+    let rx = rx.shared();
+    for &(priority, name) in &[(Priority::Low, "low"), (Priority::High, "high")] {
+        let log = log.clone();
+        let rx = rx.clone();
+        spawn
+            .spawn_local_with_priority(priority, async move {
+                rx.await.unwrap();
+                log.borrow_mut().push(name);
+            })
+            .unwrap();
+    }
+
+    pool.run_until_stalled();
+    assert!(log.borrow().is_empty());
+    tx.send(()).unwrap();
+    pool.run_until_stalled();
+    assert_eq!(*log.borrow(), ["high", "low"]);
+}
+
+#[test]
+fn wakeups_from_other_threads_are_not_lost() {
+    use futures::channel::mpsc;
+    use futures::executor::Priority;
+    use futures::stream::StreamExt;
+
+    for _ in 0..200 {
+        let mut pool = LocalPool::new();
+        let (tx, mut rx) = mpsc::channel::<usize>(1);
+        let sum = Rc::new(Cell::new(0));
+
+        let s = sum.clone();
+        pool.spawner()
+            .spawn_local_with_priority(Priority::Low, async move {
+                while let Some(i) = rx.next().await {
+                    s.set(s.get() + i);
+                }
+            })
+            .unwrap();
+        let sender = thread::spawn(move || {
+            futures::executor::block_on(async move {
+                let mut tx = tx;
+                for i in 0..100 {
+                    futures::SinkExt::send(&mut tx, i).await.unwrap();
+                }
+            })
+        });
+
+        pool.run();
+        sender.join().unwrap();
+        assert_eq!(sum.get(), (0..100).sum());
+    }
+}
+
+#[test]
+fn low_priority_is_not_starved() {
+    use futures::executor::Priority;
+
+    let mut pool = LocalPool::new();
+    let spawn = pool.spawner();
+    let low_done = Rc::new(Cell::new(false));
+    let high_runs = Rc::new(Cell::new(0));
+
+    {
+        let low_done = low_done.clone();
+        spawn.spawn_local_with_priority(Priority::Low, async move { low_done.set(true) }).unwrap();
+    }
+
+    // A high priority task that keeps spawning more high priority work.
+    fn spawn_high(spawn: LocalSpawner, runs: Rc<Cell<usize>>, low_done: Rc<Cell<bool>>) {
+        let spawn2 = spawn.clone();
+        spawn
+            .spawn_local_with_priority(Priority::High, async move {
+                runs.set(runs.get() + 1);
+                if !low_done.get() && runs.get() < 1000 {
+                    spawn_high(spawn2, runs, low_done);
+                }
+            })
+            .unwrap();
+    }
+    spawn_high(spawn, high_runs.clone(), low_done.clone());
+
+    pool.run();
+    assert!(low_done.get());
+    assert!(high_runs.get() < 1000);
+}
+
+#[test]
+fn run_until_timeout_returns_unfinished_future() {
+    let mut pool = LocalPool::new();
+    let (tx, rx) = oneshot::channel();
+    let cnt = Rc::new(Cell::new(0));
+    let cnt2 = cnt.clone();
+    pool.spawner()
+        .spawn_local_obj(Box::pin(lazy(move |_| cnt2.set(cnt2.get() + 1))).into())
+        .unwrap();
+
+    let start = std::time::Instant::now();
+    let rx = pool.run_until_timeout(rx, Duration::from_millis(20)).unwrap_err();
+    assert!(start.elapsed() >= Duration::from_millis(20));
+    // the pool made progress while waiting
+    assert_eq!(cnt.get(), 1);
+
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        tx.send(3).unwrap();
+    });
+    assert_eq!(pool.run_until_timeout(rx, Duration::from_secs(10)).unwrap(), Ok(3));
+}
+
+#[test]
+fn block_on_until_deadline() {
+    use futures::executor::{block_on_timeout, block_on_until};
+
+    let (tx, rx) = oneshot::channel();
+    let deadline = std::time::Instant::now() + Duration::from_millis(20);
+    let rx = block_on_until(rx, deadline).unwrap_err();
+    assert!(std::time::Instant::now() >= deadline);
+
+    tx.send(()).unwrap();
+    assert_eq!(block_on_timeout(rx, Duration::from_millis(0)).unwrap(), Ok(()));
+}
+
+#[test]
+fn blocking_stream_next_timeout() {
+    use futures::channel::mpsc;
+    use futures::executor::block_on_stream;
+
+    let (mut tx, rx) = mpsc::unbounded();
+    let mut iter = block_on_stream(rx);
+    assert!(iter.next_timeout(Duration::from_millis(10)).is_err());
+
+    tx.unbounded_send(1).unwrap();
+    assert_eq!(iter.next_timeout(Duration::from_millis(10)), Ok(Some(1)));
+    tx.disconnect();
+    assert_eq!(iter.next_timeout(Duration::from_millis(10)), Ok(None));
+}
+
+#[test]
+fn deadline_applies_to_futures_that_keep_waking_themselves() {
+    use futures::executor::{block_on_stream, block_on_timeout};
+    use futures::stream;
+
+    let busy = || {
+        poll_fn(|cx| {
+            cx.waker().wake_by_ref();
+            Poll::<()>::Pending
+        })
+    };
+
+    assert!(block_on_timeout(busy(), Duration::from_millis(10)).is_err());
+    assert!(LocalPool::new().run_until_timeout(busy(), Duration::from_millis(10)).is_err());
+
+    let mut iter = block_on_stream(stream::poll_fn(|cx| {
+        cx.waker().wake_by_ref();
+        Poll::<Option<()>>::Pending
+    }));
+    assert!(iter.next_timeout(Duration::from_millis(10)).is_err());
+}
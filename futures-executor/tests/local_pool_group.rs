@@ -0,0 +1,119 @@
+#![cfg(feature = "thread-pool")]
+
+use futures::executor::{block_on, LocalPoolGroup, ThreadPoolBuilder};
+use futures::future;
+use futures::task::SpawnExt;
+use std::rc::Rc;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+#[test]
+fn spawn_on_runs_on_that_worker() {
+    let group =
+        ThreadPoolBuilder::new().pool_size(3).name_prefix("core-").create_local_group().unwrap();
+    assert_eq!(group.size(), 3);
+
+    let (tx, rx) = mpsc::channel();
+    for idx in 0..3 {
+        let tx = tx.clone();
+        group
+            .spawn_on(idx, move || {
+                let name = Rc::new(thread::current().name().unwrap().to_string());
+                async move { tx.send((idx, (*name).clone())).unwrap() }
+            })
+            .unwrap();
+    }
+    drop(tx);
+
+    let mut got: Vec<_> = rx.iter().collect();
+    got.sort();
+    assert_eq!(
+        got,
+        [(0, "core-0".to_string()), (1, "core-1".to_string()), (2, "core-2".to_string())]
+    );
+    group.shutdown();
+}
+
+#[test]
+fn spawn_local_anywhere_round_robin() {
+    let group = ThreadPoolBuilder::new().pool_size(2).create_local_group().unwrap();
+    let threads = Arc::new(Mutex::new(Vec::new()));
+
+    for _ in 0..4 {
+        let threads = threads.clone();
+        let (tx, rx) = futures::channel::oneshot::channel();
+        group
+            .spawn_local_anywhere(move || async move {
+                threads.lock().unwrap().push(thread::current().id());
+                tx.send(()).unwrap();
+            })
+            .unwrap();
+        block_on(rx).unwrap();
+    }
+
+    let threads = threads.lock().unwrap();
+    assert_eq!(threads[0], threads[2]);
+    assert_eq!(threads[1], threads[3]);
+    assert_ne!(threads[0], threads[1]);
+}
+
+#[test]
+fn spawn_send_future() {
+    let group = LocalPoolGroup::new().unwrap();
+    let (tx, rx) = futures::channel::oneshot::channel();
+    group.spawn(async move { tx.send(7).unwrap() }).unwrap();
+    assert_eq!(block_on(rx).unwrap(), 7);
+}
+
+#[test]
+fn shutdown_stops_workers() {
+    let (tx, rx) = mpsc::channel();
+    let group = ThreadPoolBuilder::new()
+        .pool_size(2)
+        .before_stop(move |idx| tx.send(idx).unwrap())
+        .create_local_group()
+        .unwrap();
+
+    group.spawn_on(1, || future::pending()).unwrap();
+    group.shutdown();
+
+    let mut stopped: Vec<_> = rx.try_iter().collect();
+    stopped.sort();
+    assert_eq!(stopped, [0, 1]);
+}
+
+#[test]
+fn panicking_task_does_not_kill_worker() {
+    let (tx, rx) = mpsc::channel();
+    let group = ThreadPoolBuilder::new()
+        .pool_size(1)
+        .panic_handler(move |payload, task| {
+            tx.send((*payload.downcast::<&str>().unwrap(), task.location())).unwrap();
+        })
+        .create_local_group()
+        .unwrap();
+
+    group.spawn_on(0, || async { panic!("in task") }).unwrap();
+    let (msg, location) = rx.recv().unwrap();
+    assert_eq!(msg, "in task");
+    if let Some(location) = location {
+        assert_eq!(location.file(), file!());
+    }
+
+    group.spawn_on(0, || -> future::Ready<()> { panic!("in constructor") }).unwrap();
+    assert_eq!(rx.recv().unwrap().0, "in constructor");
+
+    // the only worker thread is still alive
+    let (done_tx, done_rx) = futures::channel::oneshot::channel();
+    group.spawn_on(0, move || async move { done_tx.send(()).unwrap() }).unwrap();
+    block_on(done_rx).unwrap();
+    group.shutdown();
+}
+
+#[test]
+#[should_panic(expected = "index out of bounds")]
+fn spawn_on_missing_worker_panics() {
+    let group = ThreadPoolBuilder::new().pool_size(1).create_local_group().unwrap();
+    let _ = group.spawn_on(1, || future::ready(()));
+}
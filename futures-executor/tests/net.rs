@@ -0,0 +1,123 @@
+#![cfg(all(feature = "net", target_os = "linux"))]
+
+use futures::executor::net::{self, Async};
+use futures::executor::{block_on, LocalPool};
+use futures::io::{AsyncReadExt, AsyncWriteExt};
+use futures::task::LocalSpawnExt;
+use std::io;
+use std::net::{TcpListener, TcpStream};
+use std::os::unix::net::UnixStream;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn tcp_echo() {
+    block_on(async {
+        let listener = Async::<TcpListener>::bind(([127, 0, 0, 1], 0).into()).unwrap();
+        let addr = listener.get_ref().local_addr().unwrap();
+
+        let server = thread::spawn(move || {
+            block_on(async {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0; 1024];
+                loop {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    stream.write_all(&buf[..n]).await.unwrap();
+                }
+            })
+        });
+
+        let stream = Async::<TcpStream>::connect(addr).await.unwrap();
+        // larger than the socket buffers, so that both sides have to wait
+        let data: Vec<u8> = (0..1 << 22).map(|i| i as u8).collect();
+        let (mut reader, mut writer) = (&stream, &stream);
+        let (written, received) = futures::join!(
+            async {
+                writer.write_all(&data).await.unwrap();
+                stream.get_ref().shutdown(std::net::Shutdown::Write)
+            },
+            async {
+                let mut received = Vec::new();
+                reader.read_to_end(&mut received).await.unwrap();
+                received
+            },
+        );
+        written.unwrap();
+        assert!(received == data);
+        server.join().unwrap();
+    });
+}
+
+#[test]
+fn connect_refused() {
+    // find a port that nothing listens on
+    let addr = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+    let err = block_on(Async::<TcpStream>::connect(addr)).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::ConnectionRefused);
+}
+
+#[test]
+fn unix_stream_pair() {
+    let (mut a, mut b) = Async::<UnixStream>::pair().unwrap();
+    let mut pool = LocalPool::new();
+    pool.spawner()
+        .spawn_local(async move {
+            let mut buf = [0; 5];
+            a.read_exact(&mut buf).await.unwrap();
+            assert_eq!(&buf, b"hello");
+            a.write_all(b"world").await.unwrap();
+        })
+        .unwrap();
+    let reply = pool.run_until(async move {
+        b.write_all(b"hello").await.unwrap();
+        let mut buf = [0; 5];
+        b.read_exact(&mut buf).await.unwrap();
+        buf
+    });
+    assert_eq!(&reply, b"world");
+}
+
+#[test]
+fn pipe_across_threads() {
+    let (mut reader, mut writer) = net::pipe().unwrap();
+    let writer = thread::spawn(move || {
+        block_on(async {
+            for i in 0..10u8 {
+                writer.write_all(&[i; 100]).await.unwrap();
+                thread::sleep(Duration::from_millis(1));
+            }
+        })
+    });
+    let mut received = Vec::new();
+    block_on(reader.read_to_end(&mut received)).unwrap();
+    writer.join().unwrap();
+    assert_eq!(received.len(), 1000);
+    assert!(received.chunks(100).enumerate().all(|(i, c)| c.iter().all(|&b| b as usize == i)));
+}
+
+#[test]
+fn wakeup_while_waiting_for_io() {
+    // Make sure the reactor is running, so that `block_on` waits for I/O
+    // events instead of parking.
+    let (_a, _b) = Async::<UnixStream>::pair().unwrap();
+    let (tx, rx) = futures::channel::oneshot::channel();
+    let sender = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        tx.send(1).unwrap();
+    });
+    assert_eq!(block_on(rx), Ok(1));
+    sender.join().unwrap();
+}
+
+#[test]
+fn into_inner() {
+    let (a, b) = Async::<UnixStream>::pair().unwrap();
+    let a = a.into_inner().unwrap();
+    drop(b);
+    // the descriptor stays nonblocking, but is no longer registered
+    let mut buf = [0; 1];
+    assert_eq!(io::Read::read(&mut &a, &mut buf).unwrap(), 0);
+}
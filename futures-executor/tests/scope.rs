@@ -0,0 +1,105 @@
+use futures::channel::oneshot;
+use futures::executor::{self, LocalPool};
+use futures::future;
+use futures::task::LocalSpawnExt;
+use std::cell::{Cell, RefCell};
+use std::panic::{self, AssertUnwindSafe};
+
+#[test]
+fn borrows_from_stack() {
+    let data = vec![1, 2, 3];
+    let sum = Cell::new(0);
+
+    executor::scope(|s| {
+        for x in &data {
+            let sum = &sum;
+            s.spawn(async move { sum.set(sum.get() + x) });
+        }
+    });
+
+    assert_eq!(sum.get(), 6);
+}
+
+#[test]
+fn returns_closure_result() {
+    let mut x = 0;
+    let r = executor::scope(|s| {
+        s.spawn(async {
+            x += 1;
+        });
+        "done"
+    });
+    assert_eq!(r, "done");
+    assert_eq!(x, 1);
+}
+
+#[test]
+fn waits_for_nested_and_pending_tasks() {
+    let log = RefCell::new(Vec::new());
+    let (tx, rx) = oneshot::channel();
+
+    executor::scope(|s| {
+        let log = &log;
+        s.spawn(async move {
+            rx.await.unwrap();
+            log.borrow_mut().push("received");
+        });
+        s.spawn(async move {
+            log.borrow_mut().push("spawning");
+            s.spawn(async move {
+                log.borrow_mut().push("nested");
+                tx.send(()).unwrap();
+            });
+        });
+    });
+
+    assert_eq!(*log.borrow(), ["spawning", "nested", "received"]);
+}
+
+#[test]
+fn local_pool_scope_runs_pool_tasks() {
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    let (tx, rx) = oneshot::channel();
+    let mut got = None;
+
+    spawner.spawn_local(async move { tx.send(5).unwrap() }).unwrap();
+    spawner.spawn_local(future::pending()).unwrap();
+
+    pool.scope(|s| {
+        s.spawn(async {
+            got = Some(rx.await.unwrap());
+        });
+    });
+
+    assert_eq!(got, Some(5));
+    // the pending task is still in the pool
+    assert!(!pool.try_run_one());
+}
+
+#[test]
+fn panic_propagates_and_cancels() {
+    struct SetOnDrop<'a>(&'a Cell<bool>);
+
+    impl Drop for SetOnDrop<'_> {
+        fn drop(&mut self) {
+            self.0.set(true);
+        }
+    }
+
+    let dropped = Cell::new(false);
+
+    let res = panic::catch_unwind(AssertUnwindSafe(|| {
+        executor::scope(|s| {
+            s.spawn(async {
+                let _guard = SetOnDrop(&dropped);
+                future::pending::<()>().await;
+            });
+            s.spawn(async { panic!("boom") });
+        })
+    }));
+
+    let payload = res.unwrap_err();
+    assert_eq!(payload.downcast_ref::<&str>(), Some(&"boom"));
+    assert!(dropped.get());
+}
@@ -0,0 +1,107 @@
+use futures::channel::oneshot;
+use futures::executor::{block_on, task_local, LocalPool};
+use futures::future::{self, FutureExt};
+use futures::task::LocalSpawnExt;
+
+task_local! {
+    static NUM: u32;
+
+    static NAME: String;
+}
+
+#[test]
+fn not_set_outside_scope() {
+    assert!(NUM.try_with(|_| ()).is_err());
+    block_on(async {
+        assert!(NUM.try_with(|_| ()).is_err());
+    });
+}
+
+#[test]
+fn nested_scopes() {
+    block_on(NUM.scope(1, async {
+        assert_eq!(NUM.with(|v| *v), 1);
+        NUM.scope(2, async {
+            assert_eq!(NUM.with(|v| *v), 2);
+            NAME.scope("inner".to_string(), async {
+                assert_eq!(NUM.with(|v| *v), 2);
+                assert_eq!(NAME.with(|v| v.clone()), "inner");
+            })
+            .await;
+        })
+        .await;
+        assert_eq!(NUM.with(|v| *v), 1);
+        assert!(NAME.try_with(|_| ()).is_err());
+    }));
+    assert!(NUM.try_with(|_| ()).is_err());
+}
+
+#[test]
+fn sync_scope() {
+    let v = NUM.sync_scope(5, || NUM.with(|v| *v + 1));
+    assert_eq!(v, 6);
+    assert!(NUM.try_with(|_| ()).is_err());
+}
+
+#[test]
+fn isolated_between_tasks() {
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    let (tx1, rx1) = oneshot::channel::<()>();
+    let (tx2, rx2) = oneshot::channel::<()>();
+
+    spawner
+        .spawn_local(NUM.scope(1, async move {
+            rx1.await.unwrap();
+            assert_eq!(NUM.with(|v| *v), 1);
+        }))
+        .unwrap();
+    spawner
+        .spawn_local(NUM.scope(2, async move {
+            tx1.send(()).unwrap();
+            rx2.await.unwrap();
+            assert_eq!(NUM.with(|v| *v), 2);
+        }))
+        .unwrap();
+    spawner
+        .spawn_local(async move {
+            assert!(NUM.try_with(|_| ()).is_err());
+            tx2.send(()).unwrap();
+        })
+        .unwrap();
+
+    pool.run();
+    assert!(NUM.try_with(|_| ()).is_err());
+}
+
+#[test]
+fn restored_after_panic() {
+    let res = std::panic::catch_unwind(|| {
+        block_on(NUM.scope(1, future::lazy(|_| panic!("boom"))));
+    });
+    assert!(res.is_err());
+    assert!(NUM.try_with(|_| ()).is_err());
+
+    let res = block_on(NUM.scope(1, future::lazy(|_| panic!("boom"))).catch_unwind());
+    assert!(res.is_err());
+    assert!(NUM.try_with(|_| ()).is_err());
+}
+
+#[cfg(feature = "thread-pool")]
+#[test]
+fn follows_task_across_threads() {
+    use futures::executor::ThreadPool;
+    use std::thread;
+
+    let pool = ThreadPool::builder().pool_size(2).create().unwrap();
+    let (tx, rx) = oneshot::channel();
+    let (done_tx, done_rx) = oneshot::channel();
+    pool.spawn_ok(NUM.scope(7, async move {
+        assert_eq!(NUM.with(|v| *v), 7);
+        rx.await.unwrap();
+        assert_eq!(NUM.with(|v| *v), 7);
+        done_tx.send(()).unwrap();
+    }));
+    thread::spawn(move || tx.send(()).unwrap());
+    block_on(done_rx).unwrap();
+}
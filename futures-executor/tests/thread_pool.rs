@@ -0,0 +1,81 @@
+#![cfg(feature = "thread-pool")]
+
+use futures::channel::oneshot;
+use futures::executor::{block_on, ThreadPool};
+use futures::task::{self, SpawnExt, SpawnOptions};
+use std::sync::mpsc;
+
+#[test]
+fn panicking_task_does_not_kill_worker() {
+    let (tx, rx) = mpsc::channel();
+    let pool = ThreadPool::builder()
+        .pool_size(1)
+        .panic_handler(move |payload, task| {
+            tx.send((*payload.downcast::<&str>().unwrap(), task.id())).unwrap();
+        })
+        .create()
+        .unwrap();
+
+    pool.spawn_ok(async { panic!("boom") });
+    let (msg, _id) = rx.recv().unwrap();
+    assert_eq!(msg, "boom");
+
+    // the only worker thread is still alive
+    let (done_tx, done_rx) = oneshot::channel();
+    pool.spawn_ok(async move { done_tx.send(()).unwrap() });
+    block_on(done_rx).unwrap();
+}
+
+#[test]
+fn panic_handler_receives_distinct_ids() {
+    let (tx, rx) = mpsc::channel();
+    let pool = ThreadPool::builder()
+        .pool_size(2)
+        .panic_handler(move |_, task| tx.send(task.id()).unwrap())
+        .create()
+        .unwrap();
+
+    pool.spawn_ok(async { panic!() });
+    pool.spawn_ok(async { panic!() });
+    let a = rx.recv().unwrap();
+    let b = rx.recv().unwrap();
+    assert_ne!(a, b);
+}
+
+#[test]
+fn panic_handler_sees_task_name() {
+    let (tx, rx) = mpsc::channel();
+    let pool = ThreadPool::builder()
+        .pool_size(1)
+        .panic_handler(move |_, task| tx.send((task.clone(), task::current().unwrap())).unwrap())
+        .create()
+        .unwrap();
+
+    pool.spawn_with(SpawnOptions::new().name("doomed"), async { panic!() }).unwrap();
+    let (task, current) = rx.recv().unwrap();
+    assert_eq!(task.name(), Some("doomed"));
+    assert_eq!(current, task);
+}
+
+#[test]
+fn debug_lists_named_tasks() {
+    let pool = ThreadPool::builder().pool_size(1).create().unwrap();
+    let (tx, rx) = oneshot::channel::<()>();
+    let (name_tx, name_rx) = oneshot::channel();
+
+    pool.spawn_with(SpawnOptions::new().name("waiter"), async move {
+        name_tx.send(task::current().unwrap().name().map(str::to_owned)).unwrap();
+        rx.await.unwrap();
+    })
+    .unwrap();
+    assert_eq!(block_on(name_rx).unwrap(), Some("waiter".to_owned()));
+    assert!(format!("{:?}", pool).contains("\"waiter\""));
+
+    // waking "waiter" queues it before the next task on the single worker
+    tx.send(()).unwrap();
+    let (done_tx, done_rx) = oneshot::channel();
+    pool.spawn_ok(async move { done_tx.send(()).unwrap() });
+    block_on(done_rx).unwrap();
+    let debug = format!("{:?}", pool);
+    assert!(!debug.contains("waiter"), "{}", debug);
+}
@@ -0,0 +1 @@
+../track_caller.rs
\ No newline at end of file
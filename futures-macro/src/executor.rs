@@ -3,11 +3,10 @@ use proc_macro2::Span;
 use quote::{quote, quote_spanned, ToTokens};
 
 pub(crate) fn test(args: TokenStream, item: TokenStream) -> TokenStream {
-    if !args.is_empty() {
-        return syn::Error::new_spanned(proc_macro2::TokenStream::from(args), "invalid argument")
-            .to_compile_error()
-            .into();
-    }
+    let seed = match parse_seed(args) {
+        Ok(seed) => seed,
+        Err(e) => return e.to_compile_error().into(),
+    };
 
     let mut input = syn::parse_macro_input!(item as syn::ItemFn);
 
@@ -39,12 +38,15 @@ pub(crate) fn test(args: TokenStream, item: TokenStream) -> TokenStream {
         ::futures_test::__private
     };
     let body = &input.block;
-    input.block.stmts = vec![syn::Stmt::Expr(
-        syn::parse2(quote_spanned! {last_stmt_end_span=>
+    let run = match seed {
+        Some(seed) => quote_spanned! {last_stmt_end_span=>
+            #path::SimExecutor::new(#seed).run_until(async #body)
+        },
+        None => quote_spanned! {last_stmt_end_span=>
             #path::block_on(async #body)
-        })
-        .unwrap(),
-    )];
+        },
+    };
+    input.block.stmts = vec![syn::Stmt::Expr(syn::parse2(run).unwrap())];
 
     let gen = quote! {
         #[::core::prelude::v1::test]
@@ -53,3 +55,19 @@ pub(crate) fn test(args: TokenStream, item: TokenStream) -> TokenStream {
 
     gen.into()
 }
+
+// Parses the optional `seed = <expr>` argument.
+fn parse_seed(args: TokenStream) -> syn::Result<Option<syn::Expr>> {
+    if args.is_empty() {
+        return Ok(None);
+    }
+    let parser = |input: syn::parse::ParseStream<'_>| {
+        let name: syn::Ident = input.parse()?;
+        if name != "seed" {
+            return Err(syn::Error::new_spanned(name, "invalid argument"));
+        }
+        input.parse::<syn::Token![=]>()?;
+        input.parse::<syn::Expr>()
+    };
+    syn::parse::Parser::parse(parser, args).map(Some)
+}
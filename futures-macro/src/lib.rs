@@ -19,6 +19,7 @@ use proc_macro::TokenStream;
 mod executor;
 mod join;
 mod select;
+mod stream;
 
 /// The `join!` macro.
 #[cfg_attr(fn_like_proc_macro, proc_macro)]
@@ -48,6 +49,20 @@ pub fn select_biased_internal(input: TokenStream) -> TokenStream {
     crate::select::select_biased(input)
 }
 
+/// The `stream!` macro.
+#[cfg_attr(fn_like_proc_macro, proc_macro)]
+#[cfg_attr(not(fn_like_proc_macro), proc_macro_hack::proc_macro_hack)]
+pub fn stream_internal(input: TokenStream) -> TokenStream {
+    crate::stream::stream(input)
+}
+
+/// The `try_stream!` macro.
+#[cfg_attr(fn_like_proc_macro, proc_macro)]
+#[cfg_attr(not(fn_like_proc_macro), proc_macro_hack::proc_macro_hack)]
+pub fn try_stream_internal(input: TokenStream) -> TokenStream {
+    crate::stream::try_stream(input)
+}
+
 // TODO: Change this to doc comment once rustdoc bug fixed.
 // The `test` attribute.
 #[proc_macro_attribute]
@@ -0,0 +1,97 @@
+//! The futures-rs `stream!` macro implementation.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::visit_mut::{self, VisitMut};
+use syn::{parse_quote, Block, Expr, Ident, Item, Stmt};
+
+struct Body {
+    stmts: Vec<Stmt>,
+}
+
+impl Parse for Body {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        Ok(Self { stmts: input.call(Block::parse_within)? })
+    }
+}
+
+// Rewrites the `yield` expressions, and the `?` expressions if `try_stream`,
+// of the body of the stream.
+struct Scrub {
+    yielder: Ident,
+    is_try: bool,
+}
+
+impl VisitMut for Scrub {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::Yield(yield_expr) => {
+                let value = match yield_expr.expr.as_mut() {
+                    Some(value) => {
+                        self.visit_expr_mut(value);
+                        quote!(#value)
+                    }
+                    None => quote!(()),
+                };
+                let yielder = &self.yielder;
+                *expr = if self.is_try {
+                    parse_quote!(#yielder.send(__futures_crate::Ok(#value)).await)
+                } else {
+                    parse_quote!(#yielder.send(#value).await)
+                };
+            }
+            Expr::Try(try_expr) if self.is_try => {
+                self.visit_expr_mut(&mut try_expr.expr);
+                let yielder = &self.yielder;
+                let value = &try_expr.expr;
+                *expr = parse_quote! {
+                    match #value {
+                        __futures_crate::Ok(value) => value,
+                        __futures_crate::Err(error) => {
+                            #yielder.send(__futures_crate::Err(error.into())).await;
+                            return;
+                        }
+                    }
+                };
+            }
+            // `yield` and `?` in these belong to another function, or to an
+            // inner `async` block.
+            Expr::Closure(_) | Expr::Async(_) => {}
+            _ => visit_mut::visit_expr_mut(self, expr),
+        }
+    }
+
+    fn visit_item_mut(&mut self, _item: &mut Item) {}
+}
+
+fn expand(input: TokenStream, is_try: bool) -> TokenStream {
+    let mut body = syn::parse_macro_input!(input as Body);
+
+    // should be def_site, but that's unstable
+    let yielder = Ident::new("__yielder", Span::call_site());
+
+    let mut scrub = Scrub { yielder: yielder.clone(), is_try };
+    for stmt in &mut body.stmts {
+        scrub.visit_stmt_mut(stmt);
+    }
+    let stmts = body.stmts;
+    let stmts: TokenStream2 = quote!(#( #stmts )*);
+
+    TokenStream::from(quote! {
+        __futures_crate::async_await::AsyncStream::new(move |mut #yielder| async move {
+            #stmts
+        })
+    })
+}
+
+/// The `stream!` macro.
+pub(crate) fn stream(input: TokenStream) -> TokenStream {
+    expand(input, false)
+}
+
+/// The `try_stream!` macro.
+pub(crate) fn try_stream(input: TokenStream) -> TokenStream {
+    expand(input, true)
+}
@@ -1,8 +1,10 @@
 #![warn(rust_2018_idioms, single_use_lifetimes)]
 
+use autocfg::AutoCfg;
 use std::env;
 
 include!("no_atomic_cas.rs");
+include!("track_caller.rs");
 
 // The rustc-cfg listed below are considered public API, but it is *unstable*
 // and outside of the normal semver guarantees:
@@ -37,6 +39,19 @@ fn main() {
     if NO_ATOMIC_CAS_TARGETS.contains(&&*target) {
         println!("cargo:rustc-cfg=futures_no_atomic_cas");
     }
-
     println!("cargo:rerun-if-changed=no_atomic_cas.rs");
+
+    let cfg = match AutoCfg::new() {
+        Ok(cfg) => cfg,
+        Err(e) => {
+            println!(
+                "cargo:warning={}: unable to determine rustc version: {}",
+                env!("CARGO_PKG_NAME"),
+                e
+            );
+            return;
+        }
+    };
+
+    probe_track_caller(&cfg);
 }
@@ -0,0 +1,116 @@
+use crate::{SpawnOptions, TaskId};
+use std::cell::Cell;
+use std::fmt;
+use std::panic::Location;
+use std::ptr;
+use std::sync::Arc;
+
+/// The identity of a spawned task: its [`TaskId`] and, if it was spawned with
+/// one, its name.
+///
+/// The task that is currently being polled on this thread can be retrieved
+/// with [`current`].
+#[derive(Clone, PartialEq, Eq)]
+pub struct TaskInfo {
+    id: TaskId,
+    name: Option<Arc<str>>,
+    location: Option<&'static Location<'static>>,
+}
+
+impl TaskInfo {
+    /// Creates the identity of a task with the given id and name.
+    pub fn new(id: TaskId, name: Option<&str>) -> Self {
+        Self { id, name: name.map(Arc::from), location: None }
+    }
+
+    /// Creates the identity of a new task spawned with the given options,
+    /// allocating a new [`TaskId`].
+    pub fn from_options(options: SpawnOptions<'_>) -> Self {
+        Self { location: options.location, ..Self::new(TaskId::new(), options.name) }
+    }
+
+    /// Returns the id of the task.
+    pub fn id(&self) -> TaskId {
+        self.id
+    }
+
+    /// Returns the name of the task, if it has one.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_ref().map(|name| &**name)
+    }
+
+    /// Returns where the task was spawned, if it is known.
+    pub fn location(&self) -> Option<&'static Location<'static>> {
+        self.location
+    }
+}
+
+impl fmt::Debug for TaskInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaskInfo")
+            .field("id", &self.id)
+            .field("name", &self.name())
+            .field("location", &self.location)
+            .finish()
+    }
+}
+
+impl fmt::Display for TaskInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name() {
+            Some(name) => write!(f, "{} ({:?})", self.id, name),
+            None => fmt::Display::fmt(&self.id, f),
+        }
+    }
+}
+
+thread_local! {
+    static CURRENT: Cell<*const TaskInfo> = Cell::new(ptr::null());
+}
+
+/// Returns the identity of the task that is currently being polled on this
+/// thread, or `None` if no task is being polled or the executor does not
+/// support task identities.
+///
+/// The executors in `futures::executor` set the current task while polling
+/// spawned tasks (but not the future passed to `block_on` or `run_until`).
+/// As the current task is also set while a task unwinds, `current` can be
+/// used from a panic hook to include the task in panic messages.
+///
+/// ```
+/// use futures_task::{current, set_current, TaskId, TaskInfo};
+///
+/// assert!(current().is_none());
+///
+/// let task = TaskInfo::new(TaskId::new(), Some("worker"));
+/// set_current(&task, || {
+///     assert_eq!(current().unwrap().name(), Some("worker"));
+/// });
+/// ```
+pub fn current() -> Option<TaskInfo> {
+    CURRENT.with(|current| {
+        let task = current.get();
+        // Safety: the pointer is only non-null while `set_current` is
+        // running, which borrows the task for that long.
+        unsafe { task.as_ref() }.cloned()
+    })
+}
+
+/// Marks `task` as the current task on this thread while `f` runs, e.g.
+/// while an executor polls the task's future.
+///
+/// The previously current task, if any, is restored once `f` returns or
+/// panics.
+pub fn set_current<R>(task: &TaskInfo, f: impl FnOnce() -> R) -> R {
+    struct Reset(*const TaskInfo);
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            CURRENT.with(|current| current.set(self.0));
+        }
+    }
+
+    let prev = CURRENT.with(|current| current.replace(task));
+    let _reset = Reset(prev);
+    f()
+}
@@ -16,7 +16,17 @@
 extern crate alloc;
 
 mod spawn;
-pub use crate::spawn::{LocalSpawn, Spawn, SpawnError};
+pub use crate::spawn::{LocalSpawn, Spawn, SpawnError, SpawnOptions};
+
+mod task_id;
+pub use crate::task_id::TaskId;
+
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "std")]
+mod current;
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "std")]
+pub use crate::current::{current, set_current, TaskInfo};
 
 #[cfg(not(futures_no_atomic_cas))]
 #[cfg(feature = "alloc")]
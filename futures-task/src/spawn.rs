@@ -1,5 +1,6 @@
 use crate::{FutureObj, LocalFutureObj};
 use core::fmt;
+use core::panic::Location;
 
 /// The `Spawn` trait allows for pushing futures onto an executor that will
 /// run them to completion.
@@ -14,6 +15,26 @@ pub trait Spawn {
     /// tasks.
     fn spawn_obj(&self, future: FutureObj<'static, ()>) -> Result<(), SpawnError>;
 
+    /// Spawns a future that will be run to completion, configured by the
+    /// given [`SpawnOptions`].
+    ///
+    /// Executors that do not support any of the options may ignore them.
+    /// The default implementation ignores all options and calls
+    /// [`spawn_obj`](Spawn::spawn_obj).
+    ///
+    /// # Errors
+    ///
+    /// See [`spawn_obj`](Spawn::spawn_obj).
+    #[inline]
+    fn spawn_obj_with(
+        &self,
+        future: FutureObj<'static, ()>,
+        options: SpawnOptions<'_>,
+    ) -> Result<(), SpawnError> {
+        let _ = options;
+        self.spawn_obj(future)
+    }
+
     /// Determines whether the executor is able to spawn new tasks.
     ///
     /// This method will return `Ok` when the executor is *likely*
@@ -39,6 +60,26 @@ pub trait LocalSpawn {
     /// tasks.
     fn spawn_local_obj(&self, future: LocalFutureObj<'static, ()>) -> Result<(), SpawnError>;
 
+    /// Spawns a future that will be run to completion, configured by the
+    /// given [`SpawnOptions`].
+    ///
+    /// Executors that do not support any of the options may ignore them.
+    /// The default implementation ignores all options and calls
+    /// [`spawn_local_obj`](LocalSpawn::spawn_local_obj).
+    ///
+    /// # Errors
+    ///
+    /// See [`spawn_local_obj`](LocalSpawn::spawn_local_obj).
+    #[inline]
+    fn spawn_local_obj_with(
+        &self,
+        future: LocalFutureObj<'static, ()>,
+        options: SpawnOptions<'_>,
+    ) -> Result<(), SpawnError> {
+        let _ = options;
+        self.spawn_local_obj(future)
+    }
+
     /// Determines whether the executor is able to spawn new tasks.
     ///
     /// This method will return `Ok` when the executor is *likely*
@@ -51,6 +92,77 @@ pub trait LocalSpawn {
     }
 }
 
+/// Options for spawning a task with [`Spawn::spawn_obj_with`] or
+/// [`LocalSpawn::spawn_local_obj_with`].
+///
+/// New options may be added in the future, so this type can't be constructed
+/// with a struct literal. Use [`SpawnOptions::new`] and the builder methods
+/// instead:
+///
+/// ```
+/// use futures_task::SpawnOptions;
+///
+/// let options = SpawnOptions::new().name("worker");
+/// assert_eq!(options.name, Some("worker"));
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpawnOptions<'a> {
+    /// The name of the task, used to identify it in debug output and panic
+    /// reports. Executors copy the name if they keep it.
+    pub name: Option<&'a str>,
+    /// Where the task was spawned, used to identify it in reports about
+    /// stalled tasks. The spawn methods of the `futures` crate set this to
+    /// their caller (on Rust 1.46 and later).
+    pub location: Option<&'static Location<'static>>,
+    _priv: (),
+}
+
+impl fmt::Debug for SpawnOptions<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpawnOptions")
+            .field("name", &self.name)
+            .field("location", &self.location)
+            .finish()
+    }
+}
+
+impl<'a> SpawnOptions<'a> {
+    /// Creates options with all fields set to their defaults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the name of the task.
+    pub fn name(mut self, name: &'a str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Sets where the task was spawned.
+    pub fn location(mut self, location: &'static Location<'static>) -> Self {
+        self.location = Some(location);
+        self
+    }
+
+    /// Sets where the task was spawned to the caller of this method, unless
+    /// a location is set already.
+    ///
+    /// This is meant for spawn methods marked `#[track_caller]`, for which
+    /// the caller is the code calling the spawn method. Before Rust 1.46,
+    /// the options are returned unchanged.
+    #[cfg_attr(track_caller, track_caller)]
+    pub fn at_caller(self) -> Self {
+        #[cfg(track_caller)]
+        #[allow(clippy::incompatible_msrv)] // only built on Rust 1.46 and later
+        {
+            if self.location.is_none() {
+                return self.location(Location::caller());
+            }
+        }
+        self
+    }
+}
+
 /// An error that occurred during spawning.
 pub struct SpawnError {
     _priv: (),
@@ -88,6 +200,14 @@ impl<Sp: ?Sized + Spawn> Spawn for &Sp {
         Sp::spawn_obj(self, future)
     }
 
+    fn spawn_obj_with(
+        &self,
+        future: FutureObj<'static, ()>,
+        options: SpawnOptions<'_>,
+    ) -> Result<(), SpawnError> {
+        Sp::spawn_obj_with(self, future, options)
+    }
+
     fn status(&self) -> Result<(), SpawnError> {
         Sp::status(self)
     }
@@ -98,6 +218,14 @@ impl<Sp: ?Sized + Spawn> Spawn for &mut Sp {
         Sp::spawn_obj(self, future)
     }
 
+    fn spawn_obj_with(
+        &self,
+        future: FutureObj<'static, ()>,
+        options: SpawnOptions<'_>,
+    ) -> Result<(), SpawnError> {
+        Sp::spawn_obj_with(self, future, options)
+    }
+
     fn status(&self) -> Result<(), SpawnError> {
         Sp::status(self)
     }
@@ -108,6 +236,14 @@ impl<Sp: ?Sized + LocalSpawn> LocalSpawn for &Sp {
         Sp::spawn_local_obj(self, future)
     }
 
+    fn spawn_local_obj_with(
+        &self,
+        future: LocalFutureObj<'static, ()>,
+        options: SpawnOptions<'_>,
+    ) -> Result<(), SpawnError> {
+        Sp::spawn_local_obj_with(self, future, options)
+    }
+
     fn status_local(&self) -> Result<(), SpawnError> {
         Sp::status_local(self)
     }
@@ -118,6 +254,14 @@ impl<Sp: ?Sized + LocalSpawn> LocalSpawn for &mut Sp {
         Sp::spawn_local_obj(self, future)
     }
 
+    fn spawn_local_obj_with(
+        &self,
+        future: LocalFutureObj<'static, ()>,
+        options: SpawnOptions<'_>,
+    ) -> Result<(), SpawnError> {
+        Sp::spawn_local_obj_with(self, future, options)
+    }
+
     fn status_local(&self) -> Result<(), SpawnError> {
         Sp::status_local(self)
     }
@@ -133,6 +277,14 @@ mod if_alloc {
             (**self).spawn_obj(future)
         }
 
+        fn spawn_obj_with(
+            &self,
+            future: FutureObj<'static, ()>,
+            options: SpawnOptions<'_>,
+        ) -> Result<(), SpawnError> {
+            (**self).spawn_obj_with(future, options)
+        }
+
         fn status(&self) -> Result<(), SpawnError> {
             (**self).status()
         }
@@ -143,6 +295,14 @@ mod if_alloc {
             (**self).spawn_local_obj(future)
         }
 
+        fn spawn_local_obj_with(
+            &self,
+            future: LocalFutureObj<'static, ()>,
+            options: SpawnOptions<'_>,
+        ) -> Result<(), SpawnError> {
+            (**self).spawn_local_obj_with(future, options)
+        }
+
         fn status_local(&self) -> Result<(), SpawnError> {
             (**self).status_local()
         }
@@ -153,6 +313,14 @@ mod if_alloc {
             (**self).spawn_obj(future)
         }
 
+        fn spawn_obj_with(
+            &self,
+            future: FutureObj<'static, ()>,
+            options: SpawnOptions<'_>,
+        ) -> Result<(), SpawnError> {
+            (**self).spawn_obj_with(future, options)
+        }
+
         fn status(&self) -> Result<(), SpawnError> {
             (**self).status()
         }
@@ -163,6 +331,14 @@ mod if_alloc {
             (**self).spawn_local_obj(future)
         }
 
+        fn spawn_local_obj_with(
+            &self,
+            future: LocalFutureObj<'static, ()>,
+            options: SpawnOptions<'_>,
+        ) -> Result<(), SpawnError> {
+            (**self).spawn_local_obj_with(future, options)
+        }
+
         fn status_local(&self) -> Result<(), SpawnError> {
             (**self).status_local()
         }
@@ -174,6 +350,14 @@ mod if_alloc {
             (**self).spawn_obj(future)
         }
 
+        fn spawn_obj_with(
+            &self,
+            future: FutureObj<'static, ()>,
+            options: SpawnOptions<'_>,
+        ) -> Result<(), SpawnError> {
+            (**self).spawn_obj_with(future, options)
+        }
+
         fn status(&self) -> Result<(), SpawnError> {
             (**self).status()
         }
@@ -185,6 +369,14 @@ mod if_alloc {
             (**self).spawn_local_obj(future)
         }
 
+        fn spawn_local_obj_with(
+            &self,
+            future: LocalFutureObj<'static, ()>,
+            options: SpawnOptions<'_>,
+        ) -> Result<(), SpawnError> {
+            (**self).spawn_local_obj_with(future, options)
+        }
+
         fn status_local(&self) -> Result<(), SpawnError> {
             (**self).status_local()
         }
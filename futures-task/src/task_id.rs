@@ -0,0 +1,35 @@
+use core::fmt;
+
+/// An opaque identifier of a spawned task.
+///
+/// Executors assign a `TaskId` to each task they spawn, for example to
+/// identify the task in panic reports. Ids allocated with
+/// [`TaskId::new`](TaskId::new) are unique within the process, except on
+/// targets with 32-bit pointers: there, the counter the ids are taken from
+/// wraps around after 2<sup>32</sup> ids, and ids are reused from then on.
+/// (A 64-bit counter isn't available on every target this crate supports.)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TaskId(u64);
+
+impl TaskId {
+    /// Allocates a new, unique task id.
+    #[cfg(not(futures_no_atomic_cas))]
+    #[allow(clippy::new_without_default)] // each call returns a different id
+    pub fn new() -> Self {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+        Self(NEXT_ID.fetch_add(1, Ordering::Relaxed) as u64)
+    }
+
+    /// Returns the id as an integer.
+    pub fn as_u64(self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for TaskId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
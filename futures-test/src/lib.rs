@@ -24,6 +24,7 @@ compile_error!(
 #[doc(hidden)]
 #[cfg(feature = "std")]
 pub mod __private {
+    pub use crate::sim::SimExecutor;
     pub use futures_core::{future, stream, task};
     pub use futures_executor::block_on;
     pub use std::{
@@ -56,8 +57,13 @@ pub mod sink;
 #[cfg(feature = "std")]
 pub mod io;
 
+#[cfg(feature = "std")]
+pub mod sim;
+
 mod assert_unmoved;
 mod interleave_pending;
+#[cfg(feature = "std")]
+mod task_slots;
 mod track_closed;
 
 /// Enables an `async` test function. The generated future will be run to completion with
@@ -82,5 +88,28 @@ mod track_closed;
 ///     })
 /// }
 /// ```
+///
+/// With a `seed` argument, the test is run on a deterministic
+/// [`SimExecutor`](crate::sim::SimExecutor) with the given seed instead:
+///
+/// ```
+/// #[futures_test::test(seed = 42)]
+/// async fn my_test() {
+///     let fut = async { true };
+///     assert!(fut.await);
+/// }
+/// ```
+///
+/// This is equivalent to the following code:
+///
+/// ```
+/// #[test]
+/// fn my_test() {
+///     futures_test::sim::SimExecutor::new(42).run_until(async move {
+///         let fut = async { true };
+///         assert!(fut.await);
+///     })
+/// }
+/// ```
 #[cfg(feature = "std")]
 pub use futures_macro::test_internal as test;
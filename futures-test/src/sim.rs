@@ -0,0 +1,585 @@
+//! Deterministic simulation of concurrent tasks.
+//!
+//! A [`SimExecutor`] runs a set of tasks on the current thread. Whenever
+//! several tasks are ready, the next one to poll is picked by a random number
+//! generator seeded with a fixed seed, so that running a test with many
+//! different seeds explores many different interleavings, while each seed
+//! reproduces exactly the same interleaving every time it is run.
+//!
+//! The executor records the order in which it polled the tasks as a
+//! [`Schedule`], which can be replayed with [`SimExecutor::replay`], e.g. after
+//! removing steps from it to reduce a failing schedule.
+//!
+//! The simulation has its own virtual clock: [`sleep`] waits for a duration of
+//! virtual time, and [`now`] returns the virtual time elapsed since the start
+//! of the simulation. The clock only advances when no task is ready, and then
+//! jumps straight to the next expiring timer, so tests with long timeouts run
+//! instantly and deterministically. [`Timer`] hands this clock to the
+//! combinators that take a [`Sleep`](futures_util::future::Sleep) timer, like
+//! [`retry`](futures_util::future::retry).
+//!
+//! All wakeups must originate from within the simulation: if all tasks are
+//! pending and no timer is scheduled, the simulation is considered
+//! deadlocked and the executor panics.
+//!
+//! If a simulation panics, the seed (or the fact that a schedule was being
+//! replayed) is printed to stderr, so the failure can be reproduced.
+//!
+//! # Examples
+//!
+//! ```
+//! use futures::channel::mpsc;
+//! use futures::{SinkExt, StreamExt};
+//! use futures::task::LocalSpawnExt;
+//! use futures_test::sim::{self, SimExecutor};
+//! use std::time::Duration;
+//!
+//! for seed in 0..32 {
+//!     let mut executor = SimExecutor::new(seed);
+//!     let (tx, rx) = mpsc::channel(1);
+//!
+//!     for i in 0..3 {
+//!         let mut tx = tx.clone();
+//!         executor.spawner().spawn_local(async move {
+//!             sim::sleep(Duration::from_secs(1)).await;
+//!             tx.send(i).await.unwrap();
+//!         }).unwrap();
+//!     }
+//!     drop(tx);
+//!
+//!     let mut received: Vec<i32> = executor.run_until(rx.collect());
+//!     assert_eq!(executor.now(), Duration::from_secs(1));
+//!     received.sort_unstable();
+//!     assert_eq!(received, [0, 1, 2]);
+//! }
+//! ```
+//!
+//! With the [`test`](crate::test) attribute, an `async` test can be run on a
+//! `SimExecutor` with the given seed:
+//!
+//! ```
+//! #[futures_test::test(seed = 42)]
+//! async fn my_test() {
+//!     use futures_test::sim;
+//!     use std::time::Duration;
+//!
+//!     sim::sleep(Duration::from_secs(60)).await;
+//!     assert_eq!(sim::now(), Duration::from_secs(60));
+//! }
+//! ```
+
+use crate::task_slots::TaskSlots;
+use futures_core::future::Future;
+use futures_core::task::{Context, Poll, Waker};
+use futures_task::{waker, ArcWake, FutureObj, LocalFutureObj, LocalSpawn, Spawn, SpawnError};
+use futures_util::pin_mut;
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::num::ParseIntError;
+use std::pin::Pin;
+use std::rc::{Rc, Weak};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The task id of the future passed to [`SimExecutor::run_until`].
+const MAIN_TASK: usize = 0;
+
+/// A single-threaded executor that interleaves its tasks deterministically,
+/// driven by a seeded random number generator.
+///
+/// See the [module documentation](crate::sim) for details.
+pub struct SimExecutor {
+    sim: Rc<Sim>,
+    seed: Option<u64>,
+    chooser: Chooser,
+    schedule: Vec<usize>,
+}
+
+/// A handle to a [`SimExecutor`] that implements
+/// [`Spawn`](futures_task::Spawn) and [`LocalSpawn`](futures_task::LocalSpawn).
+#[derive(Clone)]
+pub struct SimSpawner {
+    sim: Weak<Sim>,
+}
+
+/// The order in which a [`SimExecutor`] polled its tasks.
+///
+/// Tasks are identified by the order in which they were spawned, starting at
+/// `1`; `0` is the future passed to [`run_until`](SimExecutor::run_until).
+///
+/// A schedule can be converted to and parsed from a comma-separated list of
+/// task ids, e.g. to paste a recorded schedule into a test.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Schedule {
+    steps: Vec<usize>,
+}
+
+enum Chooser {
+    Random(Rng),
+    Replay { steps: Vec<usize>, pos: usize },
+}
+
+struct Sim {
+    tasks: TaskSlots,
+    ready: Arc<ReadyQueue>,
+    /// The wakers of pending `Sleep`s, by deadline and timer id.
+    timers: RefCell<BTreeMap<(Duration, u64), Waker>>,
+    next_timer: Cell<u64>,
+    now: Cell<Duration>,
+    /// The instant the virtual time is counted from, for [`Timer::now`].
+    epoch: Instant,
+}
+
+struct ReadyQueue {
+    tasks: Mutex<BTreeSet<usize>>,
+}
+
+struct TaskWaker {
+    id: usize,
+    ready: Arc<ReadyQueue>,
+}
+
+impl ArcWake for TaskWaker {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.ready.tasks.lock().unwrap().insert(arc_self.id);
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Rc<Sim>>> = RefCell::new(None);
+}
+
+impl SimExecutor {
+    /// Creates a simulation that picks the next ready task with a random
+    /// number generator seeded with `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self::with_chooser(Some(seed), Chooser::Random(Rng(seed)))
+    }
+
+    /// Creates a simulation that polls the tasks in the order given by
+    /// `schedule`, e.g. as previously recorded by
+    /// [`schedule`](SimExecutor::schedule).
+    ///
+    /// Once the schedule is exhausted, the ready task with the lowest id is
+    /// polled next.
+    ///
+    /// # Panics
+    ///
+    /// Running the simulation panics if the task to poll next according to
+    /// the schedule is not ready, i.e. if the tasks behave differently than
+    /// when the schedule was recorded.
+    pub fn replay(schedule: Schedule) -> Self {
+        Self::with_chooser(None, Chooser::Replay { steps: schedule.steps, pos: 0 })
+    }
+
+    fn with_chooser(seed: Option<u64>, chooser: Chooser) -> Self {
+        let sim = Sim {
+            // the slot of the main task is never used
+            tasks: TaskSlots::new(MAIN_TASK + 1),
+            ready: Arc::new(ReadyQueue { tasks: Mutex::new(BTreeSet::new()) }),
+            timers: RefCell::new(BTreeMap::new()),
+            next_timer: Cell::new(0),
+            now: Cell::new(Duration::from_secs(0)),
+            epoch: Instant::now(),
+        };
+        Self { sim: Rc::new(sim), seed, chooser, schedule: Vec::new() }
+    }
+
+    /// Returns the seed of the simulation, or `None` if it replays a
+    /// schedule.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Returns the order in which the tasks have been polled so far.
+    pub fn schedule(&self) -> Schedule {
+        Schedule { steps: self.schedule.clone() }
+    }
+
+    /// Returns the virtual time elapsed since the start of the simulation.
+    pub fn now(&self) -> Duration {
+        self.sim.now.get()
+    }
+
+    /// Get a clonable handle to the simulation as a [`Spawn`].
+    pub fn spawner(&self) -> SimSpawner {
+        SimSpawner { sim: Rc::downgrade(&self.sim) }
+    }
+
+    /// Runs all tasks in the simulation to completion.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a task panics, or if the simulation is deadlocked.
+    pub fn run(&mut self) {
+        self.run_inner(None)
+    }
+
+    /// Runs the tasks in the simulation until the given future completes.
+    ///
+    /// The future takes part in the simulation like a spawned task. Tasks
+    /// that have not completed when it completes are kept for subsequent
+    /// calls.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a task or the future panics, or if the simulation is
+    /// deadlocked.
+    pub fn run_until<F: Future>(&mut self, future: F) -> F::Output {
+        pin_mut!(future);
+        let mut output = None;
+        self.sim.ready.tasks.lock().unwrap().insert(MAIN_TASK);
+        self.run_inner(Some(&mut |cx| match future.as_mut().poll(cx) {
+            Poll::Ready(out) => {
+                output = Some(out);
+                true
+            }
+            Poll::Pending => false,
+        }));
+        output.unwrap()
+    }
+
+    // Returns once `main` returns `true` or, if there is no main future, once
+    // all tasks have completed.
+    fn run_inner(&mut self, mut main: Option<&mut dyn FnMut(&mut Context<'_>) -> bool>) {
+        let _enter = enter(&self.sim);
+        loop {
+            let id = match self.next_task(main.is_some()) {
+                Some(id) => id,
+                None if self.sim.advance_time() => continue,
+                None if main.is_none() && self.sim.is_empty() => return,
+                None => {
+                    panic!("simulation deadlocked: all tasks are pending and no timer is scheduled")
+                }
+            };
+            self.schedule.push(id);
+
+            let waker = waker(Arc::new(TaskWaker { id, ready: self.sim.ready.clone() }));
+            let mut cx = Context::from_waker(&waker);
+            if id == MAIN_TASK {
+                if let Some(main) = &mut main {
+                    if main(&mut cx) {
+                        return;
+                    }
+                }
+            } else {
+                self.sim.tasks.poll(id, &mut cx);
+            }
+        }
+    }
+
+    fn next_task(&mut self, has_main: bool) -> Option<usize> {
+        let mut ready = self.sim.ready.tasks.lock().unwrap();
+        let candidates: Vec<usize> = ready
+            .iter()
+            .copied()
+            .filter(|&id| if id == MAIN_TASK { has_main } else { self.sim.tasks.is_pending(id) })
+            .collect();
+        if candidates.is_empty() {
+            ready.clear();
+            return None;
+        }
+
+        let id = match &mut self.chooser {
+            Chooser::Random(rng) => candidates[rng.below(candidates.len())],
+            Chooser::Replay { steps, pos } => match steps.get(*pos) {
+                Some(&id) => {
+                    if !candidates.contains(&id) {
+                        panic!(
+                            "schedule diverged at step {}: task {} is not ready (ready tasks: {:?})",
+                            pos, id, candidates
+                        );
+                    }
+                    *pos += 1;
+                    id
+                }
+                None => candidates[0],
+            },
+        };
+        ready.remove(&id);
+        Some(id)
+    }
+}
+
+impl Drop for SimExecutor {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            match self.seed {
+                Some(seed) => eprintln!(
+                    "simulation panicked with seed {} after {} steps",
+                    seed,
+                    self.schedule.len()
+                ),
+                None => eprintln!(
+                    "simulation panicked while replaying a schedule after {} steps",
+                    self.schedule.len()
+                ),
+            }
+        }
+    }
+}
+
+impl fmt::Debug for SimExecutor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SimExecutor")
+            .field("seed", &self.seed)
+            .field("now", &self.now())
+            .field("steps", &self.schedule.len())
+            .finish()
+    }
+}
+
+impl Sim {
+    fn spawn(&self, future: LocalFutureObj<'static, ()>) {
+        let id = self.tasks.push(future);
+        self.ready.tasks.lock().unwrap().insert(id);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.tasks.pending_count() == 0
+    }
+
+    // Advances the clock to the next deadline and wakes the expired timers.
+    // Returns `false` if no timer is scheduled.
+    fn advance_time(&self) -> bool {
+        let mut timers = self.timers.borrow_mut();
+        let deadline = match timers.keys().next() {
+            Some(&(deadline, _)) => deadline,
+            None => return false,
+        };
+        self.now.set(deadline);
+        while let Some(&key) = timers.keys().next() {
+            if key.0 > deadline {
+                break;
+            }
+            timers.remove(&key).unwrap().wake();
+        }
+        true
+    }
+}
+
+// Makes `sim` the current simulation of this thread until the guard is
+// dropped.
+fn enter(sim: &Rc<Sim>) -> impl Drop {
+    struct Reset(Option<Rc<Sim>>);
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            CURRENT.with(|current| *current.borrow_mut() = self.0.take());
+        }
+    }
+
+    Reset(CURRENT.with(|current| current.borrow_mut().replace(sim.clone())))
+}
+
+fn current() -> Rc<Sim> {
+    CURRENT.with(|current| current.borrow().clone()).expect("not inside a simulation")
+}
+
+/// Returns the virtual time elapsed since the start of the current
+/// simulation.
+///
+/// # Panics
+///
+/// Panics if not called from within a task of a [`SimExecutor`].
+pub fn now() -> Duration {
+    current().now.get()
+}
+
+/// Waits until `duration` of virtual time has elapsed in the current
+/// simulation.
+///
+/// # Panics
+///
+/// Panics if not called from within a task of a [`SimExecutor`].
+pub fn sleep(duration: Duration) -> Sleep {
+    let sim = current();
+    Sleep { deadline: sim.now.get() + duration, sim: Rc::downgrade(&sim), timer: None }
+}
+
+/// Returns a handle to spawn tasks onto the current simulation.
+///
+/// # Panics
+///
+/// Panics if not called from within a task of a [`SimExecutor`].
+pub fn spawner() -> SimSpawner {
+    SimSpawner { sim: Rc::downgrade(&current()) }
+}
+
+/// A [`Sleep`](futures_util::future::Sleep) timer following the virtual
+/// clock of the current simulation.
+///
+/// Its [`now`](futures_util::future::Sleep::now) is a fixed instant plus the
+/// virtual time elapsed since the start of the simulation.
+///
+/// # Panics
+///
+/// Using a `Timer` panics if not done from within a task of a
+/// [`SimExecutor`].
+///
+/// # Examples
+///
+/// ```
+/// use futures::future::{self, Backoff};
+/// use futures_test::sim::{SimExecutor, Timer};
+/// use std::time::Duration;
+///
+/// let mut executor = SimExecutor::new(0);
+/// let policy = Backoff::constant(Duration::from_secs(5)).max_attempts(3);
+/// let result = executor.run_until(future::retry(|| future::err::<(), _>(()), policy, Timer));
+/// assert_eq!(result, Err(()));
+/// assert_eq!(executor.now(), Duration::from_secs(10));
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Timer;
+
+impl futures_util::future::Sleep for Timer {
+    type Future = Sleep;
+
+    fn sleep(&self, duration: Duration) -> Sleep {
+        sleep(duration)
+    }
+
+    fn now(&self) -> Instant {
+        let sim = current();
+        sim.epoch + sim.now.get()
+    }
+}
+
+/// Future for the [`sleep`] function.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Sleep {
+    sim: Weak<Sim>,
+    deadline: Duration,
+    timer: Option<u64>,
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = &mut *self;
+        let sim = match this.sim.upgrade() {
+            Some(sim) => sim,
+            // the simulation is gone, so its clock will never advance
+            None => return Poll::Pending,
+        };
+        if sim.now.get() >= this.deadline {
+            return Poll::Ready(());
+        }
+        let timer = *this.timer.get_or_insert_with(|| {
+            let timer = sim.next_timer.get();
+            sim.next_timer.set(timer + 1);
+            timer
+        });
+        sim.timers.borrow_mut().insert((this.deadline, timer), cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        if let (Some(sim), Some(timer)) = (self.sim.upgrade(), self.timer) {
+            sim.timers.borrow_mut().remove(&(self.deadline, timer));
+        }
+    }
+}
+
+impl fmt::Debug for Sleep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sleep").field("deadline", &self.deadline).finish()
+    }
+}
+
+impl Spawn for SimSpawner {
+    fn spawn_obj(&self, future: FutureObj<'static, ()>) -> Result<(), SpawnError> {
+        self.spawn_local_obj(future.into())
+    }
+
+    fn status(&self) -> Result<(), SpawnError> {
+        self.status_local()
+    }
+}
+
+impl LocalSpawn for SimSpawner {
+    fn spawn_local_obj(&self, future: LocalFutureObj<'static, ()>) -> Result<(), SpawnError> {
+        match self.sim.upgrade() {
+            Some(sim) => {
+                sim.spawn(future);
+                Ok(())
+            }
+            None => Err(SpawnError::shutdown()),
+        }
+    }
+
+    fn status_local(&self) -> Result<(), SpawnError> {
+        if self.sim.upgrade().is_some() {
+            Ok(())
+        } else {
+            Err(SpawnError::shutdown())
+        }
+    }
+}
+
+impl fmt::Debug for SimSpawner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SimSpawner").finish()
+    }
+}
+
+impl Schedule {
+    /// Creates a schedule from a list of task ids.
+    pub fn new(steps: Vec<usize>) -> Self {
+        Self { steps }
+    }
+
+    /// Returns the ids of the tasks in the order in which they were polled.
+    pub fn steps(&self) -> &[usize] {
+        &self.steps
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, id) in self.steps.iter().enumerate() {
+            if i != 0 {
+                f.write_str(",")?;
+            }
+            write!(f, "{}", id)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        let steps = s.split(',').map(|id| id.trim().parse()).collect::<Result<_, _>>()?;
+        Ok(Self { steps })
+    }
+}
+
+/// A small, fast generator (SplitMix64); the quality of its output is more
+/// than sufficient to pick tasks.
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
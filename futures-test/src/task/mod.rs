@@ -34,6 +34,8 @@
 //! - [`PanicSpawner`](crate::task::PanicSpawner) panics if [`spawn`](futures_util::task::SpawnExt::spawn) is
 //!   called.
 //! - [`RecordSpawner`](crate::task::RecordSpawner) records the spawned futures.
+//! - [`TestExecutor`](crate::task::TestExecutor) records the spawned futures
+//!   and runs them step by step.
 //!
 //! For convenience there additionally exist various functions that directly
 //! return waker/spawner references: [`noop_waker_ref`](crate::task::noop_waker_ref),
@@ -56,5 +58,8 @@ pub use self::panic_waker::{panic_waker, panic_waker_ref};
 mod record_spawner;
 pub use self::record_spawner::RecordSpawner;
 
+mod test_executor;
+pub use self::test_executor::{TestExecutor, TestSpawner};
+
 mod wake_counter;
 pub use self::wake_counter::{new_count_waker, AwokenCount};
@@ -14,6 +14,9 @@ use std::cell::{Ref, RefCell};
 /// recorder.spawn(async { }).unwrap();
 /// assert_eq!(recorder.spawned().len(), 1);
 /// ```
+///
+/// To run the recorded futures, convert the `RecordSpawner` into a
+/// [`TestExecutor`](crate::task::TestExecutor).
 #[derive(Debug, Default)]
 pub struct RecordSpawner {
     spawned: RefCell<Vec<FutureObj<'static, ()>>>,
@@ -29,6 +32,10 @@ impl RecordSpawner {
     pub fn spawned(&self) -> Ref<'_, Vec<FutureObj<'static, ()>>> {
         self.spawned.borrow()
     }
+
+    pub(crate) fn into_spawned(self) -> Vec<FutureObj<'static, ()>> {
+        self.spawned.into_inner()
+    }
 }
 
 impl Spawn for RecordSpawner {
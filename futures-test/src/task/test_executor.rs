@@ -0,0 +1,235 @@
+use super::RecordSpawner;
+use crate::task_slots::TaskSlots;
+use futures_core::task::{Context, Poll};
+use futures_task::{FutureObj, LocalFutureObj, LocalSpawn, Spawn, SpawnError};
+use futures_util::task::{self, ArcWake};
+use std::collections::VecDeque;
+use std::fmt;
+use std::mem;
+use std::rc::{Rc, Weak};
+use std::sync::{Arc, Mutex};
+
+/// An executor whose tasks only make progress when the test tells it to.
+///
+/// Like [`RecordSpawner`](crate::task::RecordSpawner), `TestExecutor`
+/// collects the futures spawned on it, but it can also run them, one
+/// [`poll_one`](TestExecutor::poll_one) at a time or
+/// [until none of them is ready](TestExecutor::run_until_stalled). Tasks are
+/// identified by the order in which they were spawned, starting at `0`, and
+/// are polled in the order in which they became ready.
+///
+/// Every time a task is woken, its id is added to the
+/// [`wake_log`](TestExecutor::wake_log), so that tests can assert exactly
+/// which tasks became ready after each step.
+///
+/// Tasks can spawn further tasks through a [`TestSpawner`] obtained from
+/// [`spawner`](TestExecutor::spawner).
+///
+/// # Examples
+///
+/// ```
+/// use futures::channel::oneshot;
+/// use futures::task::LocalSpawnExt;
+/// use futures_test::task::TestExecutor;
+///
+/// let executor = TestExecutor::new();
+/// let (tx, rx) = oneshot::channel();
+///
+/// executor.spawn_local(async { rx.await.unwrap() }).unwrap();
+/// assert_eq!(executor.ready_task_count(), 1);
+/// assert!(executor.run_until_stalled().is_pending());
+/// assert!(executor.wake_log().is_empty());
+///
+/// tx.send(()).unwrap();
+/// assert_eq!(executor.wake_log(), [0]);
+/// assert_eq!(executor.poll_one(), Some(0));
+/// assert!(executor.run_until_stalled().is_ready());
+/// ```
+pub struct TestExecutor {
+    inner: Rc<Inner>,
+}
+
+/// A handle to a [`TestExecutor`] that implements
+/// [`Spawn`](futures_task::Spawn) and [`LocalSpawn`](futures_task::LocalSpawn).
+///
+/// The handle doesn't keep the executor alive, so tasks can hold one without
+/// creating a reference cycle with the executor that stores them.
+#[derive(Clone)]
+pub struct TestSpawner {
+    inner: Weak<Inner>,
+}
+
+struct Inner {
+    tasks: TaskSlots,
+    shared: Arc<Shared>,
+}
+
+/// The state that is shared with the wakers of the tasks.
+#[derive(Default)]
+struct Shared {
+    ready: Mutex<VecDeque<usize>>,
+    wake_log: Mutex<Vec<usize>>,
+}
+
+struct TaskWaker {
+    id: usize,
+    shared: Arc<Shared>,
+}
+
+impl ArcWake for TaskWaker {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.shared.wake_log.lock().unwrap().push(arc_self.id);
+        arc_self.shared.make_ready(arc_self.id);
+    }
+}
+
+impl Shared {
+    fn make_ready(&self, id: usize) {
+        let mut ready = self.ready.lock().unwrap();
+        if !ready.contains(&id) {
+            ready.push_back(id);
+        }
+    }
+}
+
+impl Inner {
+    fn spawn(&self, future: LocalFutureObj<'static, ()>) {
+        let id = self.tasks.push(future);
+        self.shared.make_ready(id);
+    }
+}
+
+impl TestExecutor {
+    /// Create a new instance
+    pub fn new() -> Self {
+        Self { inner: Rc::new(Inner { tasks: TaskSlots::new(0), shared: Default::default() }) }
+    }
+
+    /// Get a clonable handle to the executor as a [`Spawn`].
+    pub fn spawner(&self) -> TestSpawner {
+        TestSpawner { inner: Rc::downgrade(&self.inner) }
+    }
+
+    /// Polls the task that became ready first, if any, and returns its id.
+    ///
+    /// Returns `None` if no task is ready.
+    pub fn poll_one(&self) -> Option<usize> {
+        loop {
+            let id = self.inner.shared.ready.lock().unwrap().pop_front()?;
+            let waker = task::waker(Arc::new(TaskWaker { id, shared: self.inner.shared.clone() }));
+            // Tasks that have completed since they were woken are skipped.
+            if self.inner.tasks.poll(id, &mut Context::from_waker(&waker)).is_some() {
+                return Some(id);
+            }
+        }
+    }
+
+    /// Polls ready tasks until none is ready anymore.
+    ///
+    /// Returns `Poll::Ready(())` if all tasks have completed, and
+    /// `Poll::Pending` if some tasks are still waiting to be woken.
+    pub fn run_until_stalled(&self) -> Poll<()> {
+        while self.poll_one().is_some() {}
+        if self.task_count() == 0 {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+
+    /// Returns the number of tasks that are ready to be polled.
+    pub fn ready_task_count(&self) -> usize {
+        let ready = self.inner.shared.ready.lock().unwrap();
+        ready.iter().filter(|&&id| self.inner.tasks.is_pending(id)).count()
+    }
+
+    /// Returns the number of tasks that have not completed yet.
+    pub fn task_count(&self) -> usize {
+        self.inner.tasks.pending_count()
+    }
+
+    /// Returns the ids of the tasks that have been woken since the last call,
+    /// in the order of the wakeups.
+    ///
+    /// A task that was woken several times appears several times. Spawning a
+    /// task makes it ready, but is not logged as a wakeup.
+    pub fn wake_log(&self) -> Vec<usize> {
+        mem::replace(&mut *self.inner.shared.wake_log.lock().unwrap(), Vec::new())
+    }
+}
+
+impl Default for TestExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<RecordSpawner> for TestExecutor {
+    /// Creates an executor that runs the futures recorded by the
+    /// [`RecordSpawner`], with ids in the order in which they were spawned.
+    fn from(recorder: RecordSpawner) -> Self {
+        let executor = Self::new();
+        for future in recorder.into_spawned() {
+            executor.spawn_local_obj(future.into()).unwrap();
+        }
+        executor
+    }
+}
+
+impl Spawn for TestExecutor {
+    fn spawn_obj(&self, future: FutureObj<'static, ()>) -> Result<(), SpawnError> {
+        self.spawn_local_obj(future.into())
+    }
+}
+
+impl LocalSpawn for TestExecutor {
+    fn spawn_local_obj(&self, future: LocalFutureObj<'static, ()>) -> Result<(), SpawnError> {
+        self.inner.spawn(future);
+        Ok(())
+    }
+}
+
+impl fmt::Debug for TestExecutor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TestExecutor")
+            .field("task_count", &self.task_count())
+            .field("ready_task_count", &self.ready_task_count())
+            .finish()
+    }
+}
+
+impl Spawn for TestSpawner {
+    fn spawn_obj(&self, future: FutureObj<'static, ()>) -> Result<(), SpawnError> {
+        self.spawn_local_obj(future.into())
+    }
+
+    fn status(&self) -> Result<(), SpawnError> {
+        self.status_local()
+    }
+}
+
+impl LocalSpawn for TestSpawner {
+    fn spawn_local_obj(&self, future: LocalFutureObj<'static, ()>) -> Result<(), SpawnError> {
+        match self.inner.upgrade() {
+            Some(inner) => {
+                inner.spawn(future);
+                Ok(())
+            }
+            None => Err(SpawnError::shutdown()),
+        }
+    }
+
+    fn status_local(&self) -> Result<(), SpawnError> {
+        if self.inner.upgrade().is_some() {
+            Ok(())
+        } else {
+            Err(SpawnError::shutdown())
+        }
+    }
+}
+
+impl fmt::Debug for TestSpawner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TestSpawner").finish()
+    }
+}
@@ -0,0 +1,49 @@
+use futures_core::task::{Context, Poll};
+use futures_task::LocalFutureObj;
+use futures_util::future::FutureExt;
+use std::cell::RefCell;
+
+/// The tasks of a single-threaded test executor, indexed by task id.
+///
+/// The slot of a task is `None` once it has completed or while it is being
+/// polled.
+pub(crate) struct TaskSlots {
+    tasks: RefCell<Vec<Option<LocalFutureObj<'static, ()>>>>,
+}
+
+impl TaskSlots {
+    /// Creates the slots with the ids below `first_id` reserved, e.g. for a
+    /// future that isn't spawned.
+    pub(crate) fn new(first_id: usize) -> Self {
+        Self { tasks: RefCell::new((0..first_id).map(|_| None).collect()) }
+    }
+
+    /// Adds a task and returns its id.
+    pub(crate) fn push(&self, future: LocalFutureObj<'static, ()>) -> usize {
+        let mut tasks = self.tasks.borrow_mut();
+        tasks.push(Some(future));
+        tasks.len() - 1
+    }
+
+    /// Polls the task `id`, or returns `None` if it has completed.
+    pub(crate) fn poll(&self, id: usize, cx: &mut Context<'_>) -> Option<Poll<()>> {
+        // Take the task out of its slot, so that it can spawn new tasks
+        // while it is being polled.
+        let mut future = self.tasks.borrow_mut()[id].take()?;
+        let poll = future.poll_unpin(cx);
+        if poll.is_pending() {
+            self.tasks.borrow_mut()[id] = Some(future);
+        }
+        Some(poll)
+    }
+
+    /// Returns whether the task `id` has not completed yet.
+    pub(crate) fn is_pending(&self, id: usize) -> bool {
+        self.tasks.borrow()[id].is_some()
+    }
+
+    /// Returns the number of tasks that have not completed yet.
+    pub(crate) fn pending_count(&self) -> usize {
+        self.tasks.borrow().iter().filter(|task| task.is_some()).count()
+    }
+}
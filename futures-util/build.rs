@@ -4,6 +4,7 @@ use autocfg::AutoCfg;
 use std::env;
 
 include!("no_atomic_cas.rs");
+include!("track_caller.rs");
 
 // The rustc-cfg listed below are considered public API, but it is *unstable*
 // and outside of the normal semver guarantees:
@@ -57,5 +58,7 @@ fn main() {
         println!("cargo:rustc-cfg=fn_like_proc_macro");
     }
 
+    probe_track_caller(&cfg);
+
     println!("cargo:rerun-if-changed=no_atomic_cas.rs");
 }
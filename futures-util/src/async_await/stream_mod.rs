@@ -0,0 +1,206 @@
+//! The `stream` macro.
+
+use core::pin::Pin;
+use futures_core::future::Future;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+use std::sync::{Arc, Mutex};
+
+#[allow(unreachable_pub)]
+#[doc(hidden)]
+#[cfg_attr(not(fn_like_proc_macro), proc_macro_hack::proc_macro_hack(support_nested))]
+pub use futures_macro::stream_internal;
+
+#[allow(unreachable_pub)]
+#[doc(hidden)]
+#[cfg_attr(not(fn_like_proc_macro), proc_macro_hack::proc_macro_hack(support_nested))]
+pub use futures_macro::try_stream_internal;
+
+/// Creates a stream from a block of code which yields its items with
+/// `yield` expressions.
+///
+/// The block is run as the body of an `async move` block, so it can
+/// `.await` futures, and takes ownership of the variables it uses. Every
+/// `yield value` hands `value` to the consumer of the stream, and waits for
+/// the stream to be polled again before the block goes on. The stream ends
+/// once the block has run to completion.
+///
+/// `yield` can't be used from within a closure or a nested `async` block.
+///
+/// This macro is gated behind the `std` and `async-await-macro` features of
+/// this library, which are activated by default. In the `futures` crate,
+/// the `std` and `async-await` features activate them.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::future;
+/// use futures::stream::{self, StreamExt};
+///
+/// let stream = stream::stream! {
+///     for i in 0..3 {
+///         let x = future::ready(i * 2).await;
+///         yield x;
+///     }
+/// };
+///
+/// assert_eq!(stream.collect::<Vec<_>>().await, vec![0, 2, 4]);
+/// # });
+/// ```
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __async_stream {
+    ($($tokens:tt)*) => {{
+        use $crate::__private as __futures_crate;
+        $crate::stream_internal! {
+            $( $tokens )*
+        }
+    }}
+}
+
+/// Creates a stream of `Result`s from a block of code which yields its
+/// successful items with `yield` expressions, and its errors with `?`.
+///
+/// This is the fallible version of [`stream!`](crate::stream::stream). Every
+/// `yield value` yields `Ok(value)`, and an expression `expr?` for which
+/// `expr` is an error yields it, converted with [`Into`], and ends the
+/// stream.
+///
+/// This macro is gated behind the `std` and `async-await-macro` features of
+/// this library, which are activated by default. In the `futures` crate,
+/// the `std` and `async-await` features activate them.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::stream::{self, StreamExt, TryStream, TryStreamExt};
+///
+/// fn parse<'a>(input: &'a [&'a str]) -> impl TryStream<Ok = u8, Error = String> + 'a {
+///     stream::try_stream! {
+///         for s in input {
+///             yield s.parse::<u8>().map_err(|e| format!("{}: {}", s, e))?;
+///         }
+///     }
+/// }
+///
+/// let items: Vec<_> = parse(&["1", "2", "x", "3"]).into_stream().collect().await;
+/// assert_eq!(items, vec![Ok(1), Ok(2), Err("x: invalid digit found in string".to_string())]);
+/// # });
+/// ```
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __async_try_stream {
+    ($($tokens:tt)*) => {{
+        use $crate::__private as __futures_crate;
+        $crate::try_stream_internal! {
+            $( $tokens )*
+        }
+    }}
+}
+
+pin_project! {
+    /// Stream returned by the `stream!` and `try_stream!` macros.
+    #[doc(hidden)]
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct AsyncStream<T, Fut> {
+        slot: Arc<Mutex<Option<T>>>,
+        #[pin]
+        future: Option<Fut>,
+    }
+}
+
+/// The sending half of the single-slot channel between the body of a
+/// `stream!` and its `AsyncStream`.
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct Yielder<T> {
+    slot: Arc<Mutex<Option<T>>>,
+}
+
+/// Future for the [`Yielder::send`] method.
+#[doc(hidden)]
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Yield<'a, T> {
+    slot: &'a Mutex<Option<T>>,
+    value: Option<T>,
+}
+
+impl<T> Unpin for Yield<'_, T> {}
+
+impl<T, Fut> AsyncStream<T, Fut>
+where
+    Fut: Future<Output = ()>,
+{
+    pub fn new<F>(f: F) -> Self
+    where
+        F: FnOnce(Yielder<T>) -> Fut,
+    {
+        let slot = Arc::new(Mutex::new(None));
+        let future = f(Yielder { slot: slot.clone() });
+        Self { slot, future: Some(future) }
+    }
+}
+
+impl<T> Yielder<T> {
+    pub fn send(&mut self, value: T) -> Yield<'_, T> {
+        Yield { slot: &self.slot, value: Some(value) }
+    }
+}
+
+impl<T> Future for Yield<'_, T> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+        let mut slot = self.slot.lock().unwrap();
+        match self.value.take() {
+            // The stream yields the value as soon as this returns, and then
+            // polls again once its consumer wants the next one.
+            Some(value) => {
+                *slot = Some(value);
+                Poll::Pending
+            }
+            None if slot.is_some() => Poll::Pending,
+            None => Poll::Ready(()),
+        }
+    }
+}
+
+impl<T, Fut> FusedStream for AsyncStream<T, Fut>
+where
+    Fut: Future<Output = ()>,
+{
+    fn is_terminated(&self) -> bool {
+        self.future.is_none()
+    }
+}
+
+impl<T, Fut> Stream for AsyncStream<T, Fut>
+where
+    Fut: Future<Output = ()>,
+{
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let mut this = self.project();
+        let future = match this.future.as_mut().as_pin_mut() {
+            Some(future) => future,
+            None => return Poll::Ready(None),
+        };
+
+        let done = future.poll(cx).is_ready();
+        let item = this.slot.lock().unwrap().take();
+        if done {
+            this.future.set(None);
+        }
+        match item {
+            Some(item) => Poll::Ready(Some(item)),
+            None if done => Poll::Ready(None),
+            None => Poll::Pending,
+        }
+    }
+}
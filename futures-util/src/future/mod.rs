@@ -106,6 +106,16 @@ pub use self::select_ok::{select_ok, SelectOk};
 mod either;
 pub use self::either::Either;
 
+#[cfg(feature = "std")]
+mod sleep;
+#[cfg(feature = "std")]
+pub use self::sleep::Sleep;
+
+#[cfg(feature = "std")]
+mod retry;
+#[cfg(feature = "std")]
+pub use self::retry::{retry, Backoff, Retry, RetryPolicy};
+
 #[cfg(not(futures_no_atomic_cas))]
 #[cfg(feature = "alloc")]
 mod abortable;
@@ -0,0 +1,287 @@
+use super::{assert_future, Sleep};
+use core::convert::TryFrom;
+use core::fmt;
+use core::pin::Pin;
+use futures_core::future::{FusedFuture, Future, TryFuture};
+use futures_core::ready;
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{Duration, Instant};
+
+/// Decides whether a failed operation should be attempted again, and after
+/// how long.
+///
+/// This is implemented by [`Backoff`], and by any closure taking the same
+/// arguments as [`retry`](RetryPolicy::retry).
+pub trait RetryPolicy<E> {
+    /// Returns the delay to wait for before the next attempt, or `None` to
+    /// give up and return `error`.
+    ///
+    /// `attempt` is the number of attempts that have failed so far, starting
+    /// at `1`, and `elapsed` is the time since the first of them started.
+    fn retry(&mut self, error: &E, attempt: usize, elapsed: Duration) -> Option<Duration>;
+}
+
+impl<E, F> RetryPolicy<E> for F
+where
+    F: FnMut(&E, usize, Duration) -> Option<Duration>,
+{
+    fn retry(&mut self, error: &E, attempt: usize, elapsed: Duration) -> Option<Duration> {
+        self(error, attempt, elapsed)
+    }
+}
+
+/// A [`RetryPolicy`] that retries on every error, with a constant or
+/// exponentially growing delay.
+///
+/// By default, a `Backoff` retries forever. It can be limited with
+/// [`max_attempts`](Backoff::max_attempts) and
+/// [`max_elapsed`](Backoff::max_elapsed).
+///
+/// # Examples
+///
+/// ```
+/// use futures::future::{Backoff, RetryPolicy};
+/// use std::time::Duration;
+///
+/// let mut backoff = Backoff::exponential(Duration::from_millis(100), 2)
+///     .max_delay(Duration::from_millis(300))
+///     .max_attempts(4);
+///
+/// let mut delays = (1..).map(|attempt| backoff.retry(&(), attempt, Duration::from_secs(0)));
+/// assert_eq!(delays.next(), Some(Some(Duration::from_millis(100))));
+/// assert_eq!(delays.next(), Some(Some(Duration::from_millis(200))));
+/// assert_eq!(delays.next(), Some(Some(Duration::from_millis(300))));
+/// assert_eq!(delays.next(), Some(None));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    initial: Duration,
+    factor: u32,
+    max_delay: Option<Duration>,
+    max_attempts: Option<usize>,
+    max_elapsed: Option<Duration>,
+    // The state of the random number generator, if the delays are jittered.
+    jitter: Option<u64>,
+}
+
+impl Backoff {
+    /// Creates a policy that waits for `delay` before every attempt.
+    pub fn constant(delay: Duration) -> Self {
+        Self::exponential(delay, 1)
+    }
+
+    /// Creates a policy that waits for `initial` before the second attempt,
+    /// and multiplies the delay by `factor` before each of the next ones.
+    pub fn exponential(initial: Duration, factor: u32) -> Self {
+        Self {
+            initial,
+            factor,
+            max_delay: None,
+            max_attempts: None,
+            max_elapsed: None,
+            jitter: None,
+        }
+    }
+
+    /// Caps the delay between two attempts to `max_delay`.
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = Some(max_delay);
+        self
+    }
+
+    /// Gives up once `max_attempts` attempts have failed.
+    pub fn max_attempts(mut self, max_attempts: usize) -> Self {
+        self.max_attempts = Some(max_attempts);
+        self
+    }
+
+    /// Gives up once `max_elapsed` has elapsed since the first attempt.
+    pub fn max_elapsed(mut self, max_elapsed: Duration) -> Self {
+        self.max_elapsed = Some(max_elapsed);
+        self
+    }
+
+    /// Randomizes every delay between zero and the delay it would otherwise
+    /// be, so that many clients failing at once don't retry in lockstep.
+    pub fn jitter(mut self) -> Self {
+        // Any non-zero seed will do
+        let seed = RandomState::new().build_hasher().finish();
+        self.jitter = Some(seed | 1);
+        self
+    }
+
+    fn delay(&self, attempt: usize) -> Duration {
+        let max_delay = self.max_delay.unwrap_or_else(|| Duration::from_secs(u64::max_value()));
+        let delay = u32::try_from(attempt.saturating_sub(1))
+            .ok()
+            .and_then(|exp| self.factor.checked_pow(exp))
+            .and_then(|factor| self.initial.checked_mul(factor));
+        match delay {
+            Some(delay) if delay < max_delay => delay,
+            _ => max_delay,
+        }
+    }
+}
+
+impl<E> RetryPolicy<E> for Backoff {
+    fn retry(&mut self, _error: &E, attempt: usize, elapsed: Duration) -> Option<Duration> {
+        if self.max_attempts.map_or(false, |max| attempt >= max)
+            || self.max_elapsed.map_or(false, |max| elapsed >= max)
+        {
+            return None;
+        }
+
+        let delay = self.delay(attempt);
+        Some(match &mut self.jitter {
+            Some(state) => {
+                // xorshift*, as in `select!`
+                let mut x = *state;
+                x ^= x >> 12;
+                x ^= x << 25;
+                x ^= x >> 27;
+                *state = x;
+                let random = x.wrapping_mul(0x2545_f491_4f6c_dd1d);
+                let nanos = delay.as_nanos().min(u128::from(u64::max_value()));
+                Duration::from_nanos(((u128::from(random) * (nanos + 1)) >> 64) as u64)
+            }
+            None => delay,
+        })
+    }
+}
+
+pin_project! {
+    /// Future for the [`retry`] function.
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct Retry<F, Fut, P, S: Sleep> {
+        #[pin]
+        future: Option<Fut>,
+        #[pin]
+        delay: Option<S::Future>,
+        factory: F,
+        policy: P,
+        sleep: S,
+        attempt: usize,
+        start: Option<Instant>,
+    }
+}
+
+impl<F, Fut, P, S> fmt::Debug for Retry<F, Fut, P, S>
+where
+    Fut: fmt::Debug,
+    P: fmt::Debug,
+    S: Sleep,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Retry")
+            .field("future", &self.future)
+            .field("policy", &self.policy)
+            .field("attempt", &self.attempt)
+            .finish()
+    }
+}
+
+/// Creates a future which runs the future returned by `factory` until it
+/// succeeds, or until `policy` gives up.
+///
+/// Whenever the future fails, `policy` is asked whether to try again. If it
+/// returns a delay, the returned future waits for it using the `sleep` timer,
+/// then calls `factory` again for a new attempt. Otherwise, it resolves to
+/// the error of the last attempt.
+///
+/// `factory` isn't called until the returned future is first polled.
+///
+/// This function is only available when the `std` feature of this
+/// library is activated, and it is activated by default.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::future::{self, Backoff};
+/// use std::time::Duration;
+///
+/// let mut attempts = 0;
+/// let result = future::retry(
+///     || {
+///         attempts += 1;
+///         future::ready(if attempts < 3 { Err("not yet") } else { Ok(attempts) })
+///     },
+///     Backoff::constant(Duration::from_millis(10)),
+///     // Any timer will do, this one doesn't wait at all.
+///     |_| future::ready(()),
+/// );
+/// assert_eq!(result.await, Ok(3));
+/// # });
+/// ```
+pub fn retry<F, Fut, P, S>(factory: F, policy: P, sleep: S) -> Retry<F, Fut, P, S>
+where
+    F: FnMut() -> Fut,
+    Fut: TryFuture,
+    P: RetryPolicy<Fut::Error>,
+    S: Sleep,
+{
+    assert_future::<Result<Fut::Ok, Fut::Error>, _>(Retry {
+        future: None,
+        delay: None,
+        factory,
+        policy,
+        sleep,
+        attempt: 0,
+        start: None,
+    })
+}
+
+impl<F, Fut, P, S> FusedFuture for Retry<F, Fut, P, S>
+where
+    F: FnMut() -> Fut,
+    Fut: TryFuture,
+    P: RetryPolicy<Fut::Error>,
+    S: Sleep,
+{
+    fn is_terminated(&self) -> bool {
+        self.start.is_some() && self.future.is_none() && self.delay.is_none()
+    }
+}
+
+impl<F, Fut, P, S> Future for Retry<F, Fut, P, S>
+where
+    F: FnMut() -> Fut,
+    Fut: TryFuture,
+    P: RetryPolicy<Fut::Error>,
+    S: Sleep,
+{
+    type Output = Result<Fut::Ok, Fut::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+
+        loop {
+            if let Some(delay) = this.delay.as_mut().as_pin_mut() {
+                ready!(delay.poll(cx));
+                this.delay.set(None);
+                this.future.set(Some((this.factory)()));
+            } else if this.future.is_none() {
+                assert!(this.start.is_none(), "Retry polled after completion");
+                *this.start = Some(this.sleep.now());
+                this.future.set(Some((this.factory)()));
+            }
+
+            let result = ready!(this.future.as_mut().as_pin_mut().unwrap().try_poll(cx));
+            this.future.set(None);
+            let error = match result {
+                Ok(output) => return Poll::Ready(Ok(output)),
+                Err(error) => error,
+            };
+
+            *this.attempt += 1;
+            let elapsed = this.sleep.now().duration_since(this.start.unwrap());
+            match this.policy.retry(&error, *this.attempt, elapsed) {
+                Some(delay) => this.delay.set(Some(this.sleep.sleep(delay))),
+                None => return Poll::Ready(Err(error)),
+            }
+        }
+    }
+}
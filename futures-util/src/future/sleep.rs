@@ -0,0 +1,39 @@
+use core::future::Future;
+use std::time::{Duration, Instant};
+
+/// A source of timers, for the combinators that need to wait for some time.
+///
+/// This library doesn't come with a timer of its own, so these combinators
+/// are given one by their caller, usually backed by the timer of the
+/// runtime they run on. Any closure that takes a [`Duration`] and returns a
+/// future implements this trait, e.g. `|duration| tokio::time::sleep(duration)`.
+///
+/// A test can provide a timer backed by a virtual clock instead, so that
+/// timing-dependent code runs instantly and deterministically.
+pub trait Sleep {
+    /// The future returned by [`sleep`](Sleep::sleep).
+    type Future: Future<Output = ()>;
+
+    /// Returns a future that completes once `duration` has elapsed.
+    fn sleep(&self, duration: Duration) -> Self::Future;
+
+    /// Returns the current time of the clock driving this timer.
+    ///
+    /// This defaults to [`Instant::now`], and should be overridden by timers
+    /// that don't follow the system clock.
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+impl<F, Fut> Sleep for F
+where
+    F: Fn(Duration) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    type Future = Fut;
+
+    fn sleep(&self, duration: Duration) -> Fut {
+        self(duration)
+    }
+}
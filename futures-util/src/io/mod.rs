@@ -131,6 +131,9 @@ pub use self::split::{ReadHalf, ReuniteError, WriteHalf};
 mod take;
 pub use self::take::Take;
 
+mod unblock;
+pub use self::unblock::Unblock;
+
 mod window;
 pub use self::window::Window;
 
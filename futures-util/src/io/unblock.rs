@@ -0,0 +1,543 @@
+use super::DEFAULT_BUF_SIZE;
+use futures_core::ready;
+use futures_core::task::{Context, Poll, Waker};
+use futures_io::{AsyncBufRead, AsyncRead, AsyncSeek, AsyncWrite, SeekFrom};
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::{cmp, fmt, io, panic};
+
+/// Runs blocking I/O on a helper thread, so that it can be used in contexts
+/// which expect an `AsyncRead`, `AsyncWrite`, `AsyncBufRead` or `AsyncSeek`.
+///
+/// Unlike [`AllowStdIo`](super::AllowStdIo), which calls the blocking
+/// methods of the I/O object inline, `Unblock` moves the I/O object to a
+/// helper thread of its own, so that reading from or writing to files, pipes
+/// or other blocking I/O objects does not block the executor.
+///
+/// Data is passed between the task and the helper thread through a bounded
+/// buffer. While reading, the helper thread reads ahead until the buffer is
+/// full. While writing, writes complete as soon as the data has been copied
+/// into the buffer; [`flush`](crate::io::AsyncWriteExt::flush) or
+/// [`close`](crate::io::AsyncWriteExt::close) wait until all data has been
+/// written and the I/O object has been flushed, and return the first error
+/// that occurred.
+///
+/// As the helper thread reads ahead, the position of the I/O object is past
+/// the data read so far. A write that follows a read therefore fails with
+/// [`InvalidInput`](io::ErrorKind::InvalidInput) while data read ahead has
+/// not been consumed. Seek before writing, e.g. to
+/// [`SeekFrom::Current(0)`](SeekFrom::Current), to write at the position of
+/// the last read: seeking takes the data read ahead into account.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt, SeekFrom, Unblock};
+/// use std::io::Cursor;
+///
+/// let mut io = Unblock::new(Cursor::new(Vec::new()));
+/// io.write_all(b"hello world").await?;
+/// io.seek(SeekFrom::Start(6)).await?;
+///
+/// let mut buf = String::new();
+/// io.read_to_string(&mut buf).await?;
+/// assert_eq!(buf, "world");
+/// # Ok::<(), Box<dyn std::error::Error>>(()) }).unwrap();
+/// ```
+pub struct Unblock<T> {
+    jobs: Option<Sender<Job<T>>>,
+    thread: Option<JoinHandle<T>>,
+    capacity: usize,
+    state: State,
+    /// Data taken from the buffer of the current reading job, which has not
+    /// been consumed yet.
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl<T> Unpin for Unblock<T> {}
+
+type Job<T> = Box<dyn FnOnce(&mut T) + Send>;
+
+enum State {
+    Idle,
+    Reading(Arc<Pipe>),
+    Writing(Arc<Pipe>),
+    // The position the seek was started for.
+    Seeking(Arc<Slot<u64>>, SeekFrom),
+}
+
+/// The buffer between the task and a job reading or writing on the helper
+/// thread.
+struct Pipe {
+    state: Mutex<PipeState>,
+    /// Notifies the job of changes made by the task.
+    changed: Condvar,
+}
+
+struct PipeState {
+    buf: VecDeque<u8>,
+    /// Set by the task to stop the job. A writing job first writes the
+    /// remaining data and flushes the I/O object.
+    closed: bool,
+    /// Set by the job once it has stopped.
+    done: bool,
+    error: Option<io::Error>,
+    waker: Option<Waker>,
+}
+
+/// Finishes the pipe of a job when dropped, even if the job panicked or the
+/// helper thread is gone.
+struct PipeEnd {
+    pipe: Arc<Pipe>,
+    result: Option<io::Result<()>>,
+}
+
+/// The result of a seek on the helper thread.
+struct Slot<R> {
+    state: Mutex<(Option<io::Result<R>>, Option<Waker>)>,
+}
+
+/// Completes the slot of a job when dropped, like `PipeEnd`.
+struct Completer<R> {
+    slot: Arc<Slot<R>>,
+    result: Option<io::Result<R>>,
+}
+
+fn helper_gone() -> io::Error {
+    io::Error::new(io::ErrorKind::Other, "the I/O helper thread panicked")
+}
+
+fn register(waker: &mut Option<Waker>, cx: &Context<'_>) {
+    match waker {
+        Some(waker) if waker.will_wake(cx.waker()) => {}
+        _ => *waker = Some(cx.waker().clone()),
+    }
+}
+
+impl Pipe {
+    fn new(capacity: usize) -> Self {
+        Self {
+            state: Mutex::new(PipeState {
+                buf: VecDeque::with_capacity(capacity),
+                closed: false,
+                done: false,
+                error: None,
+                waker: None,
+            }),
+            changed: Condvar::new(),
+        }
+    }
+
+    fn close(&self) {
+        self.state.lock().unwrap().closed = true;
+        self.changed.notify_one();
+    }
+}
+
+impl PipeEnd {
+    fn finish(mut self, result: io::Result<()>) {
+        self.result = Some(result);
+    }
+}
+
+impl Drop for PipeEnd {
+    fn drop(&mut self) {
+        let mut state = self.pipe.state.lock().unwrap();
+        state.done = true;
+        state.error = match self.result.take() {
+            Some(result) => result.err(),
+            None => Some(helper_gone()),
+        };
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<R> Slot<R> {
+    fn poll(&self, cx: &mut Context<'_>) -> Poll<io::Result<R>> {
+        let mut state = self.state.lock().unwrap();
+        match state.0.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                register(&mut state.1, cx);
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<R> Completer<R> {
+    fn complete(mut self, result: io::Result<R>) {
+        self.result = Some(result);
+    }
+}
+
+impl<R> Drop for Completer<R> {
+    fn drop(&mut self) {
+        let mut state = self.slot.state.lock().unwrap();
+        state.0 = Some(self.result.take().unwrap_or_else(|| Err(helper_gone())));
+        if let Some(waker) = state.1.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T: Send + 'static> Unblock<T> {
+    /// Moves `io` to a new helper thread, using a default buffer capacity.
+    ///
+    /// The default capacity is currently 8 KB, but may change in the future.
+    pub fn new(io: T) -> Self {
+        Self::with_capacity(DEFAULT_BUF_SIZE, io)
+    }
+
+    /// Moves `io` to a new helper thread, using a buffer with the specified
+    /// capacity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn with_capacity(capacity: usize, io: T) -> Self {
+        assert!(capacity > 0, "capacity must be greater than zero");
+        let (jobs, rx) = mpsc::channel::<Job<T>>();
+        let thread = thread::Builder::new()
+            .name("futures-unblock".to_string())
+            .spawn(move || {
+                let mut io = io;
+                for job in rx {
+                    job(&mut io);
+                }
+                io
+            })
+            .expect("failed to spawn the I/O helper thread");
+        Self {
+            jobs: Some(jobs),
+            thread: Some(thread),
+            capacity,
+            state: State::Idle,
+            buf: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    /// Consumes self and returns the I/O object.
+    ///
+    /// This blocks the current thread until the helper thread has written
+    /// the data buffered by previous writes. Errors that occur while doing
+    /// so are discarded; [`flush`](crate::io::AsyncWriteExt::flush) first to
+    /// observe them. Data read ahead is discarded.
+    pub fn into_inner(mut self) -> T {
+        self.stop();
+        drop(self.jobs.take());
+        match self.thread.take().unwrap().join() {
+            Ok(io) => io,
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    }
+
+    fn spawn(&self, job: Job<T>) {
+        // If the helper thread is gone, dropping the job finishes it with an
+        // error.
+        let _ = self.jobs.as_ref().unwrap().send(job);
+    }
+}
+
+impl<T> Unblock<T> {
+    // Stops the current reading or writing job.
+    fn stop(&mut self) {
+        match &self.state {
+            State::Reading(pipe) | State::Writing(pipe) => pipe.close(),
+            State::Idle | State::Seeking(..) => {}
+        }
+    }
+
+    // Stops the current reading job before a write, failing if data was
+    // read ahead that hasn't been consumed, as the write would land past it.
+    fn poll_stop_reading(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if let State::Reading(pipe) = &self.state {
+            let mut state = pipe.state.lock().unwrap();
+            if !state.closed {
+                state.closed = true;
+                pipe.changed.notify_one();
+            }
+            if !state.done {
+                register(&mut state.waker, cx);
+                return Poll::Pending;
+            }
+            // The data stays buffered for the reads that follow.
+            if self.pos < self.buf.len() || !state.buf.is_empty() {
+                return Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "cannot write after reading ahead, seek to set the position first",
+                )));
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    // Waits until no job is running, returning the error of a writing job.
+    fn poll_idle(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let res = match &self.state {
+            State::Idle => return Poll::Ready(Ok(())),
+            State::Reading(pipe) => {
+                pipe.close();
+                self.buf.clear();
+                self.pos = 0;
+                Ok(())
+            }
+            State::Writing(pipe) => {
+                let mut state = pipe.state.lock().unwrap();
+                if !state.closed {
+                    state.closed = true;
+                    pipe.changed.notify_one();
+                }
+                if !state.done {
+                    register(&mut state.waker, cx);
+                    return Poll::Pending;
+                }
+                state.error.take().map_or(Ok(()), Err)
+            }
+            // The result of a seek that was not waited for is discarded.
+            State::Seeking(slot, _) => ready!(slot.poll(cx)).map(drop),
+        };
+        self.state = State::Idle;
+        Poll::Ready(res)
+    }
+}
+
+impl<T> Drop for Unblock<T> {
+    fn drop(&mut self) {
+        // Buffered data is still written. The helper thread exits once it has
+        // run the remaining jobs.
+        self.stop();
+    }
+}
+
+impl<T> fmt::Debug for Unblock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = match self.state {
+            State::Idle => "Idle",
+            State::Reading(_) => "Reading",
+            State::Writing(_) => "Writing",
+            State::Seeking(..) => "Seeking",
+        };
+        f.debug_struct("Unblock").field("capacity", &self.capacity).field("state", &state).finish()
+    }
+}
+
+fn read_job<T: io::Read>(end: PipeEnd, capacity: usize) -> Job<T> {
+    Box::new(move |io: &mut T| {
+        let pipe = end.pipe.clone();
+        let mut chunk = vec![0; capacity];
+        end.finish(loop {
+            let len = {
+                let mut state = pipe.state.lock().unwrap();
+                while state.buf.len() == capacity && !state.closed {
+                    state = pipe.changed.wait(state).unwrap();
+                }
+                if state.closed {
+                    break Ok(());
+                }
+                capacity - state.buf.len()
+            };
+            match io.read(&mut chunk[..len]) {
+                Ok(0) => break Ok(()),
+                Ok(n) => {
+                    let mut state = pipe.state.lock().unwrap();
+                    state.buf.extend(&chunk[..n]);
+                    if let Some(waker) = state.waker.take() {
+                        waker.wake();
+                    }
+                }
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => break Err(e),
+            }
+        });
+    })
+}
+
+fn write_job<T: io::Write>(end: PipeEnd) -> Job<T> {
+    Box::new(move |io: &mut T| {
+        let pipe = end.pipe.clone();
+        end.finish(loop {
+            let chunk: Vec<u8> = {
+                let mut state = pipe.state.lock().unwrap();
+                while state.buf.is_empty() && !state.closed {
+                    state = pipe.changed.wait(state).unwrap();
+                }
+                if state.buf.is_empty() {
+                    break io.flush();
+                }
+                if let Some(waker) = state.waker.take() {
+                    waker.wake();
+                }
+                state.buf.drain(..).collect()
+            };
+            if let Err(e) = io.write_all(&chunk) {
+                break Err(e);
+            }
+        });
+    })
+}
+
+impl<T: io::Read + Send + 'static> AsyncRead for Unblock<T> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let mut rem = ready!(self.as_mut().poll_fill_buf(cx))?;
+        let n = io::Read::read(&mut rem, buf)?;
+        self.consume(n);
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl<T: io::Read + Send + 'static> AsyncBufRead for Unblock<T> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+        while this.pos == this.buf.len() {
+            let pipe = match &this.state {
+                State::Reading(pipe) => pipe.clone(),
+                _ => {
+                    ready!(this.poll_idle(cx))?;
+                    let pipe = Arc::new(Pipe::new(this.capacity));
+                    let end = PipeEnd { pipe: pipe.clone(), result: None };
+                    this.spawn(read_job(end, this.capacity));
+                    this.state = State::Reading(pipe.clone());
+                    pipe
+                }
+            };
+
+            let mut state = pipe.state.lock().unwrap();
+            if state.buf.is_empty() {
+                if !state.done {
+                    register(&mut state.waker, cx);
+                    return Poll::Pending;
+                }
+                // End of file or error, unless the job was stopped by a
+                // write. The next read starts a new job, in case more data
+                // becomes available.
+                let error = state.error.take();
+                let stopped = state.closed;
+                drop(state);
+                this.state = State::Idle;
+                if let Some(e) = error {
+                    return Poll::Ready(Err(e));
+                }
+                if !stopped {
+                    break;
+                }
+            } else {
+                this.buf.clear();
+                this.pos = 0;
+                this.buf.extend(state.buf.drain(..));
+                pipe.changed.notify_one();
+            }
+        }
+        Poll::Ready(Ok(&this.buf[this.pos..]))
+    }
+
+    fn consume(mut self: Pin<&mut Self>, amt: usize) {
+        self.pos = cmp::min(self.pos + amt, self.buf.len());
+    }
+}
+
+impl<T: io::Write + Send + 'static> AsyncWrite for Unblock<T> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        loop {
+            let pipe = match &self.state {
+                State::Writing(pipe) => pipe.clone(),
+                _ => {
+                    ready!(self.poll_stop_reading(cx))?;
+                    ready!(self.poll_idle(cx))?;
+                    let pipe = Arc::new(Pipe::new(self.capacity));
+                    self.spawn(write_job(PipeEnd { pipe: pipe.clone(), result: None }));
+                    self.state = State::Writing(pipe.clone());
+                    pipe
+                }
+            };
+
+            let mut state = pipe.state.lock().unwrap();
+            if state.closed || state.done {
+                // A flush is in progress or writing failed.
+                drop(state);
+                ready!(self.poll_idle(cx))?;
+                continue;
+            }
+            let n = cmp::min(buf.len(), self.capacity - state.buf.len());
+            if n == 0 {
+                register(&mut state.waker, cx);
+                return Poll::Pending;
+            }
+            state.buf.extend(&buf[..n]);
+            pipe.changed.notify_one();
+            return Poll::Ready(Ok(n));
+        }
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.state {
+            State::Writing(_) => self.poll_idle(cx),
+            _ => Poll::Ready(Ok(())),
+        }
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_flush(cx)
+    }
+}
+
+impl<T: io::Seek + Send + 'static> AsyncSeek for Unblock<T> {
+    fn poll_seek(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        pos: SeekFrom,
+    ) -> Poll<io::Result<u64>> {
+        if let State::Seeking(slot, started) = &self.state {
+            let started = *started;
+            let res = ready!(slot.poll(cx));
+            self.state = State::Idle;
+            if started == pos {
+                // the seek started by a previous call
+                return Poll::Ready(res);
+            }
+            // The caller gave up on that seek and asks for another position,
+            // which is sought once the old seek is done.
+        }
+
+        // The position of the I/O object is past the data read ahead.
+        let read_ahead = match &self.state {
+            State::Reading(pipe) => Some((pipe.clone(), self.buf.len() - self.pos)),
+            _ => None,
+        };
+        ready!(self.poll_idle(cx))?;
+
+        let slot = Arc::new(Slot { state: Mutex::new((None, None)) });
+        let completer = Completer { slot: slot.clone(), result: None };
+        self.spawn(Box::new(move |io: &mut T| {
+            let pos = match (pos, read_ahead) {
+                (SeekFrom::Current(n), Some((pipe, unread))) => {
+                    let unread = unread + pipe.state.lock().unwrap().buf.len();
+                    SeekFrom::Current(n - unread as i64)
+                }
+                (pos, _) => pos,
+            };
+            completer.complete(io.seek(pos));
+        }));
+        self.state = State::Seeking(slot, pos);
+        self.poll_seek(cx, pos)
+    }
+}
@@ -0,0 +1,491 @@
+//! Merging of sorted streams
+
+use alloc::collections::BinaryHeap;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+use core::convert::Infallible;
+use core::fmt;
+use core::pin::Pin;
+
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream, TryStream};
+use futures_core::task::{Context, Poll};
+
+use super::assert_stream;
+use crate::stream::StreamExt;
+
+/// The streams being merged, and which of them need a new head item.
+struct Sources<St> {
+    streams: Vec<Option<St>>,
+    // The streams that haven't finished and have no item in the heap.
+    missing: Vec<usize>,
+    remaining: usize,
+}
+
+impl<St: Stream + Unpin> Sources<St> {
+    fn new<I: IntoIterator<Item = St>>(streams: I) -> Self {
+        let streams: Vec<_> = streams.into_iter().map(Some).collect();
+        let missing = (0..streams.len()).collect();
+        let remaining = streams.len();
+        Self { streams, missing, remaining }
+    }
+
+    /// Polls the streams that need a new head item, until every stream that
+    /// hasn't finished has one.
+    ///
+    /// An error returned by `push` is returned right away, and the stream
+    /// that produced it is polled again the next time.
+    fn poll_heads<E>(
+        &mut self,
+        cx: &mut Context<'_>,
+        mut push: impl FnMut(usize, St::Item) -> Result<(), E>,
+    ) -> Poll<Result<(), E>> {
+        let mut i = 0;
+        while i < self.missing.len() {
+            let index = self.missing[i];
+            let stream = self.streams[index].as_mut().unwrap();
+            match stream.poll_next_unpin(cx) {
+                Poll::Ready(Some(item)) => {
+                    if let Err(e) = push(index, item) {
+                        return Poll::Ready(Err(e));
+                    }
+                    self.missing.swap_remove(i);
+                }
+                Poll::Ready(None) => {
+                    self.streams[index] = None;
+                    self.remaining -= 1;
+                    self.missing.swap_remove(i);
+                }
+                Poll::Pending => i += 1,
+            }
+        }
+
+        // Yielding anything before every stream has a head could break the
+        // ordering.
+        if self.missing.is_empty() {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+
+    /// Marks the stream whose head was just yielded as needing a new one.
+    fn taken(&mut self, index: usize) {
+        if self.streams[index].is_some() {
+            self.missing.push(index);
+        }
+    }
+
+    fn size_hint(&self, buffered: usize) -> (usize, Option<usize>) {
+        self.streams.iter().flatten().map(|stream| stream.size_hint()).fold(
+            (buffered, Some(buffered)),
+            |(lower, upper), (l, u)| {
+                (lower.saturating_add(l), upper.and_then(|upper| u?.checked_add(upper)))
+            },
+        )
+    }
+}
+
+/// Stream for the [`merge_sorted`] function.
+#[must_use = "streams do nothing unless polled"]
+pub struct MergeSorted<St: Stream> {
+    sources: Sources<St>,
+    heap: BinaryHeap<Reverse<(St::Item, usize)>>,
+}
+
+// The streams are required to be `Unpin`, and nothing else is pinned.
+impl<St: Stream> Unpin for MergeSorted<St> {}
+
+impl<St> fmt::Debug for MergeSorted<St>
+where
+    St: Stream + fmt::Debug,
+    St::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MergeSorted")
+            .field("streams", &self.sources.streams)
+            .field("heap", &self.heap)
+            .finish()
+    }
+}
+
+impl<St> Stream for MergeSorted<St>
+where
+    St: Stream + Unpin,
+    St::Item: Ord,
+{
+    type Item = St::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let heap = &mut this.heap;
+        let res = ready!(this.sources.poll_heads(cx, |index, item| {
+            heap.push(Reverse((item, index)));
+            Ok::<_, Infallible>(())
+        }));
+        match res {
+            Ok(()) => {}
+            Err(e) => match e {},
+        }
+
+        Poll::Ready(this.heap.pop().map(|Reverse((item, index))| {
+            this.sources.taken(index);
+            item
+        }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.sources.size_hint(self.heap.len())
+    }
+}
+
+impl<St> FusedStream for MergeSorted<St>
+where
+    St: Stream + Unpin,
+    St::Item: Ord,
+{
+    fn is_terminated(&self) -> bool {
+        self.sources.remaining == 0 && self.heap.is_empty()
+    }
+}
+
+/// Merges streams whose items are sorted into a single sorted stream.
+///
+/// The returned stream keeps the next item of every stream, and yields the
+/// smallest of them. Since a stream that is not ready yet might still yield
+/// a smaller item, nothing is yielded until every stream that hasn't
+/// finished has an item ready. If several streams yield equal items, the
+/// one from the stream that comes first in `streams` is yielded first.
+///
+/// The output is only sorted if each of the streams is sorted.
+///
+/// This function is only available when the `std` or `alloc` feature of this
+/// library is activated, and it is activated by default.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::stream::{self, StreamExt};
+///
+/// let streams = vec![stream::iter(vec![1, 4, 7]), stream::iter(vec![2, 5]), stream::iter(vec![3])];
+/// let merged = stream::merge_sorted(streams).collect::<Vec<_>>().await;
+/// assert_eq!(merged, vec![1, 2, 3, 4, 5, 7]);
+/// # });
+/// ```
+pub fn merge_sorted<I>(streams: I) -> MergeSorted<I::Item>
+where
+    I: IntoIterator,
+    I::Item: Stream + Unpin,
+    <I::Item as Stream>::Item: Ord,
+{
+    assert_stream::<<I::Item as Stream>::Item, _>(MergeSorted {
+        sources: Sources::new(streams),
+        heap: BinaryHeap::new(),
+    })
+}
+
+/// Stream for the [`merge_sorted_by_key`] function.
+#[must_use = "streams do nothing unless polled"]
+pub struct MergeSortedByKey<St: Stream, K, F> {
+    sources: Sources<St>,
+    heads: Vec<Option<St::Item>>,
+    heap: BinaryHeap<Reverse<(K, usize)>>,
+    f: F,
+}
+
+impl<St: Stream, K, F> Unpin for MergeSortedByKey<St, K, F> {}
+
+impl<St, K, F> fmt::Debug for MergeSortedByKey<St, K, F>
+where
+    St: Stream + fmt::Debug,
+    St::Item: fmt::Debug,
+    K: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MergeSortedByKey")
+            .field("streams", &self.sources.streams)
+            .field("heads", &self.heads)
+            .field("heap", &self.heap)
+            .finish()
+    }
+}
+
+impl<St, K, F> Stream for MergeSortedByKey<St, K, F>
+where
+    St: Stream + Unpin,
+    F: FnMut(&St::Item) -> K,
+    K: Ord,
+{
+    type Item = St::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let (heap, heads, f) = (&mut this.heap, &mut this.heads, &mut this.f);
+        let res = ready!(this.sources.poll_heads(cx, |index, item| {
+            heap.push(Reverse((f(&item), index)));
+            heads[index] = Some(item);
+            Ok::<_, Infallible>(())
+        }));
+        match res {
+            Ok(()) => {}
+            Err(e) => match e {},
+        }
+
+        Poll::Ready(this.heap.pop().map(|Reverse((_, index))| {
+            this.sources.taken(index);
+            this.heads[index].take().unwrap()
+        }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.sources.size_hint(self.heap.len())
+    }
+}
+
+impl<St, K, F> FusedStream for MergeSortedByKey<St, K, F>
+where
+    St: Stream + Unpin,
+    F: FnMut(&St::Item) -> K,
+    K: Ord,
+{
+    fn is_terminated(&self) -> bool {
+        self.sources.remaining == 0 && self.heap.is_empty()
+    }
+}
+
+/// Merges streams whose items are sorted by the given key into a single
+/// stream sorted by that key.
+///
+/// This is like [`merge_sorted`], but the items are compared by the key the
+/// closure returns for them.
+///
+/// This function is only available when the `std` or `alloc` feature of this
+/// library is activated, and it is activated by default.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::stream::{self, StreamExt};
+///
+/// let a = stream::iter(vec![(1, "a"), (3, "a")]);
+/// let b = stream::iter(vec![(1, "b"), (2, "b")]);
+/// let merged = stream::merge_sorted_by_key(vec![a, b], |(t, _)| *t);
+/// assert_eq!(merged.collect::<Vec<_>>().await, vec![(1, "a"), (1, "b"), (2, "b"), (3, "a")]);
+/// # });
+/// ```
+pub fn merge_sorted_by_key<I, K, F>(streams: I, f: F) -> MergeSortedByKey<I::Item, K, F>
+where
+    I: IntoIterator,
+    I::Item: Stream + Unpin,
+    F: FnMut(&<I::Item as Stream>::Item) -> K,
+    K: Ord,
+{
+    let sources = Sources::new(streams);
+    let mut heads = Vec::new();
+    heads.resize_with(sources.streams.len(), || None);
+    assert_stream::<<I::Item as Stream>::Item, _>(MergeSortedByKey {
+        sources,
+        heads,
+        heap: BinaryHeap::new(),
+        f,
+    })
+}
+
+/// Stream for the [`try_merge_sorted`] function.
+#[must_use = "streams do nothing unless polled"]
+pub struct TryMergeSorted<St: TryStream> {
+    sources: Sources<St>,
+    heap: BinaryHeap<Reverse<(St::Ok, usize)>>,
+}
+
+impl<St: TryStream> Unpin for TryMergeSorted<St> {}
+
+impl<St> fmt::Debug for TryMergeSorted<St>
+where
+    St: TryStream + fmt::Debug,
+    St::Ok: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TryMergeSorted")
+            .field("streams", &self.sources.streams)
+            .field("heap", &self.heap)
+            .finish()
+    }
+}
+
+impl<St, T, E> Stream for TryMergeSorted<St>
+where
+    St: TryStream<Ok = T, Error = E> + Stream<Item = Result<T, E>> + Unpin,
+    T: Ord,
+{
+    type Item = Result<T, E>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let heap = &mut this.heap;
+        let res = ready!(this.sources.poll_heads(cx, |index, item| {
+            heap.push(Reverse((item?, index)));
+            Ok(())
+        }));
+        if let Err(e) = res {
+            return Poll::Ready(Some(Err(e)));
+        }
+
+        Poll::Ready(this.heap.pop().map(|Reverse((item, index))| {
+            this.sources.taken(index);
+            Ok(item)
+        }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.sources.size_hint(self.heap.len())
+    }
+}
+
+impl<St, T, E> FusedStream for TryMergeSorted<St>
+where
+    St: TryStream<Ok = T, Error = E> + Stream<Item = Result<T, E>> + Unpin,
+    T: Ord,
+{
+    fn is_terminated(&self) -> bool {
+        self.sources.remaining == 0 && self.heap.is_empty()
+    }
+}
+
+/// Merges streams whose successful values are sorted into a single sorted
+/// stream.
+///
+/// This is like [`merge_sorted`], but for streams of `Result`s. Errors are
+/// yielded as soon as they are encountered, without waiting for the other
+/// streams, and the stream that produced them is then polled again for its
+/// next value.
+///
+/// This function is only available when the `std` or `alloc` feature of this
+/// library is activated, and it is activated by default.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::stream::{self, StreamExt};
+///
+/// let a = stream::iter(vec![Ok(1), Err("oops"), Ok(4)]);
+/// let b = stream::iter(vec![Ok(2), Ok(3)]);
+/// let merged = stream::try_merge_sorted(vec![a, b]).collect::<Vec<_>>().await;
+/// assert_eq!(merged, vec![Ok(1), Err("oops"), Ok(2), Ok(3), Ok(4)]);
+/// # });
+/// ```
+pub fn try_merge_sorted<I>(streams: I) -> TryMergeSorted<I::Item>
+where
+    I: IntoIterator,
+    I::Item: TryStream + Unpin,
+    <I::Item as TryStream>::Ok: Ord,
+{
+    TryMergeSorted { sources: Sources::new(streams), heap: BinaryHeap::new() }
+}
+
+/// Stream for the [`try_merge_sorted_by_key`] function.
+#[must_use = "streams do nothing unless polled"]
+pub struct TryMergeSortedByKey<St: TryStream, K, F> {
+    sources: Sources<St>,
+    heads: Vec<Option<St::Ok>>,
+    heap: BinaryHeap<Reverse<(K, usize)>>,
+    f: F,
+}
+
+impl<St: TryStream, K, F> Unpin for TryMergeSortedByKey<St, K, F> {}
+
+impl<St, K, F> fmt::Debug for TryMergeSortedByKey<St, K, F>
+where
+    St: TryStream + fmt::Debug,
+    St::Ok: fmt::Debug,
+    K: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TryMergeSortedByKey")
+            .field("streams", &self.sources.streams)
+            .field("heads", &self.heads)
+            .field("heap", &self.heap)
+            .finish()
+    }
+}
+
+impl<St, T, E, K, F> Stream for TryMergeSortedByKey<St, K, F>
+where
+    St: TryStream<Ok = T, Error = E> + Stream<Item = Result<T, E>> + Unpin,
+    F: FnMut(&T) -> K,
+    K: Ord,
+{
+    type Item = Result<T, E>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let (heap, heads, f) = (&mut this.heap, &mut this.heads, &mut this.f);
+        let res = ready!(this.sources.poll_heads(cx, |index, item| {
+            let item = item?;
+            heap.push(Reverse((f(&item), index)));
+            heads[index] = Some(item);
+            Ok(())
+        }));
+        if let Err(e) = res {
+            return Poll::Ready(Some(Err(e)));
+        }
+
+        Poll::Ready(this.heap.pop().map(|Reverse((_, index))| {
+            this.sources.taken(index);
+            Ok(this.heads[index].take().unwrap())
+        }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.sources.size_hint(self.heap.len())
+    }
+}
+
+impl<St, T, E, K, F> FusedStream for TryMergeSortedByKey<St, K, F>
+where
+    St: TryStream<Ok = T, Error = E> + Stream<Item = Result<T, E>> + Unpin,
+    F: FnMut(&T) -> K,
+    K: Ord,
+{
+    fn is_terminated(&self) -> bool {
+        self.sources.remaining == 0 && self.heap.is_empty()
+    }
+}
+
+/// Merges streams whose successful values are sorted by the given key into
+/// a single stream sorted by that key.
+///
+/// This is like [`merge_sorted_by_key`], but for streams of `Result`s.
+/// Errors are yielded as soon as they are encountered, as with
+/// [`try_merge_sorted`].
+///
+/// This function is only available when the `std` or `alloc` feature of this
+/// library is activated, and it is activated by default.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::stream::{self, TryStreamExt};
+///
+/// let a = stream::iter(vec![Ok::<_, ()>((1, "a")), Ok((3, "a"))]);
+/// let b = stream::iter(vec![Ok((2, "b"))]);
+/// let merged = stream::try_merge_sorted_by_key(vec![a, b], |(t, _)| *t);
+/// assert_eq!(merged.try_collect::<Vec<_>>().await, Ok(vec![(1, "a"), (2, "b"), (3, "a")]));
+/// # });
+/// ```
+pub fn try_merge_sorted_by_key<I, K, F>(streams: I, f: F) -> TryMergeSortedByKey<I::Item, K, F>
+where
+    I: IntoIterator,
+    I::Item: TryStream + Unpin,
+    F: FnMut(&<I::Item as TryStream>::Ok) -> K,
+    K: Ord,
+{
+    let sources = Sources::new(streams);
+    let mut heads = Vec::new();
+    heads.resize_with(sources.streams.len(), || None);
+    TryMergeSortedByKey { sources, heads, heap: BinaryHeap::new(), f }
+}
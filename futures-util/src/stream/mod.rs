@@ -18,28 +18,38 @@ pub use futures_core::stream::{FusedStream, Stream, TryStream};
 #[allow(clippy::module_inception)]
 mod stream;
 pub use self::stream::{
-    Chain, Collect, Concat, Cycle, Enumerate, Filter, FilterMap, FlatMap, Flatten, Fold, ForEach,
-    Fuse, Inspect, Map, Next, NextIf, NextIfEq, Peek, Peekable, Scan, SelectNextSome, Skip,
-    SkipWhile, StreamExt, StreamFuture, Take, TakeUntil, TakeWhile, Then, TryFold, TryForEach,
-    Unzip, Zip,
+    All, Any, Chain, Collect, Concat, Count, Cycle, Dedup, DedupByKey, EitherOrBoth, Enumerate,
+    Filter, FilterMap, Find, FindMap, FlatMap, Flatten, Fold, ForEach, Fuse, Inspect, Last, Map,
+    MaxByKey, MinByKey, Next, NextIf, NextIfEq, Nth, Peek, Peekable, Position, Reduce, Scan,
+    SelectNextSome, Skip, SkipWhile, StreamExt, StreamFuture, SwitchMap, Take, TakeUntil,
+    TakeWhile, Then, ThenLatest, TryFold, TryForEach, Unzip, Zip, ZipLongest,
 };
 
 #[cfg(feature = "std")]
-pub use self::stream::CatchUnwind;
+pub use self::stream::{
+    CatchUnwind, Debounce, Group, GroupByKey, Partition, RateLimit, Route, Sample, Shared, Throttle,
+};
 
 #[cfg(feature = "alloc")]
-pub use self::stream::Chunks;
+pub use self::stream::{ChunkBy, Chunks};
 
 #[cfg(feature = "alloc")]
 pub use self::stream::ReadyChunks;
 
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "alloc")]
+pub use self::stream::{Windows, WindowsUntil};
+
 #[cfg(feature = "sink")]
 #[cfg_attr(docsrs, doc(cfg(feature = "sink")))]
 pub use self::stream::Forward;
 
 #[cfg(not(futures_no_atomic_cas))]
 #[cfg(feature = "alloc")]
-pub use self::stream::{BufferUnordered, Buffered, ForEachConcurrent, TryForEachConcurrent};
+pub use self::stream::{
+    BufferUnordered, Buffered, FlatMapUnordered, FlattenUnordered, ForEachConcurrent,
+    TryForEachConcurrent,
+};
 
 #[cfg(not(futures_no_atomic_cas))]
 #[cfg(feature = "sink")]
@@ -49,11 +59,15 @@ pub use self::stream::{ReuniteError, SplitSink, SplitStream};
 
 mod try_stream;
 pub use self::try_stream::{
-    try_unfold, AndThen, ErrInto, InspectErr, InspectOk, IntoStream, MapErr, MapOk, OrElse,
-    TryCollect, TryConcat, TryFilter, TryFilterMap, TryFlatten, TryNext, TrySkipWhile,
-    TryStreamExt, TryTakeWhile, TryUnfold,
+    try_unfold, AndThen, ErrInto, InspectErr, InspectOk, IntoStream, MapErr, MapOk, OrElse, TryAll,
+    TryAny, TryCollect, TryConcat, TryCount, TryFilter, TryFilterMap, TryFind, TryFindMap,
+    TryFlatten, TryLast, TryMaxByKey, TryMinByKey, TryNext, TryNth, TryPosition, TryReduce,
+    TrySkipWhile, TryStreamExt, TryTakeWhile, TryUnfold,
 };
 
+#[cfg(feature = "std")]
+pub use self::try_stream::RetryWith;
+
 #[cfg(feature = "io")]
 #[cfg_attr(docsrs, doc(cfg(feature = "io")))]
 #[cfg(feature = "std")]
@@ -61,13 +75,21 @@ pub use self::try_stream::IntoAsyncRead;
 
 #[cfg(not(futures_no_atomic_cas))]
 #[cfg(feature = "alloc")]
-pub use self::try_stream::{TryBufferUnordered, TryBuffered};
+pub use self::try_stream::{
+    TryBufferUnordered, TryBuffered, TryFlatMapUnordered, TryFlattenUnordered,
+};
 
 #[cfg(feature = "alloc")]
 pub use self::try_stream::{TryChunks, TryChunksError};
 
 // Primitive streams
 
+#[cfg(feature = "std")]
+#[cfg(feature = "async-await-macro")]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "std", feature = "async-await-macro"))))]
+#[doc(inline)]
+pub use crate::{__async_stream as stream, __async_try_stream as try_stream};
+
 mod iter;
 pub use self::iter::{iter, Iter};
 
@@ -121,6 +143,19 @@ pub mod select_all;
 #[doc(inline)]
 pub use self::select_all::{select_all, SelectAll};
 
+#[cfg(feature = "alloc")]
+mod merge_sorted;
+#[cfg(feature = "alloc")]
+pub use self::merge_sorted::{
+    merge_sorted, merge_sorted_by_key, try_merge_sorted, try_merge_sorted_by_key, MergeSorted,
+    MergeSortedByKey, TryMergeSorted, TryMergeSortedByKey,
+};
+
+#[cfg(feature = "alloc")]
+mod zip_all;
+#[cfg(feature = "alloc")]
+pub use self::zip_all::{zip_all, ZipAll};
+
 #[cfg(not(futures_no_atomic_cas))]
 #[cfg(feature = "alloc")]
 mod abortable;
@@ -139,3 +174,7 @@ where
 {
     stream
 }
+
+// How many items a combinator takes from a stream that is always ready within
+// a single poll before it wakes itself and yields to the executor.
+pub(crate) const YIELD_EVERY: usize = 32;
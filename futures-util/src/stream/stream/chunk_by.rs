@@ -0,0 +1,113 @@
+use crate::stream::Fuse;
+use alloc::vec::Vec;
+use core::fmt;
+use core::pin::Pin;
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+#[cfg(feature = "sink")]
+use futures_sink::Sink;
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// Stream for the [`chunk_by`](super::StreamExt::chunk_by) method.
+    #[must_use = "streams do nothing unless polled"]
+    pub struct ChunkBy<St: Stream, K, F> {
+        #[pin]
+        stream: Fuse<St>,
+        f: F,
+        chunk: Option<(K, Vec<St::Item>)>,
+    }
+}
+
+impl<St, K, F> fmt::Debug for ChunkBy<St, K, F>
+where
+    St: Stream + fmt::Debug,
+    St::Item: fmt::Debug,
+    K: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChunkBy").field("stream", &self.stream).field("chunk", &self.chunk).finish()
+    }
+}
+
+impl<St, K, F> ChunkBy<St, K, F>
+where
+    St: Stream,
+    F: FnMut(&St::Item) -> K,
+    K: PartialEq,
+{
+    pub(super) fn new(stream: St, f: F) -> Self {
+        Self { stream: super::Fuse::new(stream), f, chunk: None }
+    }
+
+    delegate_access_inner!(stream, St, (.));
+}
+
+impl<St, K, F> Stream for ChunkBy<St, K, F>
+where
+    St: Stream,
+    F: FnMut(&St::Item) -> K,
+    K: PartialEq,
+{
+    type Item = (K, Vec<St::Item>);
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        loop {
+            match ready!(this.stream.as_mut().poll_next(cx)) {
+                // Add the item to the current chunk if it has the same key,
+                // otherwise start a new chunk and return the finished one.
+                Some(item) => {
+                    let key = (this.f)(&item);
+                    match this.chunk {
+                        Some((k, items)) if *k == key => items.push(item),
+                        _ => {
+                            let chunk = this.chunk.replace((key, alloc::vec![item]));
+                            if chunk.is_some() {
+                                return Poll::Ready(chunk);
+                            }
+                        }
+                    }
+                }
+
+                // Since the underlying stream ran out of values, return the
+                // last chunk, if we have one.
+                None => return Poll::Ready(this.chunk.take()),
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let chunk_len = if self.chunk.is_some() { 1 } else { 0 };
+        let (lower, upper) = self.stream.size_hint();
+        let lower = if lower > 0 || chunk_len > 0 { 1 } else { 0 };
+        let upper = match upper {
+            Some(x) => x.checked_add(chunk_len),
+            None => None,
+        };
+        (lower, upper)
+    }
+}
+
+impl<St, K, F> FusedStream for ChunkBy<St, K, F>
+where
+    St: Stream,
+    F: FnMut(&St::Item) -> K,
+    K: PartialEq,
+{
+    fn is_terminated(&self) -> bool {
+        self.stream.is_terminated() && self.chunk.is_none()
+    }
+}
+
+// Forwarding impl of Sink from the underlying stream
+#[cfg(feature = "sink")]
+impl<S, K, F, Item> Sink<Item> for ChunkBy<S, K, F>
+where
+    S: Stream + Sink<Item>,
+{
+    type Error = S::Error;
+
+    delegate_sink!(stream, Item);
+}
@@ -0,0 +1,117 @@
+use crate::future::Sleep;
+use crate::stream::{Fuse, StreamExt, YIELD_EVERY};
+use core::fmt;
+use core::pin::Pin;
+use futures_core::future::Future;
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+#[cfg(feature = "sink")]
+use futures_sink::Sink;
+use pin_project_lite::pin_project;
+use std::time::Duration;
+
+pin_project! {
+    /// Stream for the [`debounce`](super::StreamExt::debounce) method.
+    #[must_use = "streams do nothing unless polled"]
+    pub struct Debounce<St: Stream, S: Sleep> {
+        #[pin]
+        stream: Fuse<St>,
+        #[pin]
+        delay: Option<S::Future>,
+        item: Option<St::Item>,
+        quiet_period: Duration,
+        sleep: S,
+    }
+}
+
+impl<St, S> fmt::Debug for Debounce<St, S>
+where
+    St: Stream + fmt::Debug,
+    St::Item: fmt::Debug,
+    S: Sleep,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Debounce")
+            .field("stream", &self.stream)
+            .field("item", &self.item)
+            .field("quiet_period", &self.quiet_period)
+            .finish()
+    }
+}
+
+impl<St: Stream, S: Sleep> Debounce<St, S> {
+    pub(super) fn new(stream: St, quiet_period: Duration, sleep: S) -> Self {
+        Self { stream: stream.fuse(), delay: None, item: None, quiet_period, sleep }
+    }
+
+    delegate_access_inner!(stream, St, (.));
+}
+
+impl<St: Stream, S: Sleep> FusedStream for Debounce<St, S> {
+    fn is_terminated(&self) -> bool {
+        self.item.is_none() && self.stream.is_terminated()
+    }
+}
+
+impl<St: Stream, S: Sleep> Stream for Debounce<St, S> {
+    type Item = St::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        let mut pulled = 0;
+        loop {
+            match this.stream.as_mut().poll_next(cx) {
+                Poll::Ready(Some(item)) => {
+                    *this.item = Some(item);
+                    pulled += 1;
+                    if pulled == YIELD_EVERY {
+                        // The stream may always be ready, let other tasks
+                        // run before taking more items from it.
+                        cx.waker().wake_by_ref();
+                        break;
+                    }
+                }
+                Poll::Ready(None) => {
+                    this.delay.set(None);
+                    return Poll::Ready(this.item.take());
+                }
+                Poll::Pending => break,
+            }
+        }
+        // A newer item restarts the quiet period.
+        if pulled > 0 {
+            this.delay.set(Some(this.sleep.sleep(*this.quiet_period)));
+        }
+
+        if let Some(delay) = this.delay.as_mut().as_pin_mut() {
+            ready!(delay.poll(cx));
+            this.delay.set(None);
+            return Poll::Ready(this.item.take());
+        }
+        Poll::Pending
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let item_len = if self.item.is_some() { 1 } else { 0 };
+        let (lower, upper) = self.stream.size_hint();
+        let upper = match upper {
+            Some(x) => x.checked_add(item_len),
+            None => None,
+        };
+        (lower.saturating_add(item_len).min(1), upper)
+    }
+}
+
+// Forwarding impl of Sink from the underlying stream
+#[cfg(feature = "sink")]
+impl<St, S, Item> Sink<Item> for Debounce<St, S>
+where
+    St: Stream + Sink<Item>,
+    S: Sleep,
+{
+    type Error = St::Error;
+
+    delegate_sink!(stream, Item);
+}
@@ -0,0 +1,84 @@
+use core::pin::Pin;
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+#[cfg(feature = "sink")]
+use futures_sink::Sink;
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// Stream for the [`dedup`](super::StreamExt::dedup) method.
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct Dedup<St: Stream> {
+        #[pin]
+        stream: St,
+        last: Option<St::Item>,
+    }
+}
+
+impl<St> Dedup<St>
+where
+    St: Stream,
+    St::Item: PartialEq + Clone,
+{
+    pub(super) fn new(stream: St) -> Self {
+        Self { stream, last: None }
+    }
+
+    delegate_access_inner!(stream, St, ());
+}
+
+impl<St> FusedStream for Dedup<St>
+where
+    St: FusedStream,
+    St::Item: PartialEq + Clone,
+{
+    fn is_terminated(&self) -> bool {
+        self.stream.is_terminated()
+    }
+}
+
+impl<St> Stream for Dedup<St>
+where
+    St: Stream,
+    St::Item: PartialEq + Clone,
+{
+    type Item = St::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<St::Item>> {
+        let mut this = self.project();
+        Poll::Ready(loop {
+            match ready!(this.stream.as_mut().poll_next(cx)) {
+                Some(item) => {
+                    if this.last.as_ref() != Some(&item) {
+                        *this.last = Some(item.clone());
+                        break Some(item);
+                    }
+                }
+                None => {
+                    *this.last = None;
+                    break None;
+                }
+            }
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.stream.size_hint();
+        // every item may be a duplicate of the last one yielded
+        let lower = if self.last.is_some() { 0 } else { lower.min(1) };
+        (lower, upper)
+    }
+}
+
+// Forwarding impl of Sink from the underlying stream
+#[cfg(feature = "sink")]
+impl<S, Item> Sink<Item> for Dedup<S>
+where
+    S: Stream + Sink<Item>,
+{
+    type Error = S::Error;
+
+    delegate_sink!(stream, Item);
+}
@@ -0,0 +1,102 @@
+use core::fmt;
+use core::pin::Pin;
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+#[cfg(feature = "sink")]
+use futures_sink::Sink;
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// Stream for the [`dedup_by_key`](super::StreamExt::dedup_by_key) method.
+    #[must_use = "streams do nothing unless polled"]
+    pub struct DedupByKey<St, K, F> {
+        #[pin]
+        stream: St,
+        f: F,
+        last: Option<K>,
+    }
+}
+
+impl<St, K, F> fmt::Debug for DedupByKey<St, K, F>
+where
+    St: fmt::Debug,
+    K: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DedupByKey")
+            .field("stream", &self.stream)
+            .field("last", &self.last)
+            .finish()
+    }
+}
+
+impl<St, K, F> DedupByKey<St, K, F>
+where
+    St: Stream,
+    F: FnMut(&St::Item) -> K,
+    K: PartialEq,
+{
+    pub(super) fn new(stream: St, f: F) -> Self {
+        Self { stream, f, last: None }
+    }
+
+    delegate_access_inner!(stream, St, ());
+}
+
+impl<St, K, F> FusedStream for DedupByKey<St, K, F>
+where
+    St: FusedStream,
+    F: FnMut(&St::Item) -> K,
+    K: PartialEq,
+{
+    fn is_terminated(&self) -> bool {
+        self.stream.is_terminated()
+    }
+}
+
+impl<St, K, F> Stream for DedupByKey<St, K, F>
+where
+    St: Stream,
+    F: FnMut(&St::Item) -> K,
+    K: PartialEq,
+{
+    type Item = St::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<St::Item>> {
+        let mut this = self.project();
+        Poll::Ready(loop {
+            match ready!(this.stream.as_mut().poll_next(cx)) {
+                Some(item) => {
+                    let key = (this.f)(&item);
+                    if this.last.as_ref() != Some(&key) {
+                        *this.last = Some(key);
+                        break Some(item);
+                    }
+                }
+                None => {
+                    *this.last = None;
+                    break None;
+                }
+            }
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.stream.size_hint();
+        // every item may be a duplicate of the last one yielded
+        let lower = if self.last.is_some() { 0 } else { lower.min(1) };
+        (lower, upper)
+    }
+}
+
+// Forwarding impl of Sink from the underlying stream
+#[cfg(feature = "sink")]
+impl<S, K, F, Item> Sink<Item> for DedupByKey<S, K, F>
+where
+    S: Stream + Sink<Item>,
+{
+    type Error = S::Error;
+
+    delegate_sink!(stream, Item);
+}
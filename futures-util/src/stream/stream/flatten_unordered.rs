@@ -0,0 +1,123 @@
+use crate::stream::{Fuse, FuturesUnordered, StreamExt, StreamFuture, YIELD_EVERY};
+use alloc::boxed::Box;
+use core::fmt;
+use core::num::NonZeroUsize;
+use core::pin::Pin;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+#[cfg(feature = "sink")]
+use futures_sink::Sink;
+use pin_project_lite::pin_project;
+
+pin_project! {
+    #[must_use = "streams do nothing unless polled"]
+    pub struct FlattenUnordered<St, U> {
+        #[pin]
+        stream: Fuse<St>,
+        inner_streams: FuturesUnordered<StreamFuture<Pin<Box<U>>>>,
+        limit: Option<NonZeroUsize>,
+    }
+}
+
+impl<St, U> fmt::Debug for FlattenUnordered<St, U>
+where
+    St: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FlattenUnordered")
+            .field("stream", &self.stream)
+            .field("inner_streams", &self.inner_streams.len())
+            .field("limit", &self.limit)
+            .finish()
+    }
+}
+
+impl<St, U> FlattenUnordered<St, U> {
+    pub(super) fn new(stream: St, limit: Option<usize>) -> Self {
+        Self {
+            stream: super::Fuse::new(stream),
+            inner_streams: FuturesUnordered::new(),
+            limit: limit.and_then(NonZeroUsize::new),
+        }
+    }
+
+    delegate_access_inner!(stream, St, (.));
+}
+
+impl<St, U> Stream for FlattenUnordered<St, U>
+where
+    St: Stream<Item = U>,
+    U: Stream,
+{
+    type Item = U::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        let mut pulled = 0;
+        loop {
+            // Start polling as many inner streams as the limit allows.
+            while this.limit.map(|limit| this.inner_streams.len() < limit.get()).unwrap_or(true) {
+                if pulled == YIELD_EVERY {
+                    // The stream may always be ready, let other tasks run
+                    // before taking more streams from it.
+                    cx.waker().wake_by_ref();
+                    break;
+                }
+                match this.stream.as_mut().poll_next(cx) {
+                    Poll::Ready(Some(stream)) => {
+                        pulled += 1;
+                        this.inner_streams.push(Box::pin(stream).into_future())
+                    }
+                    Poll::Ready(None) | Poll::Pending => break,
+                }
+            }
+
+            match this.inner_streams.poll_next_unpin(cx) {
+                Poll::Ready(Some((Some(item), stream))) => {
+                    this.inner_streams.push(stream.into_future());
+                    return Poll::Ready(Some(item));
+                }
+                // An inner stream has ended, which may make room for another
+                // one.
+                Poll::Ready(Some((None, _))) => {}
+                Poll::Ready(None) => {
+                    // If more streams are still coming from the stream, we're
+                    // not done yet
+                    return if this.stream.is_done() { Poll::Ready(None) } else { Poll::Pending };
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.stream.is_done() && self.inner_streams.is_empty() {
+            (0, Some(0))
+        } else {
+            (0, None)
+        }
+    }
+}
+
+impl<St, U> FusedStream for FlattenUnordered<St, U>
+where
+    St: Stream<Item = U>,
+    U: Stream,
+{
+    fn is_terminated(&self) -> bool {
+        self.stream.is_terminated() && self.inner_streams.is_empty()
+    }
+}
+
+// Forwarding impl of Sink from the underlying stream
+#[cfg(feature = "sink")]
+impl<S, U, Item> Sink<Item> for FlattenUnordered<S, U>
+where
+    S: Stream<Item = U> + Sink<Item>,
+    U: Stream,
+{
+    type Error = S::Error;
+
+    delegate_sink!(stream, Item);
+}
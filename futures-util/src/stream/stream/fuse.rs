@@ -18,7 +18,7 @@ pin_project! {
 }
 
 impl<St> Fuse<St> {
-    pub(super) fn new(stream: St) -> Self {
+    pub(crate) fn new(stream: St) -> Self {
         Self { stream, done: false }
     }
 
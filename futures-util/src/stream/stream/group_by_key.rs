@@ -0,0 +1,321 @@
+use super::notifier::{Notifier, NULL_WAKER_KEY};
+use super::upstream::{poll_unlocked, Upstream};
+use crate::stream::YIELD_EVERY;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::hash::Hash;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Stream for the [`group_by_key`](super::StreamExt::group_by_key) method.
+#[must_use = "streams do nothing unless polled"]
+pub struct GroupByKey<St: Stream, K: Eq + Hash, F> {
+    inner: Arc<Inner<St, K, F>>,
+    waker_key: usize,
+}
+
+/// Stream of the items of one group of a
+/// [`GroupByKey`](super::GroupByKey) stream.
+#[must_use = "streams do nothing unless polled"]
+pub struct Group<St: Stream, K: Eq + Hash, F> {
+    inner: Arc<Inner<St, K, F>>,
+    key: K,
+    waker_key: usize,
+}
+
+struct Inner<St: Stream, K: Eq + Hash, F> {
+    state: Mutex<State<St, K, F>>,
+    notifier: Arc<Notifier>,
+}
+
+struct State<St: Stream, K: Eq + Hash, F> {
+    upstream: Upstream<(Pin<Box<St>>, F)>,
+    limit: usize,
+    // The buffered items of every group seen so far, or `None` once its
+    // `Group` has been dropped.
+    groups: HashMap<K, Option<VecDeque<St::Item>>>,
+    // The groups that haven't been yielded yet.
+    new_groups: VecDeque<K>,
+    // The number of groups whose buffer is full.
+    full: usize,
+    outer_dropped: bool,
+}
+
+// Neither the groups nor `GroupByKey` own the stream, which is boxed in
+// their shared state.
+impl<St: Stream, K: Eq + Hash, F> Unpin for GroupByKey<St, K, F> {}
+
+impl<St: Stream, K: Eq + Hash, F> Unpin for Group<St, K, F> {}
+
+impl<St: Stream, K: Eq + Hash, F> fmt::Debug for GroupByKey<St, K, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GroupByKey").finish()
+    }
+}
+
+impl<St: Stream, K: Eq + Hash + fmt::Debug, F> fmt::Debug for Group<St, K, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Group").field("key", &self.key).finish()
+    }
+}
+
+impl<St, K, F> GroupByKey<St, K, F>
+where
+    St: Stream,
+    K: Clone + Eq + Hash,
+    F: FnMut(&St::Item) -> K,
+{
+    pub(super) fn new(stream: St, limit: usize, f: F) -> Self {
+        assert!(limit > 0);
+        let state = State {
+            upstream: Upstream::Idle((Box::pin(stream), f)),
+            limit,
+            groups: HashMap::new(),
+            new_groups: VecDeque::new(),
+            full: 0,
+            outer_dropped: false,
+        };
+        let inner = Inner { state: Mutex::new(state), notifier: Arc::new(Notifier::new()) };
+
+        Self { inner: Arc::new(inner), waker_key: NULL_WAKER_KEY }
+    }
+}
+
+impl<St: Stream, K: Eq + Hash, F> Group<St, K, F> {
+    /// Returns the key of the items of this group.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+}
+
+impl<St: Stream, K: Eq + Hash, F> Inner<St, K, F> {
+    fn lock(&self) -> MutexGuard<'_, State<St, K, F>> {
+        self.state.lock().expect("inner stream panicked during poll")
+    }
+}
+
+impl<St, K, F> Inner<St, K, F>
+where
+    St: Stream,
+    K: Clone + Eq + Hash,
+    F: FnMut(&St::Item) -> K,
+{
+    /// Polls the stream for one item and hands it to its group.
+    ///
+    /// The lock is released while the stream and the closure run, and
+    /// `pulled` counts the items taken from the stream during this poll.
+    fn poll_stream(
+        &self,
+        mut guard: MutexGuard<'_, State<St, K, F>>,
+        waker_key: usize,
+        cx: &mut Context<'_>,
+        pulled: &mut usize,
+    ) -> Poll<()> {
+        // Wait for the groups to be consumed before buffering more items.
+        if guard.full > 0 || guard.new_groups.len() >= guard.limit {
+            return Poll::Pending;
+        }
+        if *pulled == YIELD_EVERY {
+            // The items may all go to dropped groups, let other tasks run
+            // before taking more items from the stream.
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+
+        // Another handle may be polling the stream, and wakes this one once
+        // it's done.
+        let (mut stream, mut f) = match guard.upstream.take() {
+            Some(upstream) => upstream,
+            None => return Poll::Pending,
+        };
+        drop(guard);
+
+        let poll = poll_unlocked(
+            &self.state,
+            |state| &mut state.upstream,
+            &self.notifier,
+            |cx| match stream.as_mut().poll_next(cx) {
+                Poll::Ready(Some(item)) => Poll::Ready(Some((f(&item), item))),
+                Poll::Ready(None) => Poll::Ready(None),
+                Poll::Pending => Poll::Pending,
+            },
+        );
+        *pulled += 1;
+
+        let mut guard = self.lock();
+        let state = &mut *guard;
+        match poll {
+            Poll::Ready(Some((key, item))) => {
+                state.upstream = Upstream::Idle((stream, f));
+                match state.groups.get_mut(&key) {
+                    Some(Some(items)) => {
+                        items.push_back(item);
+                        if items.len() == state.limit {
+                            state.full += 1;
+                        }
+                    }
+                    // Nobody is interested in this group anymore.
+                    Some(None) => {}
+                    None if state.outer_dropped => {
+                        state.groups.insert(key, None);
+                    }
+                    None => {
+                        let mut items = VecDeque::new();
+                        items.push_back(item);
+                        if state.limit == 1 {
+                            state.full += 1;
+                        }
+                        state.groups.insert(key.clone(), Some(items));
+                        state.new_groups.push_back(key);
+                    }
+                }
+            }
+            Poll::Ready(None) => state.upstream = Upstream::Done,
+            Poll::Pending => {
+                state.upstream = Upstream::Idle((stream, f));
+                return Poll::Pending;
+            }
+        }
+        drop(guard);
+        self.notifier.wake_all_except(waker_key);
+        Poll::Ready(())
+    }
+}
+
+impl<St: Stream, K: Eq + Hash, F> State<St, K, F> {
+    /// Forgets about the items of a group nobody will see.
+    fn drop_group(&mut self, key: &K) {
+        if let Some(slot) = self.groups.get_mut(key) {
+            if let Some(items) = slot.take() {
+                if items.len() >= self.limit {
+                    self.full -= 1;
+                }
+            }
+        }
+    }
+}
+
+impl<St, K, F> Stream for GroupByKey<St, K, F>
+where
+    St: Stream,
+    K: Clone + Eq + Hash,
+    F: FnMut(&St::Item) -> K,
+{
+    type Item = (K, Group<St, K, F>);
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let mut pulled = 0;
+
+        loop {
+            let mut state = this.inner.lock();
+            if let Some(key) = state.new_groups.pop_front() {
+                // There's room for another new group now.
+                this.inner.notifier.wake_all_except(this.waker_key);
+                let group = Group {
+                    inner: this.inner.clone(),
+                    key: key.clone(),
+                    waker_key: NULL_WAKER_KEY,
+                };
+                return Poll::Ready(Some((key, group)));
+            }
+            if state.upstream.is_done() {
+                return Poll::Ready(None);
+            }
+
+            this.inner.notifier.record_waker(&mut this.waker_key, cx);
+            if this.inner.poll_stream(state, this.waker_key, cx, &mut pulled).is_pending() {
+                return Poll::Pending;
+            }
+        }
+    }
+}
+
+impl<St, K, F> FusedStream for GroupByKey<St, K, F>
+where
+    St: Stream,
+    K: Clone + Eq + Hash,
+    F: FnMut(&St::Item) -> K,
+{
+    fn is_terminated(&self) -> bool {
+        let state = self.inner.lock();
+        state.upstream.is_done() && state.new_groups.is_empty()
+    }
+}
+
+impl<St: Stream, K: Eq + Hash, F> Drop for GroupByKey<St, K, F> {
+    fn drop(&mut self) {
+        self.inner.notifier.remove_waker(self.waker_key);
+        if let Ok(mut state) = self.inner.state.lock() {
+            state.outer_dropped = true;
+            // The groups that haven't been yielded will never be seen.
+            while let Some(key) = state.new_groups.pop_front() {
+                state.drop_group(&key);
+            }
+            self.inner.notifier.wake_all_except(NULL_WAKER_KEY);
+        }
+    }
+}
+
+impl<St, K, F> Stream for Group<St, K, F>
+where
+    St: Stream,
+    K: Clone + Eq + Hash,
+    F: FnMut(&St::Item) -> K,
+{
+    type Item = St::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let mut pulled = 0;
+
+        loop {
+            let mut guard = this.inner.lock();
+            let state = &mut *guard;
+            let items = state.groups.get_mut(&this.key).and_then(Option::as_mut);
+            if let Some(items) = items {
+                if let Some(item) = items.pop_front() {
+                    if items.len() + 1 == state.limit {
+                        // There's room in this group's buffer now.
+                        state.full -= 1;
+                        this.inner.notifier.wake_all_except(this.waker_key);
+                    }
+                    return Poll::Ready(Some(item));
+                }
+            }
+            if state.upstream.is_done() {
+                return Poll::Ready(None);
+            }
+
+            this.inner.notifier.record_waker(&mut this.waker_key, cx);
+            if this.inner.poll_stream(guard, this.waker_key, cx, &mut pulled).is_pending() {
+                return Poll::Pending;
+            }
+        }
+    }
+}
+
+impl<St, K, F> FusedStream for Group<St, K, F>
+where
+    St: Stream,
+    K: Clone + Eq + Hash,
+    F: FnMut(&St::Item) -> K,
+{
+    fn is_terminated(&self) -> bool {
+        let state = self.inner.lock();
+        let empty = state.groups[&self.key].as_ref().map_or(true, VecDeque::is_empty);
+        state.upstream.is_done() && empty
+    }
+}
+
+impl<St: Stream, K: Eq + Hash, F> Drop for Group<St, K, F> {
+    fn drop(&mut self) {
+        self.inner.notifier.remove_waker(self.waker_key);
+        if let Ok(mut state) = self.inner.state.lock() {
+            state.drop_group(&self.key);
+            self.inner.notifier.wake_all_except(NULL_WAKER_KEY);
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use core::fmt;
+use core::pin::Pin;
+use futures_core::future::{FusedFuture, Future};
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// Future for the [`last`](super::StreamExt::last) method.
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct Last<St>
+        where St: Stream,
+    {
+        #[pin]
+        stream: St,
+        last: Option<St::Item>,
+    }
+}
+
+impl<St> fmt::Debug for Last<St>
+where
+    St: Stream + fmt::Debug,
+    St::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Last").field("stream", &self.stream).field("last", &self.last).finish()
+    }
+}
+
+impl<St: Stream> Last<St> {
+    pub(super) fn new(stream: St) -> Self {
+        Self { stream, last: None }
+    }
+}
+
+impl<St: FusedStream> FusedFuture for Last<St> {
+    fn is_terminated(&self) -> bool {
+        self.stream.is_terminated()
+    }
+}
+
+impl<St: Stream> Future for Last<St> {
+    type Output = Option<St::Item>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        while let Some(item) = ready!(this.stream.as_mut().poll_next(cx)) {
+            *this.last = Some(item);
+        }
+        Poll::Ready(this.last.take())
+    }
+}
@@ -0,0 +1,74 @@
+use core::fmt;
+use core::pin::Pin;
+use futures_core::future::{FusedFuture, Future};
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// Future for the [`max_by_key`](super::StreamExt::max_by_key) method.
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct MaxByKey<St, K, F>
+        where St: Stream,
+    {
+        #[pin]
+        stream: St,
+        f: F,
+        max: Option<(K, St::Item)>,
+    }
+}
+
+impl<St, K, F> fmt::Debug for MaxByKey<St, K, F>
+where
+    St: Stream + fmt::Debug,
+    St::Item: fmt::Debug,
+    K: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MaxByKey").field("stream", &self.stream).field("max", &self.max).finish()
+    }
+}
+
+impl<St, K, F> MaxByKey<St, K, F>
+where
+    St: Stream,
+    F: FnMut(&St::Item) -> K,
+    K: Ord,
+{
+    pub(super) fn new(stream: St, f: F) -> Self {
+        Self { stream, f, max: None }
+    }
+}
+
+impl<St, K, F> FusedFuture for MaxByKey<St, K, F>
+where
+    St: FusedStream,
+    F: FnMut(&St::Item) -> K,
+    K: Ord,
+{
+    fn is_terminated(&self) -> bool {
+        self.stream.is_terminated()
+    }
+}
+
+impl<St, K, F> Future for MaxByKey<St, K, F>
+where
+    St: Stream,
+    F: FnMut(&St::Item) -> K,
+    K: Ord,
+{
+    type Output = Option<St::Item>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        while let Some(item) = ready!(this.stream.as_mut().poll_next(cx)) {
+            let key = (this.f)(&item);
+            // the last of several maximal items is kept
+            if this.max.as_ref().map_or(true, |(max, _)| key >= *max) {
+                *this.max = Some((key, item));
+            }
+        }
+        Poll::Ready(this.max.take().map(|(_, item)| item))
+    }
+}
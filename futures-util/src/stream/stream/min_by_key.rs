@@ -0,0 +1,74 @@
+use core::fmt;
+use core::pin::Pin;
+use futures_core::future::{FusedFuture, Future};
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// Future for the [`min_by_key`](super::StreamExt::min_by_key) method.
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct MinByKey<St, K, F>
+        where St: Stream,
+    {
+        #[pin]
+        stream: St,
+        f: F,
+        min: Option<(K, St::Item)>,
+    }
+}
+
+impl<St, K, F> fmt::Debug for MinByKey<St, K, F>
+where
+    St: Stream + fmt::Debug,
+    St::Item: fmt::Debug,
+    K: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MinByKey").field("stream", &self.stream).field("min", &self.min).finish()
+    }
+}
+
+impl<St, K, F> MinByKey<St, K, F>
+where
+    St: Stream,
+    F: FnMut(&St::Item) -> K,
+    K: Ord,
+{
+    pub(super) fn new(stream: St, f: F) -> Self {
+        Self { stream, f, min: None }
+    }
+}
+
+impl<St, K, F> FusedFuture for MinByKey<St, K, F>
+where
+    St: FusedStream,
+    F: FnMut(&St::Item) -> K,
+    K: Ord,
+{
+    fn is_terminated(&self) -> bool {
+        self.stream.is_terminated()
+    }
+}
+
+impl<St, K, F> Future for MinByKey<St, K, F>
+where
+    St: Stream,
+    F: FnMut(&St::Item) -> K,
+    K: Ord,
+{
+    type Output = Option<St::Item>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        while let Some(item) = ready!(this.stream.as_mut().poll_next(cx)) {
+            let key = (this.f)(&item);
+            // the first of several minimal items is kept
+            if this.min.as_ref().map_or(true, |(min, _)| key < *min) {
+                *this.min = Some((key, item));
+            }
+        }
+        Poll::Ready(this.min.take().map(|(_, item)| item))
+    }
+}
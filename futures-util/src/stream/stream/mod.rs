@@ -3,10 +3,15 @@
 //! This module contains a number of functions for working with `Stream`s,
 //! including the `StreamExt` trait which adds methods to `Stream` types.
 
+#[cfg(feature = "std")]
+use crate::future::Sleep;
 use crate::future::{assert_future, Either};
 use crate::stream::assert_stream;
 #[cfg(feature = "alloc")]
 use alloc::boxed::Box;
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "alloc")]
+use alloc::sync::Arc;
 #[cfg(feature = "alloc")]
 use alloc::vec::Vec;
 use core::pin::Pin;
@@ -21,6 +26,8 @@ use futures_core::{
 };
 #[cfg(feature = "sink")]
 use futures_sink::Sink;
+#[cfg(feature = "std")]
+use std::time::Duration;
 
 use crate::fns::{inspect_fn, InspectFn};
 
@@ -66,6 +73,50 @@ delegate_all!(
     where St: Stream
 );
 
+mod all;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::all::All;
+
+mod any;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::any::Any;
+
+mod count;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::count::Count;
+
+mod find;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::find::Find;
+
+mod find_map;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::find_map::FindMap;
+
+mod last;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::last::Last;
+
+mod max_by_key;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::max_by_key::MaxByKey;
+
+mod min_by_key;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::min_by_key::MinByKey;
+
+mod nth;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::nth::Nth;
+
+mod position;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::position::Position;
+
+mod reduce;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::reduce::Reduce;
+
 mod fold;
 #[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
 pub use self::fold::Fold;
@@ -149,6 +200,14 @@ mod then;
 #[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
 pub use self::then::Then;
 
+mod then_latest;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::then_latest::ThenLatest;
+
+mod switch_map;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::switch_map::SwitchMap;
+
 mod try_for_each;
 #[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
 pub use self::try_for_each::TryForEach;
@@ -161,6 +220,10 @@ mod zip;
 #[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
 pub use self::zip::Zip;
 
+mod zip_longest;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::zip_longest::{EitherOrBoth, ZipLongest};
+
 #[cfg(feature = "alloc")]
 mod chunks;
 #[cfg(feature = "alloc")]
@@ -173,6 +236,77 @@ mod ready_chunks;
 #[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
 pub use self::ready_chunks::ReadyChunks;
 
+#[cfg(feature = "alloc")]
+mod chunk_by;
+#[cfg(feature = "alloc")]
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::chunk_by::ChunkBy;
+
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "alloc")]
+mod windows;
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "alloc")]
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::windows::Windows;
+
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "alloc")]
+mod windows_until;
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "alloc")]
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::windows_until::WindowsUntil;
+
+#[cfg(feature = "std")]
+mod notifier;
+#[cfg(feature = "std")]
+mod upstream;
+
+#[cfg(feature = "std")]
+mod group_by_key;
+#[cfg(feature = "std")]
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::group_by_key::{Group, GroupByKey};
+
+#[cfg(feature = "std")]
+mod route;
+#[cfg(feature = "std")]
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::route::{Partition, Route};
+
+#[cfg(feature = "std")]
+mod throttle;
+#[cfg(feature = "std")]
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::throttle::Throttle;
+
+#[cfg(feature = "std")]
+mod debounce;
+#[cfg(feature = "std")]
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::debounce::Debounce;
+
+#[cfg(feature = "std")]
+mod sample;
+#[cfg(feature = "std")]
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::sample::Sample;
+
+#[cfg(feature = "std")]
+mod rate_limit;
+#[cfg(feature = "std")]
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::rate_limit::RateLimit;
+
+mod dedup;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::dedup::Dedup;
+
+mod dedup_by_key;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::dedup_by_key::DedupByKey;
+
 mod scan;
 #[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
 pub use self::scan::Scan;
@@ -185,6 +319,29 @@ mod buffer_unordered;
 #[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
 pub use self::buffer_unordered::BufferUnordered;
 
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "alloc")]
+mod flatten_unordered;
+
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "alloc")]
+delegate_all!(
+    /// Stream for the [`flatten_unordered`](StreamExt::flatten_unordered) method.
+    FlattenUnordered<St>(
+        flatten_unordered::FlattenUnordered<St, St::Item>
+    ): Debug + Sink + Stream + FusedStream + AccessInner[St, (.)] + New[|x: St, limit: Option<usize>| flatten_unordered::FlattenUnordered::new(x, limit)]
+    where St: Stream
+);
+
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "alloc")]
+delegate_all!(
+    /// Stream for the [`flat_map_unordered`](StreamExt::flat_map_unordered) method.
+    FlatMapUnordered<St, U, F>(
+        flatten_unordered::FlattenUnordered<Map<St, F>, U>
+    ): Debug + Sink + Stream + FusedStream + AccessInner[St, (. .)] + New[|x: St, limit: Option<usize>, f: F| flatten_unordered::FlattenUnordered::new(Map::new(x, f), limit)]
+);
+
 #[cfg(not(futures_no_atomic_cas))]
 #[cfg(feature = "alloc")]
 mod buffered;
@@ -227,6 +384,12 @@ mod catch_unwind;
 #[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
 pub use self::catch_unwind::CatchUnwind;
 
+#[cfg(feature = "std")]
+mod shared;
+#[cfg(feature = "std")]
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::shared::Shared;
+
 impl<T: ?Sized> StreamExt for T where T: Stream {}
 
 /// An extension trait for `Stream`s that provides a variety of convenient
@@ -473,6 +636,36 @@ pub trait StreamExt: Stream {
         assert_stream::<Fut::Output, _>(Then::new(self, f))
     }
 
+    /// Computes new items like [`then`](StreamExt::then), but only for the
+    /// latest item of this stream.
+    ///
+    /// Whenever this stream yields a new item before the future of the
+    /// previous one has completed, that future is dropped and replaced with
+    /// the one returned by `f` for the new item. The items this stream has
+    /// ready are consumed before the current future is polled, and `f` is
+    /// only called for the last of them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let stream = stream::iter(1..=3);
+    /// let stream = stream.then_latest(|x| async move { x + 3 });
+    ///
+    /// assert_eq!(vec![6], stream.collect::<Vec<_>>().await);
+    /// # });
+    /// ```
+    fn then_latest<Fut, F>(self, f: F) -> ThenLatest<Self, Fut, F>
+    where
+        F: FnMut(Self::Item) -> Fut,
+        Fut: Future,
+        Self: Sized,
+    {
+        assert_stream::<Fut::Output, _>(ThenLatest::new(self, f))
+    }
+
     /// Transforms a stream into a collection, returning a
     /// future representing the result of that computation.
     ///
@@ -643,57 +836,32 @@ pub trait StreamExt: Stream {
         assert_future::<T, _>(Fold::new(self, f, init))
     }
 
-    /// Flattens a stream of streams into just one continuous stream.
+    /// Returns the number of items produced by this stream, consuming it.
     ///
     /// # Examples
     ///
     /// ```
     /// # futures::executor::block_on(async {
-    /// use futures::channel::mpsc;
-    /// use futures::stream::StreamExt;
-    /// use std::thread;
-    ///
-    /// let (tx1, rx1) = mpsc::unbounded();
-    /// let (tx2, rx2) = mpsc::unbounded();
-    /// let (tx3, rx3) = mpsc::unbounded();
-    ///
-    /// thread::spawn(move || {
-    ///     tx1.unbounded_send(1).unwrap();
-    ///     tx1.unbounded_send(2).unwrap();
-    /// });
-    /// thread::spawn(move || {
-    ///     tx2.unbounded_send(3).unwrap();
-    ///     tx2.unbounded_send(4).unwrap();
-    /// });
-    /// thread::spawn(move || {
-    ///     tx3.unbounded_send(rx1).unwrap();
-    ///     tx3.unbounded_send(rx2).unwrap();
-    /// });
+    /// use futures::stream::{self, StreamExt};
     ///
-    /// let output = rx3.flatten().collect::<Vec<i32>>().await;
-    /// assert_eq!(output, vec![1, 2, 3, 4]);
+    /// let stream = stream::iter(vec![1, 2, 3]);
+    /// assert_eq!(stream.count().await, 3);
     /// # });
     /// ```
-    fn flatten(self) -> Flatten<Self>
+    fn count(self) -> Count<Self>
     where
-        Self::Item: Stream,
         Self: Sized,
     {
-        assert_stream::<<Self::Item as Stream>::Item, _>(Flatten::new(self))
+        assert_future::<usize, _>(Count::new(self))
     }
 
-    /// Maps a stream like [`StreamExt::map`] but flattens nested `Stream`s.
+    /// Checks whether the provided asynchronous predicate holds for any item
+    /// of this stream.
     ///
-    /// [`StreamExt::map`] is very useful, but if it produces a `Stream` instead,
-    /// you would have to chain combinators like `.map(f).flatten()` while this
-    /// combinator provides ability to write `.flat_map(f)` instead of chaining.
-    ///
-    /// The provided closure which produce inner streams is executed over all elements
-    /// of stream as last inner stream is terminated and next stream item is available.
-    ///
-    /// Note that this function consumes the stream passed into it and returns a
-    /// wrapped version of it, similar to the existing `flat_map` methods in the
-    /// standard library.
+    /// The predicate is called for each item until it resolves to `true`, in
+    /// which case the returned future resolves to `true` without polling the
+    /// stream any further. If the stream ends first, the future resolves to
+    /// `false`.
     ///
     /// # Examples
     ///
@@ -701,31 +869,28 @@ pub trait StreamExt: Stream {
     /// # futures::executor::block_on(async {
     /// use futures::stream::{self, StreamExt};
     ///
-    /// let stream = stream::iter(1..=3);
-    /// let stream = stream.flat_map(|x| stream::iter(vec![x + 3; x]));
-    ///
-    /// assert_eq!(vec![4, 5, 5, 6, 6, 6], stream.collect::<Vec<_>>().await);
+    /// let mut stream = stream::iter(1..=5);
+    /// assert!(stream.by_ref().any(|x| async move { x % 2 == 0 }).await);
+    /// // the stream is not polled past the first match
+    /// assert_eq!(stream.next().await, Some(3));
     /// # });
     /// ```
-    fn flat_map<U, F>(self, f: F) -> FlatMap<Self, U, F>
+    fn any<Fut, F>(self, f: F) -> Any<Self, Fut, F>
     where
-        F: FnMut(Self::Item) -> U,
-        U: Stream,
+        F: FnMut(Self::Item) -> Fut,
+        Fut: Future<Output = bool>,
         Self: Sized,
     {
-        assert_stream::<U::Item, _>(FlatMap::new(self, f))
+        assert_future::<bool, _>(Any::new(self, f))
     }
 
-    /// Combinator similar to [`StreamExt::fold`] that holds internal state
-    /// and produces a new stream.
-    ///
-    /// Accepts initial state and closure which will be applied to each element
-    /// of the stream until provided closure returns `None`. Once `None` is
-    /// returned, stream will be terminated.
+    /// Checks whether the provided asynchronous predicate holds for all items
+    /// of this stream.
     ///
-    /// Unlike [`Iterator::scan`], the closure takes the state by value instead of
-    /// mutable reference to avoid [the limitation of the async
-    /// block](https://github.com/rust-lang/futures-rs/issues/2171).
+    /// The predicate is called for each item until it resolves to `false`, in
+    /// which case the returned future resolves to `false` without polling the
+    /// stream any further. If the stream ends first, the future resolves to
+    /// `true`.
     ///
     /// # Examples
     ///
@@ -733,138 +898,615 @@ pub trait StreamExt: Stream {
     /// # futures::executor::block_on(async {
     /// use futures::stream::{self, StreamExt};
     ///
-    /// let stream = stream::iter(1..=10);
-    ///
-    /// let stream = stream.scan(0, |mut state, x| async move {
-    ///     state += x;
-    ///     if state < 10 {
-    ///         Some((state, x))
-    ///     } else {
-    ///         None
-    ///     }
-    /// });
-    ///
-    /// assert_eq!(vec![1, 2, 3], stream.collect::<Vec<_>>().await);
+    /// assert!(stream::iter(vec![2, 4, 6]).all(|x| async move { x % 2 == 0 }).await);
+    /// assert!(!stream::iter(vec![2, 3, 6]).all(|x| async move { x % 2 == 0 }).await);
     /// # });
     /// ```
-    fn scan<S, B, Fut, F>(self, initial_state: S, f: F) -> Scan<Self, S, Fut, F>
+    fn all<Fut, F>(self, f: F) -> All<Self, Fut, F>
     where
-        F: FnMut(S, Self::Item) -> Fut,
-        Fut: Future<Output = Option<(S, B)>>,
+        F: FnMut(Self::Item) -> Fut,
+        Fut: Future<Output = bool>,
         Self: Sized,
     {
-        assert_stream::<B, _>(Scan::new(self, initial_state, f))
+        assert_future::<bool, _>(All::new(self, f))
     }
 
-    /// Skip elements on this stream while the provided asynchronous predicate
-    /// resolves to `true`.
+    /// Returns the first item of this stream for which the provided
+    /// asynchronous predicate resolves to `true`.
     ///
-    /// This function, like `Iterator::skip_while`, will skip elements on the
-    /// stream until the predicate `f` resolves to `false`. Once one element
-    /// returns `false`, all future elements will be returned from the underlying
-    /// stream.
+    /// The stream is not polled any further once an item has been found. If
+    /// the stream ends first, the returned future resolves to `None`.
     ///
     /// # Examples
     ///
     /// ```
     /// # futures::executor::block_on(async {
-    /// use futures::future;
     /// use futures::stream::{self, StreamExt};
     ///
     /// let stream = stream::iter(1..=10);
-    ///
-    /// let stream = stream.skip_while(|x| future::ready(*x <= 5));
-    ///
-    /// assert_eq!(vec![6, 7, 8, 9, 10], stream.collect::<Vec<_>>().await);
+    /// assert_eq!(stream.find(|x| futures::future::ready(x * x > 20)).await, Some(5));
     /// # });
     /// ```
-    fn skip_while<Fut, F>(self, f: F) -> SkipWhile<Self, Fut, F>
+    fn find<Fut, F>(self, f: F) -> Find<Self, Fut, F>
     where
         F: FnMut(&Self::Item) -> Fut,
         Fut: Future<Output = bool>,
         Self: Sized,
     {
-        assert_stream::<Self::Item, _>(SkipWhile::new(self, f))
+        assert_future::<Option<Self::Item>, _>(Find::new(self, f))
     }
 
-    /// Take elements from this stream while the provided asynchronous predicate
-    /// resolves to `true`.
+    /// Applies the provided asynchronous closure to the items of this stream
+    /// and returns the first non-`None` result.
     ///
-    /// This function, like `Iterator::take_while`, will take elements from the
-    /// stream until the predicate `f` resolves to `false`. Once one element
-    /// returns `false`, it will always return that the stream is done.
+    /// The stream is not polled any further once a result has been found. If
+    /// the stream ends first, the returned future resolves to `None`.
     ///
     /// # Examples
     ///
     /// ```
     /// # futures::executor::block_on(async {
-    /// use futures::future;
     /// use futures::stream::{self, StreamExt};
     ///
-    /// let stream = stream::iter(1..=10);
-    ///
-    /// let stream = stream.take_while(|x| future::ready(*x <= 5));
-    ///
-    /// assert_eq!(vec![1, 2, 3, 4, 5], stream.collect::<Vec<_>>().await);
+    /// let stream = stream::iter(vec!["one", "2", "three", "4"]);
+    /// let first_number = stream.find_map(|s| async move { s.parse::<i32>().ok() });
+    /// assert_eq!(first_number.await, Some(2));
     /// # });
     /// ```
-    fn take_while<Fut, F>(self, f: F) -> TakeWhile<Self, Fut, F>
+    fn find_map<Fut, F, T>(self, f: F) -> FindMap<Self, Fut, F>
     where
-        F: FnMut(&Self::Item) -> Fut,
-        Fut: Future<Output = bool>,
+        F: FnMut(Self::Item) -> Fut,
+        Fut: Future<Output = Option<T>>,
         Self: Sized,
     {
-        assert_stream::<Self::Item, _>(TakeWhile::new(self, f))
+        assert_future::<Option<T>, _>(FindMap::new(self, f))
     }
 
-    /// Take elements from this stream until the provided future resolves.
-    ///
-    /// This function will take elements from the stream until the provided
-    /// stopping future `fut` resolves. Once the `fut` future becomes ready,
-    /// this stream combinator will always return that the stream is done.
+    /// Returns the index of the first item of this stream for which the
+    /// provided asynchronous predicate resolves to `true`.
     ///
-    /// The stopping future may return any type. Once the stream is stopped
-    /// the result of the stopping future may be accessed with `TakeUntil::take_result()`.
-    /// The stream may also be resumed with `TakeUntil::take_future()`.
-    /// See the documentation of [`TakeUntil`] for more information.
+    /// The stream is not polled any further once an item has been found. If
+    /// the stream ends first, the returned future resolves to `None`.
     ///
     /// # Examples
     ///
     /// ```
     /// # futures::executor::block_on(async {
-    /// use futures::future;
     /// use futures::stream::{self, StreamExt};
-    /// use futures::task::Poll;
-    ///
-    /// let stream = stream::iter(1..=10);
-    ///
-    /// let mut i = 0;
-    /// let stop_fut = future::poll_fn(|_cx| {
-    ///     i += 1;
-    ///     if i <= 5 {
-    ///         Poll::Pending
-    ///     } else {
-    ///         Poll::Ready(())
-    ///     }
-    /// });
-    ///
-    /// let stream = stream.take_until(stop_fut);
     ///
-    /// assert_eq!(vec![1, 2, 3, 4, 5], stream.collect::<Vec<_>>().await);
+    /// let stream = stream::iter(vec![1, 3, 4, 5]);
+    /// assert_eq!(stream.position(|x| async move { x % 2 == 0 }).await, Some(2));
     /// # });
     /// ```
-    fn take_until<Fut>(self, fut: Fut) -> TakeUntil<Self, Fut>
+    fn position<Fut, F>(self, f: F) -> Position<Self, Fut, F>
     where
-        Fut: Future,
+        F: FnMut(Self::Item) -> Fut,
+        Fut: Future<Output = bool>,
         Self: Sized,
     {
-        assert_stream::<Self::Item, _>(TakeUntil::new(self, fut))
+        assert_future::<Option<usize>, _>(Position::new(self, f))
     }
 
-    /// Runs this stream to completion, executing the provided asynchronous
-    /// closure for each element on the stream.
+    /// Returns the last item of this stream, consuming it.
     ///
-    /// The closure provided will be called for each item this stream produces,
+    /// The returned future resolves to `None` if the stream is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// assert_eq!(stream::iter(1..=3).last().await, Some(3));
+    /// assert_eq!(stream::empty::<i32>().last().await, None);
+    /// # });
+    /// ```
+    fn last(self) -> Last<Self>
+    where
+        Self: Sized,
+    {
+        assert_future::<Option<Self::Item>, _>(Last::new(self))
+    }
+
+    /// Returns the `n`th item of this stream, counting from zero.
+    ///
+    /// The preceding items are discarded. Like [`next`](StreamExt::next),
+    /// this borrows the stream, so that the remaining items can be taken from
+    /// it afterwards. The returned future resolves to `None` if the stream
+    /// ends first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let mut stream = stream::iter(1..=5);
+    /// assert_eq!(stream.nth(1).await, Some(2));
+    /// assert_eq!(stream.nth(1).await, Some(4));
+    /// assert_eq!(stream.nth(1).await, None);
+    /// # });
+    /// ```
+    fn nth(&mut self, n: usize) -> Nth<'_, Self>
+    where
+        Self: Unpin,
+    {
+        assert_future::<Option<Self::Item>, _>(Nth::new(self, n))
+    }
+
+    /// Returns the item of this stream that gives the minimum value of the
+    /// provided key function.
+    ///
+    /// If several items are equally minimum, the first one is returned. The
+    /// returned future resolves to `None` if the stream is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let stream = stream::iter(vec![-3_i32, 0, 1, 5, -10]);
+    /// assert_eq!(stream.min_by_key(|x| x.abs()).await, Some(0));
+    /// # });
+    /// ```
+    fn min_by_key<K, F>(self, f: F) -> MinByKey<Self, K, F>
+    where
+        F: FnMut(&Self::Item) -> K,
+        K: Ord,
+        Self: Sized,
+    {
+        assert_future::<Option<Self::Item>, _>(MinByKey::new(self, f))
+    }
+
+    /// Returns the item of this stream that gives the maximum value of the
+    /// provided key function.
+    ///
+    /// If several items are equally maximum, the last one is returned. The
+    /// returned future resolves to `None` if the stream is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let stream = stream::iter(vec![-3_i32, 0, 1, 5, -10]);
+    /// assert_eq!(stream.max_by_key(|x| x.abs()).await, Some(-10));
+    /// # });
+    /// ```
+    fn max_by_key<K, F>(self, f: F) -> MaxByKey<Self, K, F>
+    where
+        F: FnMut(&Self::Item) -> K,
+        K: Ord,
+        Self: Sized,
+    {
+        assert_future::<Option<Self::Item>, _>(MaxByKey::new(self, f))
+    }
+
+    /// Reduces the items of this stream to a single one, by repeatedly
+    /// applying the provided asynchronous closure.
+    ///
+    /// This is like [`fold`](StreamExt::fold), but uses the first item as
+    /// the initial value. The returned future resolves to `None` if the
+    /// stream is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let sum = stream::iter(1..=4).reduce(|acc, x| async move { acc + x });
+    /// assert_eq!(sum.await, Some(10));
+    /// assert_eq!(stream::empty::<i32>().reduce(|acc, x| async move { acc + x }).await, None);
+    /// # });
+    /// ```
+    fn reduce<Fut, F>(self, f: F) -> Reduce<Self, Fut, F>
+    where
+        F: FnMut(Self::Item, Self::Item) -> Fut,
+        Fut: Future<Output = Self::Item>,
+        Self: Sized,
+    {
+        assert_future::<Option<Self::Item>, _>(Reduce::new(self, f))
+    }
+
+    /// Flattens a stream of streams into just one continuous stream.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::channel::mpsc;
+    /// use futures::stream::StreamExt;
+    /// use std::thread;
+    ///
+    /// let (tx1, rx1) = mpsc::unbounded();
+    /// let (tx2, rx2) = mpsc::unbounded();
+    /// let (tx3, rx3) = mpsc::unbounded();
+    ///
+    /// thread::spawn(move || {
+    ///     tx1.unbounded_send(1).unwrap();
+    ///     tx1.unbounded_send(2).unwrap();
+    /// });
+    /// thread::spawn(move || {
+    ///     tx2.unbounded_send(3).unwrap();
+    ///     tx2.unbounded_send(4).unwrap();
+    /// });
+    /// thread::spawn(move || {
+    ///     tx3.unbounded_send(rx1).unwrap();
+    ///     tx3.unbounded_send(rx2).unwrap();
+    /// });
+    ///
+    /// let output = rx3.flatten().collect::<Vec<i32>>().await;
+    /// assert_eq!(output, vec![1, 2, 3, 4]);
+    /// # });
+    /// ```
+    fn flatten(self) -> Flatten<Self>
+    where
+        Self::Item: Stream,
+        Self: Sized,
+    {
+        assert_stream::<<Self::Item as Stream>::Item, _>(Flatten::new(self))
+    }
+
+    /// Maps a stream like [`StreamExt::map`] but flattens nested `Stream`s.
+    ///
+    /// [`StreamExt::map`] is very useful, but if it produces a `Stream` instead,
+    /// you would have to chain combinators like `.map(f).flatten()` while this
+    /// combinator provides ability to write `.flat_map(f)` instead of chaining.
+    ///
+    /// The provided closure which produce inner streams is executed over all elements
+    /// of stream as last inner stream is terminated and next stream item is available.
+    ///
+    /// Note that this function consumes the stream passed into it and returns a
+    /// wrapped version of it, similar to the existing `flat_map` methods in the
+    /// standard library.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let stream = stream::iter(1..=3);
+    /// let stream = stream.flat_map(|x| stream::iter(vec![x + 3; x]));
+    ///
+    /// assert_eq!(vec![4, 5, 5, 6, 6, 6], stream.collect::<Vec<_>>().await);
+    /// # });
+    /// ```
+    fn flat_map<U, F>(self, f: F) -> FlatMap<Self, U, F>
+    where
+        F: FnMut(Self::Item) -> U,
+        U: Stream,
+        Self: Sized,
+    {
+        assert_stream::<U::Item, _>(FlatMap::new(self, f))
+    }
+
+    /// Maps a stream like [`flat_map`](StreamExt::flat_map), but switches to
+    /// the stream produced for each new item as soon as it arrives.
+    ///
+    /// Whenever this stream yields a new item, the inner stream currently
+    /// being flattened is dropped, even if it hasn't ended yet, and items are
+    /// only taken from the stream returned by `f` for the new item. The
+    /// items this stream has ready are consumed before the inner stream is
+    /// polled, and `f` is only called for the last of them.
+    ///
+    /// The returned stream ends once both this stream and the latest inner
+    /// stream have ended.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::channel::mpsc;
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let (tx, rx) = mpsc::unbounded();
+    /// let mut stream = rx.switch_map(|x| stream::iter(vec![x; 3]));
+    ///
+    /// tx.unbounded_send(1).unwrap();
+    /// assert_eq!(stream.next().await, Some(1));
+    /// tx.unbounded_send(2).unwrap();
+    /// drop(tx);
+    /// assert_eq!(vec![2, 2, 2], stream.collect::<Vec<_>>().await);
+    /// # });
+    /// ```
+    fn switch_map<U, F>(self, f: F) -> SwitchMap<Self, U, F>
+    where
+        F: FnMut(Self::Item) -> U,
+        U: Stream,
+        Self: Sized,
+    {
+        assert_stream::<U::Item, _>(SwitchMap::new(self, f))
+    }
+
+    /// Flattens a stream of streams into just one continuous stream, polling
+    /// up to `limit` inner streams concurrently.
+    ///
+    /// Unlike [`flatten`](StreamExt::flatten), which drains the inner streams
+    /// one after another, this adaptor starts polling the next inner stream
+    /// before the previous ones have ended, and yields items in the order in
+    /// which they become ready. At most `limit` inner streams are polled at
+    /// any point in time; the next one is taken from this stream once one of
+    /// them has ended.
+    ///
+    /// The limit argument is of type `Into<Option<usize>>`, and so can be
+    /// provided as either `None`, `Some(10)`, or just `10`. Note: a limit of zero is
+    /// interpreted as no limit at all, and will have the same result as passing in `None`.
+    ///
+    /// This method is only available when the `std` or `alloc` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::channel::mpsc;
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let (tx1, rx1) = mpsc::unbounded();
+    /// let (tx2, rx2) = mpsc::unbounded();
+    /// let mut flattened = stream::iter(vec![rx1, rx2]).flatten_unordered(2);
+    ///
+    /// // items of the second stream are yielded before the first one ends
+    /// tx2.unbounded_send(2)?;
+    /// assert_eq!(flattened.next().await, Some(2));
+    /// tx1.unbounded_send(1)?;
+    /// assert_eq!(flattened.next().await, Some(1));
+    ///
+    /// drop((tx1, tx2));
+    /// assert_eq!(flattened.next().await, None);
+    /// # Ok::<(), Box<dyn std::error::Error>>(()) }).unwrap();
+    /// ```
+    #[cfg(not(futures_no_atomic_cas))]
+    #[cfg(feature = "alloc")]
+    fn flatten_unordered(self, limit: impl Into<Option<usize>>) -> FlattenUnordered<Self>
+    where
+        Self::Item: Stream,
+        Self: Sized,
+    {
+        assert_stream::<<Self::Item as Stream>::Item, _>(FlattenUnordered::new(self, limit.into()))
+    }
+
+    /// Maps a stream like [`StreamExt::map`] but flattens nested `Stream`s,
+    /// polling up to `limit` of them concurrently.
+    ///
+    /// This is the concurrent version of [`flat_map`](StreamExt::flat_map);
+    /// see [`flatten_unordered`](StreamExt::flatten_unordered) for how the
+    /// inner streams are polled and how `limit` is interpreted.
+    ///
+    /// This method is only available when the `std` or `alloc` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let stream = stream::iter(1..=3);
+    /// let stream = stream.flat_map_unordered(None, |x| stream::iter(vec![x + 3; x]));
+    ///
+    /// let mut values = stream.collect::<Vec<_>>().await;
+    /// values.sort();
+    /// assert_eq!(vec![4, 5, 5, 6, 6, 6], values);
+    /// # });
+    /// ```
+    #[cfg(not(futures_no_atomic_cas))]
+    #[cfg(feature = "alloc")]
+    fn flat_map_unordered<U, F>(
+        self,
+        limit: impl Into<Option<usize>>,
+        f: F,
+    ) -> FlatMapUnordered<Self, U, F>
+    where
+        F: FnMut(Self::Item) -> U,
+        U: Stream,
+        Self: Sized,
+    {
+        assert_stream::<U::Item, _>(FlatMapUnordered::new(self, limit.into(), f))
+    }
+
+    /// Combinator similar to [`StreamExt::fold`] that holds internal state
+    /// and produces a new stream.
+    ///
+    /// Accepts initial state and closure which will be applied to each element
+    /// of the stream until provided closure returns `None`. Once `None` is
+    /// returned, stream will be terminated.
+    ///
+    /// Unlike [`Iterator::scan`], the closure takes the state by value instead of
+    /// mutable reference to avoid [the limitation of the async
+    /// block](https://github.com/rust-lang/futures-rs/issues/2171).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let stream = stream::iter(1..=10);
+    ///
+    /// let stream = stream.scan(0, |mut state, x| async move {
+    ///     state += x;
+    ///     if state < 10 {
+    ///         Some((state, x))
+    ///     } else {
+    ///         None
+    ///     }
+    /// });
+    ///
+    /// assert_eq!(vec![1, 2, 3], stream.collect::<Vec<_>>().await);
+    /// # });
+    /// ```
+    fn scan<S, B, Fut, F>(self, initial_state: S, f: F) -> Scan<Self, S, Fut, F>
+    where
+        F: FnMut(S, Self::Item) -> Fut,
+        Fut: Future<Output = Option<(S, B)>>,
+        Self: Sized,
+    {
+        assert_stream::<B, _>(Scan::new(self, initial_state, f))
+    }
+
+    /// Removes consecutive repeated items of this stream.
+    ///
+    /// Like [`Vec::dedup`], this only removes the items that are equal to the
+    /// item right before them. The last item yielded is cloned in order to
+    /// compare it with the next ones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let stream = stream::iter(vec![1, 1, 2, 3, 3, 3, 1]);
+    /// assert_eq!(stream.dedup().collect::<Vec<_>>().await, vec![1, 2, 3, 1]);
+    /// # });
+    /// ```
+    fn dedup(self) -> Dedup<Self>
+    where
+        Self::Item: PartialEq + Clone,
+        Self: Sized,
+    {
+        assert_stream::<Self::Item, _>(Dedup::new(self))
+    }
+
+    /// Removes consecutive items of this stream that resolve to the same key.
+    ///
+    /// Like [`Vec::dedup_by_key`], this only removes the items whose key is
+    /// equal to the key of the item right before them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let stream = stream::iter(vec![10, 15, 20, 31, 35, 12]);
+    /// let deduped = stream.dedup_by_key(|x| x / 10).collect::<Vec<_>>().await;
+    /// assert_eq!(deduped, vec![10, 20, 31, 12]);
+    /// # });
+    /// ```
+    fn dedup_by_key<K, F>(self, f: F) -> DedupByKey<Self, K, F>
+    where
+        F: FnMut(&Self::Item) -> K,
+        K: PartialEq,
+        Self: Sized,
+    {
+        assert_stream::<Self::Item, _>(DedupByKey::new(self, f))
+    }
+
+    /// Skip elements on this stream while the provided asynchronous predicate
+    /// resolves to `true`.
+    ///
+    /// This function, like `Iterator::skip_while`, will skip elements on the
+    /// stream until the predicate `f` resolves to `false`. Once one element
+    /// returns `false`, all future elements will be returned from the underlying
+    /// stream.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::future;
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let stream = stream::iter(1..=10);
+    ///
+    /// let stream = stream.skip_while(|x| future::ready(*x <= 5));
+    ///
+    /// assert_eq!(vec![6, 7, 8, 9, 10], stream.collect::<Vec<_>>().await);
+    /// # });
+    /// ```
+    fn skip_while<Fut, F>(self, f: F) -> SkipWhile<Self, Fut, F>
+    where
+        F: FnMut(&Self::Item) -> Fut,
+        Fut: Future<Output = bool>,
+        Self: Sized,
+    {
+        assert_stream::<Self::Item, _>(SkipWhile::new(self, f))
+    }
+
+    /// Take elements from this stream while the provided asynchronous predicate
+    /// resolves to `true`.
+    ///
+    /// This function, like `Iterator::take_while`, will take elements from the
+    /// stream until the predicate `f` resolves to `false`. Once one element
+    /// returns `false`, it will always return that the stream is done.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::future;
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let stream = stream::iter(1..=10);
+    ///
+    /// let stream = stream.take_while(|x| future::ready(*x <= 5));
+    ///
+    /// assert_eq!(vec![1, 2, 3, 4, 5], stream.collect::<Vec<_>>().await);
+    /// # });
+    /// ```
+    fn take_while<Fut, F>(self, f: F) -> TakeWhile<Self, Fut, F>
+    where
+        F: FnMut(&Self::Item) -> Fut,
+        Fut: Future<Output = bool>,
+        Self: Sized,
+    {
+        assert_stream::<Self::Item, _>(TakeWhile::new(self, f))
+    }
+
+    /// Take elements from this stream until the provided future resolves.
+    ///
+    /// This function will take elements from the stream until the provided
+    /// stopping future `fut` resolves. Once the `fut` future becomes ready,
+    /// this stream combinator will always return that the stream is done.
+    ///
+    /// The stopping future may return any type. Once the stream is stopped
+    /// the result of the stopping future may be accessed with `TakeUntil::take_result()`.
+    /// The stream may also be resumed with `TakeUntil::take_future()`.
+    /// See the documentation of [`TakeUntil`] for more information.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::future;
+    /// use futures::stream::{self, StreamExt};
+    /// use futures::task::Poll;
+    ///
+    /// let stream = stream::iter(1..=10);
+    ///
+    /// let mut i = 0;
+    /// let stop_fut = future::poll_fn(|_cx| {
+    ///     i += 1;
+    ///     if i <= 5 {
+    ///         Poll::Pending
+    ///     } else {
+    ///         Poll::Ready(())
+    ///     }
+    /// });
+    ///
+    /// let stream = stream.take_until(stop_fut);
+    ///
+    /// assert_eq!(vec![1, 2, 3, 4, 5], stream.collect::<Vec<_>>().await);
+    /// # });
+    /// ```
+    fn take_until<Fut>(self, fut: Fut) -> TakeUntil<Self, Fut>
+    where
+        Fut: Future,
+        Self: Sized,
+    {
+        assert_stream::<Self::Item, _>(TakeUntil::new(self, fut))
+    }
+
+    /// Runs this stream to completion, executing the provided asynchronous
+    /// closure for each element on the stream.
+    ///
+    /// The closure provided will be called for each item this stream produces,
     /// yielding a future. That future will then be executed to completion
     /// before moving on to the next item.
     ///
@@ -1266,6 +1908,50 @@ pub trait StreamExt: Stream {
         assert_stream(CatchUnwind::new(self))
     }
 
+    /// Shares this stream between several consumers.
+    ///
+    /// The returned [`Shared`] handle can be cloned, and every clone sees
+    /// every item the upstream yields from the point it was created on. The
+    /// items are kept in a replay buffer of up to `buffer` items until every
+    /// handle has seen them. Once the buffer is full, the upstream is not
+    /// polled until the slowest handle catches up, unless that handle was
+    /// made [`lagging`](Shared::lagging).
+    ///
+    /// The upstream is only polled by whichever handle is currently being
+    /// polled and has run out of buffered items, so no separate task is
+    /// needed to drive it.
+    ///
+    /// This method is only available when the `std` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `buffer` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::future;
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let stream = stream::iter(1..=3).shared(2);
+    /// let other = stream.clone();
+    ///
+    /// let (a, b) = future::join(stream.collect::<Vec<_>>(), other.collect::<Vec<_>>()).await;
+    /// assert_eq!(a, vec![1, 2, 3]);
+    /// assert_eq!(b, vec![1, 2, 3]);
+    /// # });
+    /// ```
+    #[cfg(feature = "std")]
+    fn shared(self, buffer: usize) -> Shared<Self>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        assert_stream(Shared::new(self, buffer))
+    }
+
     /// Wrap the stream in a Box, pinning it.
     ///
     /// This method is only available when the `std` or `alloc` feature of this
@@ -1364,37 +2050,68 @@ pub trait StreamExt: Stream {
         Self::Item: Future,
         Self: Sized,
     {
-        assert_stream::<<Self::Item as Future>::Output, _>(BufferUnordered::new(self, n.into()))
+        assert_stream::<<Self::Item as Future>::Output, _>(BufferUnordered::new(self, n.into()))
+    }
+
+    /// An adapter for zipping two streams together.
+    ///
+    /// The zipped stream waits for both streams to produce an item, and then
+    /// returns that pair. If either stream ends then the zipped stream will
+    /// also end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let stream1 = stream::iter(1..=3);
+    /// let stream2 = stream::iter(5..=10);
+    ///
+    /// let vec = stream1.zip(stream2)
+    ///                  .collect::<Vec<_>>()
+    ///                  .await;
+    /// assert_eq!(vec![(1, 5), (2, 6), (3, 7)], vec);
+    /// # });
+    /// ```
+    ///
+    fn zip<St>(self, other: St) -> Zip<Self, St>
+    where
+        St: Stream,
+        Self: Sized,
+    {
+        assert_stream::<(Self::Item, St::Item), _>(Zip::new(self, other))
     }
 
-    /// An adapter for zipping two streams together.
+    /// An adapter for zipping two streams together, until both of them end.
     ///
-    /// The zipped stream waits for both streams to produce an item, and then
-    /// returns that pair. If either stream ends then the zipped stream will
-    /// also end.
+    /// Like [`zip`](StreamExt::zip), this waits for both streams to produce
+    /// an item and returns them in an [`EitherOrBoth::Both`]. Once one of the
+    /// streams has ended, the remaining items of the other one are returned
+    /// on their own, in an [`EitherOrBoth::Left`] or [`EitherOrBoth::Right`].
     ///
     /// # Examples
     ///
     /// ```
     /// # futures::executor::block_on(async {
-    /// use futures::stream::{self, StreamExt};
+    /// use futures::stream::{self, EitherOrBoth, StreamExt};
     ///
     /// let stream1 = stream::iter(1..=3);
-    /// let stream2 = stream::iter(5..=10);
+    /// let stream2 = stream::iter(vec!["a"]);
     ///
-    /// let vec = stream1.zip(stream2)
-    ///                  .collect::<Vec<_>>()
-    ///                  .await;
-    /// assert_eq!(vec![(1, 5), (2, 6), (3, 7)], vec);
+    /// let vec = stream1.zip_longest(stream2).collect::<Vec<_>>().await;
+    /// assert_eq!(
+    ///     vec,
+    ///     vec![EitherOrBoth::Both(1, "a"), EitherOrBoth::Left(2), EitherOrBoth::Left(3)]
+    /// );
     /// # });
     /// ```
-    ///
-    fn zip<St>(self, other: St) -> Zip<Self, St>
+    fn zip_longest<St>(self, other: St) -> ZipLongest<Self, St>
     where
         St: Stream,
         Self: Sized,
     {
-        assert_stream::<(Self::Item, St::Item), _>(Zip::new(self, other))
+        assert_stream::<EitherOrBoth<Self::Item, St::Item>, _>(ZipLongest::new(self, other))
     }
 
     /// Adapter for chaining two streams.
@@ -1490,6 +2207,445 @@ pub trait StreamExt: Stream {
         assert_stream::<Vec<Self::Item>, _>(ReadyChunks::new(self, capacity))
     }
 
+    /// An adaptor for chunking up consecutive items of the stream that have
+    /// the same key.
+    ///
+    /// The provided closure computes the key of each item, and the items are
+    /// buffered until one with a different key shows up. Each chunk is then
+    /// yielded together with its key. Items with the same key that aren't
+    /// consecutive end up in different chunks, so this is most useful on
+    /// streams that are sorted by the key.
+    ///
+    /// This method is only available when the `std` or `alloc` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let stream = stream::iter(vec![1, 3, 2, 4, 6, 5]);
+    /// let chunks = stream.chunk_by(|x| x % 2).collect::<Vec<_>>().await;
+    /// assert_eq!(chunks, vec![(1, vec![1, 3]), (0, vec![2, 4, 6]), (1, vec![5])]);
+    /// # });
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn chunk_by<K, F>(self, f: F) -> ChunkBy<Self, K, F>
+    where
+        F: FnMut(&Self::Item) -> K,
+        K: PartialEq,
+        Self: Sized,
+    {
+        assert_stream::<(K, Vec<Self::Item>), _>(ChunkBy::new(self, f))
+    }
+
+    /// An adaptor for yielding overlapping windows of `size` consecutive
+    /// items of the stream.
+    ///
+    /// Like [`slice::windows`], a window is yielded for every item once
+    /// `size` items have been received, so each item shows up in up to
+    /// `size` windows. If the stream ends before `size` items have been
+    /// received, no window is yielded.
+    ///
+    /// Windows don't share their items: each one is a new allocation holding
+    /// clones of its `size` items, so yielding a window takes `O(size)` time.
+    /// Items that are expensive to clone can be wrapped in an `Arc` first.
+    ///
+    /// This method is only available when the `std` or `alloc` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `size` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let stream = stream::iter(1..=4);
+    /// let windows = stream.windows(3).collect::<Vec<_>>().await;
+    /// assert_eq!(windows, vec![vec![1, 2, 3].into(), vec![2, 3, 4].into()]);
+    /// # });
+    /// ```
+    #[cfg(not(futures_no_atomic_cas))]
+    #[cfg(feature = "alloc")]
+    fn windows(self, size: usize) -> Windows<Self>
+    where
+        Self::Item: Clone,
+        Self: Sized,
+    {
+        assert_stream::<Arc<[Self::Item]>, _>(Windows::new(self, 1, size))
+    }
+
+    /// An adaptor for yielding windows of `size` consecutive items of the
+    /// stream, each starting `step` items after the previous one.
+    ///
+    /// With a `step` of one, this is the same as
+    /// [`windows`](StreamExt::windows). With a `step` equal to `size`, this
+    /// yields tumbling windows, that don't overlap. With a larger `step`, the
+    /// items between the windows are skipped. Incomplete windows at the end of
+    /// the stream are not yielded. As with `windows`, each window holds its
+    /// own clones of its items.
+    ///
+    /// This method is only available when the `std` or `alloc` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `step` or `size` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let stream = stream::iter(1..=7);
+    /// let windows = stream.windows_by(2, 3).collect::<Vec<_>>().await;
+    /// assert_eq!(windows, vec![vec![1, 2, 3].into(), vec![3, 4, 5].into(), vec![5, 6, 7].into()]);
+    /// # });
+    /// ```
+    #[cfg(not(futures_no_atomic_cas))]
+    #[cfg(feature = "alloc")]
+    fn windows_by(self, step: usize, size: usize) -> Windows<Self>
+    where
+        Self::Item: Clone,
+        Self: Sized,
+    {
+        assert_stream::<Arc<[Self::Item]>, _>(Windows::new(self, step, size))
+    }
+
+    /// An adaptor for splitting the stream into windows that are closed by
+    /// the provided predicate.
+    ///
+    /// Items are buffered until one for which the predicate returns `true`,
+    /// which closes the current window: it is yielded including that item,
+    /// and the next item starts a new one. If the stream ends, the current
+    /// window is yielded if it isn't empty.
+    ///
+    /// This method is only available when the `std` or `alloc` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let stream = stream::iter(vec!["GET", "/", "\n", "POST", "/a", "\n", "HEAD"]);
+    /// let windows = stream.windows_until(|s| *s == "\n").collect::<Vec<_>>().await;
+    /// assert_eq!(
+    ///     windows,
+    ///     vec![vec!["GET", "/", "\n"].into(), vec!["POST", "/a", "\n"].into(), vec!["HEAD"].into()]
+    /// );
+    /// # });
+    /// ```
+    #[cfg(not(futures_no_atomic_cas))]
+    #[cfg(feature = "alloc")]
+    fn windows_until<F>(self, f: F) -> WindowsUntil<Self, F>
+    where
+        F: FnMut(&Self::Item) -> bool,
+        Self: Sized,
+    {
+        assert_stream::<Arc<[Self::Item]>, _>(WindowsUntil::new(self, f))
+    }
+
+    /// Yields an item, then discards the items of this stream until
+    /// `duration` has elapsed, as measured by the `sleep` timer.
+    ///
+    /// This limits the rate of a bursty stream to at most one item per
+    /// `duration`, keeping the first item of every burst. See
+    /// [`rate_limit`](StreamExt::rate_limit) to delay the items instead of
+    /// discarding them.
+    ///
+    /// This method is only available when the `std` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::stream::{self, StreamExt};
+    /// use futures_test::sim::{self, SimExecutor, Timer};
+    /// use std::time::Duration;
+    ///
+    /// let mut executor = SimExecutor::new(0);
+    /// // An item every 100 milliseconds.
+    /// let stream = stream::iter(0..10).then(|x| async move {
+    ///     sim::sleep(Duration::from_millis(100)).await;
+    ///     x
+    /// });
+    /// let stream = stream.throttle(Duration::from_millis(250), Timer);
+    ///
+    /// assert_eq!(executor.run_until(stream.collect::<Vec<_>>()), vec![0, 3, 6, 9]);
+    /// ```
+    #[cfg(feature = "std")]
+    fn throttle<S>(self, duration: Duration, sleep: S) -> Throttle<Self, S>
+    where
+        S: Sleep,
+        Self: Sized,
+    {
+        assert_stream::<Self::Item, _>(Throttle::new(self, duration, sleep))
+    }
+
+    /// Yields an item only once this stream hasn't yielded a newer one for
+    /// `quiet_period`, as measured by the `sleep` timer.
+    ///
+    /// Every item restarts the quiet period and replaces the previous one,
+    /// so only the last item of every burst is yielded. When this stream
+    /// ends, its last item is yielded right away.
+    ///
+    /// This method is only available when the `std` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::stream::{self, StreamExt};
+    /// use futures_test::sim::{self, SimExecutor, Timer};
+    /// use std::time::Duration;
+    ///
+    /// let mut executor = SimExecutor::new(0);
+    /// let keystrokes = vec![(10, "f"), (10, "fo"), (200, "foo"), (10, "fo"), (200, "f")];
+    /// let stream = stream::iter(keystrokes).then(|(delay, text)| async move {
+    ///     sim::sleep(Duration::from_millis(delay)).await;
+    ///     text
+    /// });
+    /// let stream = stream.debounce(Duration::from_millis(100), Timer);
+    ///
+    /// assert_eq!(executor.run_until(stream.collect::<Vec<_>>()), vec!["fo", "fo", "f"]);
+    /// ```
+    #[cfg(feature = "std")]
+    fn debounce<S>(self, quiet_period: Duration, sleep: S) -> Debounce<Self, S>
+    where
+        S: Sleep,
+        Self: Sized,
+    {
+        assert_stream::<Self::Item, _>(Debounce::new(self, quiet_period, sleep))
+    }
+
+    /// Yields the latest item of this stream once every `interval`, as
+    /// measured by the `sleep` timer.
+    ///
+    /// The other items are discarded, and nothing is yielded for an interval
+    /// in which this stream yielded nothing. When this stream ends, the item
+    /// it yielded during the last interval, if any, is yielded right away.
+    ///
+    /// This method is only available when the `std` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::stream::{self, StreamExt};
+    /// use futures_test::sim::{self, SimExecutor, Timer};
+    /// use std::time::Duration;
+    ///
+    /// let mut executor = SimExecutor::new(0);
+    /// // An item every 30 milliseconds.
+    /// let stream = stream::iter(0..8).then(|x| async move {
+    ///     sim::sleep(Duration::from_millis(30)).await;
+    ///     x
+    /// });
+    /// let stream = stream.sample(Duration::from_millis(100), Timer);
+    ///
+    /// assert_eq!(executor.run_until(stream.collect::<Vec<_>>()), vec![2, 5, 7]);
+    /// ```
+    #[cfg(feature = "std")]
+    fn sample<S>(self, interval: Duration, sleep: S) -> Sample<Self, S>
+    where
+        S: Sleep,
+        Self: Sized,
+    {
+        assert_stream::<Self::Item, _>(Sample::new(self, interval, sleep))
+    }
+
+    /// Delays the items of this stream so that it yields at most `burst`
+    /// items at once, and one item per `period` on average, as measured by
+    /// the `sleep` timer.
+    ///
+    /// This is a token bucket: it starts with `burst` tokens, yielding an
+    /// item takes one of them, and one is added back every `period` unless
+    /// the bucket is full. This stream isn't polled while the bucket is
+    /// empty, so no item is discarded.
+    ///
+    /// This method is only available when the `std` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `burst` or `period` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::stream::{self, StreamExt};
+    /// use futures_test::sim::{self, SimExecutor, Timer};
+    /// use std::time::Duration;
+    ///
+    /// let mut executor = SimExecutor::new(0);
+    /// let stream = stream::iter(0..5).rate_limit(2, Duration::from_millis(100), Timer);
+    /// let stream = stream.map(|x| (x, sim::now().as_millis()));
+    ///
+    /// assert_eq!(
+    ///     executor.run_until(stream.collect::<Vec<_>>()),
+    ///     vec![(0, 0), (1, 0), (2, 100), (3, 200), (4, 300)]
+    /// );
+    /// ```
+    #[cfg(feature = "std")]
+    fn rate_limit<S>(self, burst: usize, period: Duration, sleep: S) -> RateLimit<Self, S>
+    where
+        S: Sleep,
+        Self: Sized,
+    {
+        assert_stream::<Self::Item, _>(RateLimit::new(self, burst, period, sleep))
+    }
+
+    /// Splits this stream into one stream per key.
+    ///
+    /// The provided closure computes the key of each item. The first time a
+    /// key is seen, a [`Group`] stream is yielded together with it, which
+    /// yields all the items with that key. The groups and the returned stream
+    /// all poll this stream when they run out of items, so no separate task
+    /// is needed to drive it.
+    ///
+    /// Every group buffers up to `limit` items that haven't been consumed
+    /// yet, and at most `limit` new groups are buffered before they are
+    /// yielded. Once one of these buffers is full, this stream isn't polled
+    /// until there's room again, so the groups should be consumed
+    /// concurrently. The items of a group that has been dropped, and of the
+    /// groups first seen after the returned stream has been dropped, are
+    /// discarded.
+    ///
+    /// This method is only available when the `std` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `limit` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::future;
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let stream = stream::iter(vec![1, 2, 3, 4, 5]);
+    /// let mut groups = stream.group_by_key(3, |x| x % 2 == 0);
+    ///
+    /// let (odd, odd_group) = groups.next().await.unwrap();
+    /// let (even, even_group) = groups.next().await.unwrap();
+    /// assert!(!odd && even);
+    ///
+    /// let (odds, evens) =
+    ///     future::join(odd_group.collect::<Vec<_>>(), even_group.collect::<Vec<_>>()).await;
+    /// assert_eq!(odds, vec![1, 3, 5]);
+    /// assert_eq!(evens, vec![2, 4]);
+    /// # });
+    /// ```
+    #[cfg(feature = "std")]
+    fn group_by_key<K, F>(self, limit: usize, f: F) -> GroupByKey<Self, K, F>
+    where
+        F: FnMut(&Self::Item) -> K,
+        K: Clone + Eq + std::hash::Hash,
+        Self: Sized,
+    {
+        assert_stream::<(K, Group<Self, K, F>), _>(GroupByKey::new(self, limit, f))
+    }
+
+    /// Splits this stream into two streams of the items that match a
+    /// predicate and of the ones that don't.
+    ///
+    /// The first returned stream yields the items for which `f` returns
+    /// `true`, and the second one yields the others. Both share this stream,
+    /// which is polled by whichever of them needs an item.
+    ///
+    /// Every returned stream buffers up to `limit` items that haven't been
+    /// consumed yet. Once one of these buffers is full, this stream isn't
+    /// polled until there's room again, so both streams should be consumed
+    /// concurrently. The items for a stream that has been dropped are
+    /// discarded.
+    ///
+    /// This method is only available when the `std` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `limit` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::future;
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let stream = stream::iter(vec![1, 2, 3, 4, 5]);
+    /// let (evens, odds) = stream.partition(3, |x| x % 2 == 0);
+    ///
+    /// let (evens, odds) =
+    ///     future::join(evens.collect::<Vec<_>>(), odds.collect::<Vec<_>>()).await;
+    /// assert_eq!(evens, vec![2, 4]);
+    /// assert_eq!(odds, vec![1, 3, 5]);
+    /// # });
+    /// ```
+    #[cfg(feature = "std")]
+    fn partition<F>(self, limit: usize, f: F) -> (Partition<Self, F>, Partition<Self, F>)
+    where
+        F: FnMut(&Self::Item) -> bool,
+        Self: Sized,
+    {
+        let (accepted, rejected) = Partition::new(self, limit, f);
+        (assert_stream::<Self::Item, _>(accepted), assert_stream::<Self::Item, _>(rejected))
+    }
+
+    /// Splits this stream into `n` streams, routing every item to one of
+    /// them.
+    ///
+    /// Each item goes to the stream at index `f(&item) % n` in the returned
+    /// `Vec`, so `f` can be a hash of the item to shard it. All the returned
+    /// streams share this stream, which is polled by whichever of them needs
+    /// an item.
+    ///
+    /// Every returned stream buffers up to `limit` items that haven't been
+    /// consumed yet. Once one of these buffers is full, this stream isn't
+    /// polled until there's room again, so the streams should be consumed
+    /// concurrently. The items for a stream that has been dropped are
+    /// discarded.
+    ///
+    /// This method is only available when the `std` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `n` or `limit` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::future;
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let stream = stream::iter(0..9);
+    /// let shards = stream.route(3, 3, |x| *x as usize);
+    ///
+    /// let shards = future::join_all(shards.into_iter().map(|s| s.collect::<Vec<_>>())).await;
+    /// assert_eq!(shards, vec![vec![0, 3, 6], vec![1, 4, 7], vec![2, 5, 8]]);
+    /// # });
+    /// ```
+    #[cfg(feature = "std")]
+    fn route<F>(self, n: usize, limit: usize, f: F) -> Vec<Route<Self, F>>
+    where
+        F: FnMut(&Self::Item) -> usize,
+        Self: Sized,
+    {
+        Route::new(self, n, limit, f).into_iter().map(assert_stream::<Self::Item, _>).collect()
+    }
+
     /// A future that completes after the given stream has been fully processed
     /// into the sink and the sink has been flushed and closed.
     ///
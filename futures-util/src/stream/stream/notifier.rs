@@ -0,0 +1,67 @@
+use crate::task::ArcWake;
+use futures_core::task::{Context, Waker};
+use slab::Slab;
+use std::sync::{Arc, Mutex};
+
+/// The key of a handle that has not recorded a waker yet.
+pub(crate) const NULL_WAKER_KEY: usize = usize::max_value();
+
+/// Wakes the handles of a stream that several handles share.
+///
+/// Each handle records its waker under a key of its own. The notifier is
+/// also the waker the shared stream is polled with, so whichever handle
+/// polls it, all of them are woken when it makes progress.
+pub(crate) struct Notifier {
+    wakers: Mutex<Slab<Option<Waker>>>,
+}
+
+impl Notifier {
+    pub(crate) fn new() -> Self {
+        Self { wakers: Mutex::new(Slab::new()) }
+    }
+
+    pub(crate) fn record_waker(&self, waker_key: &mut usize, cx: &mut Context<'_>) {
+        let mut wakers = self.wakers.lock().unwrap();
+        let new_waker = cx.waker();
+
+        if *waker_key == NULL_WAKER_KEY {
+            *waker_key = wakers.insert(Some(new_waker.clone()));
+        } else {
+            match wakers[*waker_key] {
+                Some(ref old_waker) if new_waker.will_wake(old_waker) => {}
+                // Could use clone_from here, but Waker doesn't specialize it.
+                ref mut slot => *slot = Some(new_waker.clone()),
+            }
+        }
+    }
+
+    pub(crate) fn remove_waker(&self, waker_key: usize) {
+        if waker_key != NULL_WAKER_KEY {
+            if let Ok(mut wakers) = self.wakers.lock() {
+                wakers.remove(waker_key);
+            }
+        }
+    }
+
+    pub(crate) fn wake_all_except(&self, waker_key: usize) {
+        // The wakers are woken after the lock is released, as one of them
+        // may be this notifier: a handle polled by the stream records it.
+        let wakers = self
+            .wakers
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .filter(|(key, _)| *key != waker_key)
+            .filter_map(|(_, opt_waker)| opt_waker.take())
+            .collect::<Vec<_>>();
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+impl ArcWake for Notifier {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.wake_all_except(NULL_WAKER_KEY);
+    }
+}
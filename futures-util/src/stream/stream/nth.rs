@@ -0,0 +1,42 @@
+use crate::stream::StreamExt;
+use core::pin::Pin;
+use futures_core::future::{FusedFuture, Future};
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+
+/// Future for the [`nth`](super::StreamExt::nth) method.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Nth<'a, St: ?Sized> {
+    stream: &'a mut St,
+    n: usize,
+}
+
+impl<St: ?Sized + Unpin> Unpin for Nth<'_, St> {}
+
+impl<'a, St: ?Sized + Stream + Unpin> Nth<'a, St> {
+    pub(super) fn new(stream: &'a mut St, n: usize) -> Self {
+        Self { stream, n }
+    }
+}
+
+impl<St: ?Sized + FusedStream + Unpin> FusedFuture for Nth<'_, St> {
+    fn is_terminated(&self) -> bool {
+        self.stream.is_terminated()
+    }
+}
+
+impl<St: ?Sized + Stream + Unpin> Future for Nth<'_, St> {
+    type Output = Option<St::Item>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            match ready!(self.stream.poll_next_unpin(cx)) {
+                Some(item) if self.n == 0 => return Poll::Ready(Some(item)),
+                Some(_) => self.n -= 1,
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use crate::future::Sleep;
+use core::convert::TryFrom;
+use core::fmt;
+use core::pin::Pin;
+use futures_core::future::Future;
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+#[cfg(feature = "sink")]
+use futures_sink::Sink;
+use pin_project_lite::pin_project;
+use std::time::{Duration, Instant};
+
+pin_project! {
+    /// Stream for the [`rate_limit`](super::StreamExt::rate_limit) method.
+    #[must_use = "streams do nothing unless polled"]
+    pub struct RateLimit<St, S: Sleep> {
+        #[pin]
+        stream: St,
+        #[pin]
+        delay: Option<S::Future>,
+        burst: usize,
+        period: Duration,
+        tokens: usize,
+        // The time from which the next token is being refilled.
+        refilled_at: Option<Instant>,
+        sleep: S,
+    }
+}
+
+impl<St, S> fmt::Debug for RateLimit<St, S>
+where
+    St: fmt::Debug,
+    S: Sleep,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RateLimit")
+            .field("stream", &self.stream)
+            .field("burst", &self.burst)
+            .field("period", &self.period)
+            .field("tokens", &self.tokens)
+            .finish()
+    }
+}
+
+impl<St: Stream, S: Sleep> RateLimit<St, S> {
+    pub(super) fn new(stream: St, burst: usize, period: Duration, sleep: S) -> Self {
+        assert!(burst > 0);
+        assert!(period > Duration::from_secs(0));
+        Self { stream, delay: None, burst, period, tokens: burst, refilled_at: None, sleep }
+    }
+
+    delegate_access_inner!(stream, St, ());
+}
+
+impl<St: FusedStream, S: Sleep> FusedStream for RateLimit<St, S> {
+    fn is_terminated(&self) -> bool {
+        self.stream.is_terminated()
+    }
+}
+
+impl<St: Stream, S: Sleep> Stream for RateLimit<St, S> {
+    type Item = St::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            if let Some(delay) = this.delay.as_mut().as_pin_mut() {
+                ready!(delay.poll(cx));
+                this.delay.set(None);
+            }
+
+            let now = this.sleep.now();
+            let refilled_at = this.refilled_at.get_or_insert(now);
+            if *this.tokens == *this.burst {
+                // A full bucket doesn't keep refilling.
+                *refilled_at = now;
+            } else {
+                let elapsed = now.duration_since(*refilled_at);
+                let refills = elapsed.as_nanos() / this.period.as_nanos();
+                let missing = *this.burst - *this.tokens;
+                match u32::try_from(refills) {
+                    Ok(refills) if (refills as usize) < missing => {
+                        *this.tokens += refills as usize;
+                        *refilled_at += *this.period * refills;
+                    }
+                    _ => {
+                        *this.tokens = *this.burst;
+                        *refilled_at = now;
+                    }
+                }
+            }
+
+            if *this.tokens == 0 {
+                // Wait for the next token before taking an item.
+                let wait = *this.period - now.duration_since(*refilled_at);
+                this.delay.set(Some(this.sleep.sleep(wait)));
+                continue;
+            }
+
+            let item = ready!(this.stream.as_mut().poll_next(cx));
+            if item.is_some() {
+                *this.tokens -= 1;
+            }
+            return Poll::Ready(item);
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+// Forwarding impl of Sink from the underlying stream
+#[cfg(feature = "sink")]
+impl<St, S, Item> Sink<Item> for RateLimit<St, S>
+where
+    St: Stream + Sink<Item>,
+    S: Sleep,
+{
+    type Error = St::Error;
+
+    delegate_sink!(stream, Item);
+}
@@ -0,0 +1,95 @@
+use core::fmt;
+use core::pin::Pin;
+use futures_core::future::{FusedFuture, Future};
+use futures_core::ready;
+use futures_core::stream::Stream;
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// Future for the [`reduce`](super::StreamExt::reduce) method.
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct Reduce<St, Fut, F>
+        where St: Stream,
+    {
+        #[pin]
+        stream: St,
+        f: F,
+        accum: Option<St::Item>,
+        done: bool,
+        #[pin]
+        future: Option<Fut>,
+    }
+}
+
+impl<St, Fut, F> fmt::Debug for Reduce<St, Fut, F>
+where
+    St: Stream + fmt::Debug,
+    St::Item: fmt::Debug,
+    Fut: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Reduce")
+            .field("stream", &self.stream)
+            .field("accum", &self.accum)
+            .field("done", &self.done)
+            .field("future", &self.future)
+            .finish()
+    }
+}
+
+impl<St, Fut, F> Reduce<St, Fut, F>
+where
+    St: Stream,
+    F: FnMut(St::Item, St::Item) -> Fut,
+    Fut: Future<Output = St::Item>,
+{
+    pub(super) fn new(stream: St, f: F) -> Self {
+        Self { stream, f, accum: None, done: false, future: None }
+    }
+}
+
+impl<St, Fut, F> FusedFuture for Reduce<St, Fut, F>
+where
+    St: Stream,
+    F: FnMut(St::Item, St::Item) -> Fut,
+    Fut: Future<Output = St::Item>,
+{
+    fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
+
+impl<St, Fut, F> Future for Reduce<St, Fut, F>
+where
+    St: Stream,
+    F: FnMut(St::Item, St::Item) -> Fut,
+    Fut: Future<Output = St::Item>,
+{
+    type Output = Option<St::Item>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        Poll::Ready(loop {
+            if let Some(fut) = this.future.as_mut().as_pin_mut() {
+                // we're currently processing a future to produce a new accum value
+                *this.accum = Some(ready!(fut.poll(cx)));
+                this.future.set(None);
+            } else if !*this.done {
+                // we're waiting on a new item from the stream
+                match ready!(this.stream.as_mut().poll_next(cx)) {
+                    Some(item) => match this.accum.take() {
+                        Some(accum) => this.future.set(Some((this.f)(accum, item))),
+                        None => *this.accum = Some(item),
+                    },
+                    None => {
+                        *this.done = true;
+                        break this.accum.take();
+                    }
+                }
+            } else {
+                panic!("Reduce polled after completion")
+            }
+        })
+    }
+}
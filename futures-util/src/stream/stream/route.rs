@@ -0,0 +1,265 @@
+use super::notifier::{Notifier, NULL_WAKER_KEY};
+use super::upstream::{poll_unlocked, Upstream};
+use crate::stream::YIELD_EVERY;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+use std::collections::VecDeque;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Stream for the [`partition`](super::StreamExt::partition) method.
+#[must_use = "streams do nothing unless polled"]
+pub struct Partition<St: Stream, F> {
+    branch: Branch<St, F>,
+}
+
+/// Stream for the [`route`](super::StreamExt::route) method.
+#[must_use = "streams do nothing unless polled"]
+pub struct Route<St: Stream, F> {
+    branch: Branch<St, F>,
+}
+
+// One of the streams sharing the upstream, which yields the items routed to
+// the buffer at `index`.
+struct Branch<St: Stream, F> {
+    inner: Arc<Inner<St, F>>,
+    index: usize,
+    waker_key: usize,
+}
+
+struct Inner<St: Stream, F> {
+    state: Mutex<State<St, F>>,
+    notifier: Arc<Notifier>,
+}
+
+struct State<St: Stream, F> {
+    upstream: Upstream<(Pin<Box<St>>, F)>,
+    limit: usize,
+    // The buffered items of every branch, or `None` once it has been
+    // dropped.
+    buffers: Vec<Option<VecDeque<St::Item>>>,
+    // The number of branches whose buffer is full.
+    full: usize,
+}
+
+// Branches only reach the stream through the state they share, where it
+// is boxed.
+impl<St: Stream, F> Unpin for Partition<St, F> {}
+
+impl<St: Stream, F> Unpin for Route<St, F> {}
+
+impl<St: Stream, F> fmt::Debug for Partition<St, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Partition").field("index", &self.branch.index).finish()
+    }
+}
+
+impl<St: Stream, F> fmt::Debug for Route<St, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Route").field("index", &self.branch.index).finish()
+    }
+}
+
+impl<St, F> Partition<St, F>
+where
+    St: Stream,
+    F: FnMut(&St::Item) -> bool,
+{
+    pub(super) fn new(stream: St, limit: usize, f: F) -> (Self, Self) {
+        let mut branches = Branch::new(stream, 2, limit, f);
+        let rejected = Self { branch: branches.pop().unwrap() };
+        let accepted = Self { branch: branches.pop().unwrap() };
+        (accepted, rejected)
+    }
+}
+
+impl<St, F> Route<St, F>
+where
+    St: Stream,
+    F: FnMut(&St::Item) -> usize,
+{
+    pub(super) fn new(stream: St, n: usize, limit: usize, f: F) -> Vec<Self> {
+        assert!(n > 0);
+        Branch::new(stream, n, limit, f).into_iter().map(|branch| Self { branch }).collect()
+    }
+
+    /// Returns the index of this stream among the streams returned by
+    /// [`route`](super::StreamExt::route).
+    pub fn index(&self) -> usize {
+        self.branch.index
+    }
+}
+
+impl<St: Stream, F> Inner<St, F> {
+    fn lock(&self) -> MutexGuard<'_, State<St, F>> {
+        self.state.lock().expect("inner stream panicked during poll")
+    }
+}
+
+impl<St: Stream, F> Branch<St, F> {
+    fn new(stream: St, n: usize, limit: usize, f: F) -> Vec<Self> {
+        assert!(limit > 0);
+        let state = State {
+            upstream: Upstream::Idle((Box::pin(stream), f)),
+            limit,
+            buffers: (0..n).map(|_| Some(VecDeque::new())).collect(),
+            full: 0,
+        };
+        let inner =
+            Arc::new(Inner { state: Mutex::new(state), notifier: Arc::new(Notifier::new()) });
+
+        (0..n)
+            .map(|index| Self { inner: inner.clone(), index, waker_key: NULL_WAKER_KEY })
+            .collect()
+    }
+
+    /// Polls for the next item routed to this branch, using `route` to pick
+    /// the branch of each item taken from the upstream.
+    fn poll_next(
+        &mut self,
+        cx: &mut Context<'_>,
+        mut route: impl FnMut(&mut F, &St::Item) -> usize,
+    ) -> Poll<Option<St::Item>> {
+        let mut pulled = 0;
+        loop {
+            let mut guard = self.inner.lock();
+            let state = &mut *guard;
+
+            if let Some(items) = state.buffers[self.index].as_mut() {
+                if let Some(item) = items.pop_front() {
+                    if items.len() + 1 == state.limit {
+                        // There's room in this branch's buffer now.
+                        state.full -= 1;
+                        self.inner.notifier.wake_all_except(self.waker_key);
+                    }
+                    return Poll::Ready(Some(item));
+                }
+            }
+            if state.upstream.is_done() {
+                return Poll::Ready(None);
+            }
+
+            self.inner.notifier.record_waker(&mut self.waker_key, cx);
+            // Wait for the other branches to be consumed before buffering
+            // more items.
+            if state.full > 0 {
+                return Poll::Pending;
+            }
+            if pulled == YIELD_EVERY {
+                // The items may all go to dropped branches, let other tasks
+                // run before taking more items from the upstream.
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+
+            // Another branch may be polling the upstream, and wakes this one
+            // once it's done.
+            let (mut stream, mut f) = match state.upstream.take() {
+                Some(upstream) => upstream,
+                None => return Poll::Pending,
+            };
+            drop(guard);
+
+            let poll = poll_unlocked(
+                &self.inner.state,
+                |state| &mut state.upstream,
+                &self.inner.notifier,
+                |cx| match stream.as_mut().poll_next(cx) {
+                    Poll::Ready(Some(item)) => Poll::Ready(Some((route(&mut f, &item), item))),
+                    Poll::Ready(None) => Poll::Ready(None),
+                    Poll::Pending => Poll::Pending,
+                },
+            );
+            pulled += 1;
+
+            let mut guard = self.inner.lock();
+            let state = &mut *guard;
+            match poll {
+                Poll::Ready(Some((index, item))) => {
+                    state.upstream = Upstream::Idle((stream, f));
+                    let index = index % state.buffers.len();
+                    // The items of a dropped branch are discarded.
+                    if let Some(items) = state.buffers[index].as_mut() {
+                        items.push_back(item);
+                        if items.len() == state.limit {
+                            state.full += 1;
+                        }
+                    }
+                }
+                Poll::Ready(None) => state.upstream = Upstream::Done,
+                Poll::Pending => {
+                    state.upstream = Upstream::Idle((stream, f));
+                    return Poll::Pending;
+                }
+            }
+            drop(guard);
+            self.inner.notifier.wake_all_except(self.waker_key);
+        }
+    }
+
+    fn is_terminated(&self) -> bool {
+        let state = self.inner.lock();
+        let empty = state.buffers[self.index].as_ref().map_or(true, VecDeque::is_empty);
+        state.upstream.is_done() && empty
+    }
+}
+
+impl<St, F> Stream for Partition<St, F>
+where
+    St: Stream,
+    F: FnMut(&St::Item) -> bool,
+{
+    type Item = St::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.branch.poll_next(cx, |f, item| if f(item) { 0 } else { 1 })
+    }
+}
+
+impl<St, F> FusedStream for Partition<St, F>
+where
+    St: Stream,
+    F: FnMut(&St::Item) -> bool,
+{
+    fn is_terminated(&self) -> bool {
+        self.branch.is_terminated()
+    }
+}
+
+impl<St, F> Stream for Route<St, F>
+where
+    St: Stream,
+    F: FnMut(&St::Item) -> usize,
+{
+    type Item = St::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.branch.poll_next(cx, |f, item| f(item))
+    }
+}
+
+impl<St, F> FusedStream for Route<St, F>
+where
+    St: Stream,
+    F: FnMut(&St::Item) -> usize,
+{
+    fn is_terminated(&self) -> bool {
+        self.branch.is_terminated()
+    }
+}
+
+impl<St: Stream, F> Drop for Branch<St, F> {
+    fn drop(&mut self) {
+        self.inner.notifier.remove_waker(self.waker_key);
+        if let Ok(mut state) = self.inner.state.lock() {
+            let limit = state.limit;
+            if let Some(items) = state.buffers[self.index].take() {
+                if items.len() >= limit {
+                    state.full -= 1;
+                }
+            }
+            self.inner.notifier.wake_all_except(NULL_WAKER_KEY);
+        }
+    }
+}
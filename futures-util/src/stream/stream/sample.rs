@@ -0,0 +1,117 @@
+use crate::future::Sleep;
+use crate::stream::{Fuse, StreamExt, YIELD_EVERY};
+use core::fmt;
+use core::pin::Pin;
+use futures_core::future::Future;
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+#[cfg(feature = "sink")]
+use futures_sink::Sink;
+use pin_project_lite::pin_project;
+use std::time::Duration;
+
+pin_project! {
+    /// Stream for the [`sample`](super::StreamExt::sample) method.
+    #[must_use = "streams do nothing unless polled"]
+    pub struct Sample<St: Stream, S: Sleep> {
+        #[pin]
+        stream: Fuse<St>,
+        #[pin]
+        delay: Option<S::Future>,
+        item: Option<St::Item>,
+        interval: Duration,
+        sleep: S,
+    }
+}
+
+impl<St, S> fmt::Debug for Sample<St, S>
+where
+    St: Stream + fmt::Debug,
+    St::Item: fmt::Debug,
+    S: Sleep,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sample")
+            .field("stream", &self.stream)
+            .field("item", &self.item)
+            .field("interval", &self.interval)
+            .finish()
+    }
+}
+
+impl<St: Stream, S: Sleep> Sample<St, S> {
+    pub(super) fn new(stream: St, interval: Duration, sleep: S) -> Self {
+        Self { stream: stream.fuse(), delay: None, item: None, interval, sleep }
+    }
+
+    delegate_access_inner!(stream, St, (.));
+}
+
+impl<St: Stream, S: Sleep> FusedStream for Sample<St, S> {
+    fn is_terminated(&self) -> bool {
+        self.item.is_none() && self.stream.is_terminated()
+    }
+}
+
+impl<St: Stream, S: Sleep> Stream for Sample<St, S> {
+    type Item = St::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        // Only the latest item of every interval is kept.
+        let mut pulled = 0;
+        while let Poll::Ready(Some(item)) = this.stream.as_mut().poll_next(cx) {
+            *this.item = Some(item);
+            pulled += 1;
+            if pulled == YIELD_EVERY {
+                // The stream may always be ready, let other tasks run before
+                // taking more items from it.
+                cx.waker().wake_by_ref();
+                break;
+            }
+        }
+        if this.stream.is_done() {
+            this.delay.set(None);
+            return Poll::Ready(this.item.take());
+        }
+
+        if this.delay.is_none() {
+            this.delay.set(Some(this.sleep.sleep(*this.interval)));
+        }
+        ready!(this.delay.as_mut().as_pin_mut().unwrap().poll(cx));
+        this.delay.set(Some(this.sleep.sleep(*this.interval)));
+        if let Some(item) = this.item.take() {
+            return Poll::Ready(Some(item));
+        }
+
+        // Nothing arrived during this interval. The next one is waited for
+        // on the next poll, rather than in a loop that would spin if the
+        // timer is always ready.
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let item_len = if self.item.is_some() { 1 } else { 0 };
+        let (lower, upper) = self.stream.size_hint();
+        let upper = match upper {
+            Some(x) => x.checked_add(item_len),
+            None => None,
+        };
+        (lower.saturating_add(item_len).min(1), upper)
+    }
+}
+
+// Forwarding impl of Sink from the underlying stream
+#[cfg(feature = "sink")]
+impl<St, S, Item> Sink<Item> for Sample<St, S>
+where
+    St: Stream + Sink<Item>,
+    S: Sleep,
+{
+    type Error = St::Error;
+
+    delegate_sink!(stream, Item);
+}
@@ -0,0 +1,266 @@
+use super::notifier::{Notifier, NULL_WAKER_KEY};
+use super::upstream::{poll_unlocked, Upstream};
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+use slab::Slab;
+use std::collections::VecDeque;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Stream for the [`shared`](super::StreamExt::shared) method.
+#[must_use = "streams do nothing unless polled"]
+pub struct Shared<St: Stream> {
+    inner: Option<Arc<Inner<St>>>,
+    key: usize,
+    waker_key: usize,
+}
+
+struct Inner<St: Stream> {
+    state: Mutex<State<St>>,
+    notifier: Arc<Notifier>,
+}
+
+struct State<St: Stream> {
+    upstream: Upstream<Pin<Box<St>>>,
+    buffer: VecDeque<St::Item>,
+    capacity: usize,
+    // Position of the first buffered item in the upstream.
+    offset: u64,
+    subscribers: Slab<Subscriber>,
+}
+
+struct Subscriber {
+    // Position of the next item this subscriber will see.
+    next: u64,
+    lagging: bool,
+}
+
+// The upstream is pinned in a box of its own.
+impl<St: Stream> Unpin for Shared<St> {}
+
+impl<St: Stream> fmt::Debug for Shared<St> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Shared")
+            .field("inner", &self.inner)
+            .field("key", &self.key)
+            .field("waker_key", &self.waker_key)
+            .finish()
+    }
+}
+
+impl<St: Stream> fmt::Debug for Inner<St> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Inner").finish()
+    }
+}
+
+impl<St: Stream> Shared<St> {
+    pub(super) fn new(stream: St, buffer: usize) -> Self {
+        assert!(buffer > 0);
+        let mut subscribers = Slab::new();
+        let key = subscribers.insert(Subscriber { next: 0, lagging: false });
+        let state = State {
+            upstream: Upstream::Idle(Box::pin(stream)),
+            buffer: VecDeque::new(),
+            capacity: buffer,
+            offset: 0,
+            subscribers,
+        };
+        let inner = Inner { state: Mutex::new(state), notifier: Arc::new(Notifier::new()) };
+
+        Self { inner: Some(Arc::new(inner)), key, waker_key: NULL_WAKER_KEY }
+    }
+
+    /// Turns this handle into a lagging subscriber.
+    ///
+    /// By default, the upstream is not polled while the replay buffer is
+    /// full, so the slowest handle holds back all the others. A lagging
+    /// handle doesn't: when it falls behind by more than the buffer size, the
+    /// items it has missed are skipped and it resumes from the oldest item
+    /// still buffered.
+    ///
+    /// Clones of a lagging handle are lagging as well.
+    pub fn lagging(self) -> Self {
+        if let Some(inner) = &self.inner {
+            let mut state = inner.lock();
+            state.subscribers[self.key].lagging = true;
+            // Other handles may be waiting for this one to make room.
+            if state.buffer.len() >= state.capacity {
+                inner.notifier.wake_all_except(self.waker_key);
+            }
+        }
+        self
+    }
+
+    /// Gets the number of handles to this shared stream, including this one.
+    ///
+    /// Returns `None` if this handle has already yielded the end of the
+    /// stream.
+    pub fn subscriber_count(&self) -> Option<usize> {
+        self.inner.as_ref().map(|inner| inner.lock().subscribers.len())
+    }
+
+    fn unsubscribe(&mut self) {
+        if let Some(inner) = self.inner.take() {
+            inner.notifier.remove_waker(self.waker_key);
+            self.waker_key = NULL_WAKER_KEY;
+            if let Ok(mut state) = inner.state.lock() {
+                state.subscribers.remove(self.key);
+                // The buffered items this handle was the last one to need
+                // can go, which may let other handles make progress.
+                if state.trim() {
+                    inner.notifier.wake_all_except(NULL_WAKER_KEY);
+                }
+            }
+        }
+    }
+}
+
+impl<St: Stream> Inner<St> {
+    fn lock(&self) -> MutexGuard<'_, State<St>> {
+        self.state.lock().expect("inner stream panicked during poll")
+    }
+}
+
+impl<St: Stream> State<St> {
+    fn end(&self) -> u64 {
+        self.offset + self.buffer.len() as u64
+    }
+
+    /// Drops the buffered items that all subscribers have seen.
+    fn trim(&mut self) -> bool {
+        let end = self.end();
+        let min = self.subscribers.iter().map(|(_, sub)| sub.next).min().unwrap_or(end);
+        let trimmed = min > self.offset;
+        while self.offset < min {
+            self.buffer.pop_front();
+            self.offset += 1;
+        }
+        trimmed
+    }
+
+    /// Checks whether a subscriber that isn't lagging has yet to see the
+    /// oldest buffered item.
+    fn front_held(&self) -> bool {
+        self.subscribers.iter().any(|(_, sub)| !sub.lagging && sub.next == self.offset)
+    }
+}
+
+impl<St> Stream for Shared<St>
+where
+    St: Stream,
+    St::Item: Clone,
+{
+    type Item = St::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let inner = match &self.inner {
+            Some(inner) => inner.clone(),
+            None => return Poll::Ready(None),
+        };
+
+        loop {
+            let mut guard = inner.lock();
+            let state = &mut *guard;
+
+            let sub = &mut state.subscribers[self.key];
+            if sub.lagging && sub.next < state.offset {
+                sub.next = state.offset;
+            }
+            if sub.next < state.offset + state.buffer.len() as u64 {
+                let item = state.buffer[(sub.next - state.offset) as usize].clone();
+                sub.next += 1;
+                if state.trim() {
+                    inner.notifier.wake_all_except(self.waker_key);
+                }
+                return Poll::Ready(Some(item));
+            }
+
+            if state.upstream.is_done() {
+                drop(guard);
+                self.unsubscribe();
+                return Poll::Ready(None);
+            }
+
+            // Whoever is polling the upstream may be woken by it, or by
+            // another handle making room in or adding to the buffer.
+            inner.notifier.record_waker(&mut self.waker_key, cx);
+            if state.buffer.len() >= state.capacity && state.front_held() {
+                // Wait for the slowest handle to catch up.
+                return Poll::Pending;
+            }
+
+            // Another handle may be polling the upstream. It wakes this one
+            // once it yields an item. This includes a poll of the upstream
+            // that polls a handle again.
+            let mut stream = match state.upstream.take() {
+                Some(stream) => stream,
+                None => return Poll::Pending,
+            };
+            drop(guard);
+
+            // The lock is released while the upstream is polled, so other
+            // handles can take buffered items in the meantime.
+            let poll = poll_unlocked(
+                &inner.state,
+                |state| &mut state.upstream,
+                &inner.notifier,
+                |cx| stream.as_mut().poll_next(cx),
+            );
+
+            let mut state = inner.lock();
+            match poll {
+                Poll::Ready(Some(item)) => {
+                    if state.buffer.len() >= state.capacity {
+                        // Only lagging handles have yet to see this one.
+                        state.buffer.pop_front();
+                        state.offset += 1;
+                    }
+                    state.buffer.push_back(item);
+                    state.upstream = Upstream::Idle(stream);
+                }
+                Poll::Ready(None) => state.upstream = Upstream::Done,
+                Poll::Pending => {
+                    state.upstream = Upstream::Idle(stream);
+                    return Poll::Pending;
+                }
+            }
+            drop(state);
+            inner.notifier.wake_all_except(self.waker_key);
+        }
+    }
+}
+
+impl<St> FusedStream for Shared<St>
+where
+    St: Stream,
+    St::Item: Clone,
+{
+    fn is_terminated(&self) -> bool {
+        self.inner.is_none()
+    }
+}
+
+impl<St: Stream> Clone for Shared<St> {
+    /// Creates a new handle that sees the items the upstream yields from now
+    /// on.
+    fn clone(&self) -> Self {
+        let key = match &self.inner {
+            Some(inner) => {
+                let mut state = inner.lock();
+                let lagging = state.subscribers[self.key].lagging;
+                let next = state.end();
+                state.subscribers.insert(Subscriber { next, lagging })
+            }
+            None => 0,
+        };
+        Self { inner: self.inner.clone(), key, waker_key: NULL_WAKER_KEY }
+    }
+}
+
+impl<St: Stream> Drop for Shared<St> {
+    fn drop(&mut self) {
+        self.unsubscribe();
+    }
+}
@@ -0,0 +1,121 @@
+use crate::stream::{Fuse, StreamExt, YIELD_EVERY};
+use core::fmt;
+use core::pin::Pin;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+#[cfg(feature = "sink")]
+use futures_sink::Sink;
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// Stream for the [`switch_map`](super::StreamExt::switch_map) method.
+    #[must_use = "streams do nothing unless polled"]
+    pub struct SwitchMap<St, U, F> {
+        #[pin]
+        stream: Fuse<St>,
+        #[pin]
+        inner: Option<U>,
+        f: F,
+    }
+}
+
+impl<St, U, F> fmt::Debug for SwitchMap<St, U, F>
+where
+    St: fmt::Debug,
+    U: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SwitchMap")
+            .field("stream", &self.stream)
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<St, U, F> SwitchMap<St, U, F>
+where
+    St: Stream,
+    F: FnMut(St::Item) -> U,
+{
+    pub(super) fn new(stream: St, f: F) -> Self {
+        Self { stream: stream.fuse(), inner: None, f }
+    }
+
+    delegate_access_inner!(stream, St, (.));
+}
+
+impl<St, U, F> FusedStream for SwitchMap<St, U, F>
+where
+    St: Stream,
+    F: FnMut(St::Item) -> U,
+    U: Stream,
+{
+    fn is_terminated(&self) -> bool {
+        self.inner.is_none() && self.stream.is_terminated()
+    }
+}
+
+impl<St, U, F> Stream for SwitchMap<St, U, F>
+where
+    St: Stream,
+    F: FnMut(St::Item) -> U,
+    U: Stream,
+{
+    type Item = U::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        // Switch to the stream of the latest item, dropping the current one.
+        // Items that are already followed by another one are skipped
+        // without being mapped.
+        let mut latest = None;
+        let mut pulled = 0;
+        while let Poll::Ready(Some(item)) = this.stream.as_mut().poll_next(cx) {
+            latest = Some(item);
+            pulled += 1;
+            if pulled == YIELD_EVERY {
+                // The stream may always be ready, let other tasks run before
+                // taking more items from it.
+                cx.waker().wake_by_ref();
+                break;
+            }
+        }
+        if let Some(item) = latest {
+            this.inner.set(Some((this.f)(item)));
+        }
+
+        if let Some(inner) = this.inner.as_mut().as_pin_mut() {
+            match inner.poll_next(cx) {
+                Poll::Ready(Some(item)) => return Poll::Ready(Some(item)),
+                Poll::Ready(None) => this.inner.set(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
+        if this.stream.is_done() {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.inner.is_none() && self.stream.is_done() {
+            (0, Some(0))
+        } else {
+            (0, None)
+        }
+    }
+}
+
+// Forwarding impl of Sink from the underlying stream
+#[cfg(feature = "sink")]
+impl<S, U, F, Item> Sink<Item> for SwitchMap<S, U, F>
+where
+    S: Stream + Sink<Item>,
+{
+    type Error = S::Error;
+
+    delegate_sink!(stream, Item);
+}
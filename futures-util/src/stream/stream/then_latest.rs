@@ -0,0 +1,123 @@
+use crate::stream::{Fuse, StreamExt, YIELD_EVERY};
+use core::fmt;
+use core::pin::Pin;
+use futures_core::future::Future;
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+#[cfg(feature = "sink")]
+use futures_sink::Sink;
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// Stream for the [`then_latest`](super::StreamExt::then_latest) method.
+    #[must_use = "streams do nothing unless polled"]
+    pub struct ThenLatest<St, Fut, F> {
+        #[pin]
+        stream: Fuse<St>,
+        #[pin]
+        future: Option<Fut>,
+        f: F,
+    }
+}
+
+impl<St, Fut, F> fmt::Debug for ThenLatest<St, Fut, F>
+where
+    St: fmt::Debug,
+    Fut: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThenLatest")
+            .field("stream", &self.stream)
+            .field("future", &self.future)
+            .finish()
+    }
+}
+
+impl<St, Fut, F> ThenLatest<St, Fut, F>
+where
+    St: Stream,
+    F: FnMut(St::Item) -> Fut,
+{
+    pub(super) fn new(stream: St, f: F) -> Self {
+        Self { stream: stream.fuse(), future: None, f }
+    }
+
+    delegate_access_inner!(stream, St, (.));
+}
+
+impl<St, Fut, F> FusedStream for ThenLatest<St, Fut, F>
+where
+    St: Stream,
+    F: FnMut(St::Item) -> Fut,
+    Fut: Future,
+{
+    fn is_terminated(&self) -> bool {
+        self.future.is_none() && self.stream.is_terminated()
+    }
+}
+
+impl<St, Fut, F> Stream for ThenLatest<St, Fut, F>
+where
+    St: Stream,
+    F: FnMut(St::Item) -> Fut,
+    Fut: Future,
+{
+    type Item = Fut::Output;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        // Only the future of the latest item gets to complete.
+        // Items that are already followed by another one are skipped
+        // without being mapped.
+        let mut latest = None;
+        let mut pulled = 0;
+        while let Poll::Ready(Some(item)) = this.stream.as_mut().poll_next(cx) {
+            latest = Some(item);
+            pulled += 1;
+            if pulled == YIELD_EVERY {
+                // The stream may always be ready, let other tasks run before
+                // taking more items from it.
+                cx.waker().wake_by_ref();
+                break;
+            }
+        }
+        if let Some(item) = latest {
+            this.future.set(Some((this.f)(item)));
+        }
+
+        if let Some(fut) = this.future.as_mut().as_pin_mut() {
+            let output = ready!(fut.poll(cx));
+            this.future.set(None);
+            return Poll::Ready(Some(output));
+        }
+
+        if this.stream.is_done() {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let pending_len = if self.future.is_some() { 1 } else { 0 };
+        let (_, upper) = self.stream.size_hint();
+        let upper = match upper {
+            Some(x) => x.checked_add(pending_len),
+            None => None,
+        };
+        (pending_len, upper)
+    }
+}
+
+// Forwarding impl of Sink from the underlying stream
+#[cfg(feature = "sink")]
+impl<S, Fut, F, Item> Sink<Item> for ThenLatest<S, Fut, F>
+where
+    S: Stream + Sink<Item>,
+{
+    type Error = S::Error;
+
+    delegate_sink!(stream, Item);
+}
@@ -0,0 +1,104 @@
+use crate::future::Sleep;
+use crate::stream::YIELD_EVERY;
+use core::fmt;
+use core::pin::Pin;
+use futures_core::future::Future;
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+#[cfg(feature = "sink")]
+use futures_sink::Sink;
+use pin_project_lite::pin_project;
+use std::time::Duration;
+
+pin_project! {
+    /// Stream for the [`throttle`](super::StreamExt::throttle) method.
+    #[must_use = "streams do nothing unless polled"]
+    pub struct Throttle<St, S: Sleep> {
+        #[pin]
+        stream: St,
+        #[pin]
+        delay: Option<S::Future>,
+        duration: Duration,
+        sleep: S,
+    }
+}
+
+impl<St, S> fmt::Debug for Throttle<St, S>
+where
+    St: fmt::Debug,
+    S: Sleep,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Throttle")
+            .field("stream", &self.stream)
+            .field("duration", &self.duration)
+            .finish()
+    }
+}
+
+impl<St: Stream, S: Sleep> Throttle<St, S> {
+    pub(super) fn new(stream: St, duration: Duration, sleep: S) -> Self {
+        Self { stream, delay: None, duration, sleep }
+    }
+
+    delegate_access_inner!(stream, St, ());
+}
+
+impl<St: FusedStream, S: Sleep> FusedStream for Throttle<St, S> {
+    fn is_terminated(&self) -> bool {
+        self.stream.is_terminated()
+    }
+}
+
+impl<St: Stream, S: Sleep> Stream for Throttle<St, S> {
+    type Item = St::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        let mut skipped = 0;
+        loop {
+            if let Some(delay) = this.delay.as_mut().as_pin_mut() {
+                if delay.poll(cx).is_ready() {
+                    this.delay.set(None);
+                }
+            }
+
+            match ready!(this.stream.as_mut().poll_next(cx)) {
+                Some(item) if this.delay.is_none() => {
+                    this.delay.set(Some(this.sleep.sleep(*this.duration)));
+                    return Poll::Ready(Some(item));
+                }
+                // Too soon after the last item.
+                Some(_) => {
+                    skipped += 1;
+                    if skipped == YIELD_EVERY {
+                        // The stream may always be ready, let other tasks
+                        // run before taking more items from it.
+                        cx.waker().wake_by_ref();
+                        return Poll::Pending;
+                    }
+                }
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.stream.size_hint();
+        (lower.min(1), upper)
+    }
+}
+
+// Forwarding impl of Sink from the underlying stream
+#[cfg(feature = "sink")]
+impl<St, S, Item> Sink<Item> for Throttle<St, S>
+where
+    St: Stream + Sink<Item>,
+    S: Sleep,
+{
+    type Error = St::Error;
+
+    delegate_sink!(stream, Item);
+}
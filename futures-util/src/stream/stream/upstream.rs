@@ -0,0 +1,83 @@
+use super::notifier::{Notifier, NULL_WAKER_KEY};
+use crate::task::waker_ref;
+use futures_core::task::Context;
+use std::mem;
+use std::sync::{Arc, Mutex};
+
+/// The stream that several handles share, along with anything else needed
+/// to process its items, such as the closure of the combinator.
+///
+/// The handle that polls the stream takes it out of the state the handles
+/// share, so that the lock isn't held while user code runs: a handle polled
+/// from within the stream would deadlock, and a panic would poison the lock.
+pub(crate) enum Upstream<T> {
+    Idle(T),
+    Polling,
+    Done,
+    Poisoned,
+}
+
+impl<T> Upstream<T> {
+    pub(crate) fn is_done(&self) -> bool {
+        match self {
+            Upstream::Done => true,
+            _ => false,
+        }
+    }
+
+    /// Takes the stream out to poll it.
+    ///
+    /// Returns `None` if the stream has ended, or if another handle is
+    /// polling it, which wakes the other handles once it is done.
+    ///
+    /// # Panics
+    ///
+    /// Panics if polling the stream panicked before.
+    pub(crate) fn take(&mut self) -> Option<T> {
+        match mem::replace(self, Upstream::Polling) {
+            Upstream::Idle(upstream) => Some(upstream),
+            Upstream::Polling => None,
+            Upstream::Done => {
+                *self = Upstream::Done;
+                None
+            }
+            Upstream::Poisoned => {
+                *self = Upstream::Poisoned;
+                panic!("inner stream panicked during poll")
+            }
+        }
+    }
+}
+
+/// Runs `f`, which polls the stream taken out of `state`, with a context
+/// that wakes every handle.
+///
+/// If `f` panics, the stream is marked as poisoned, and the handles are
+/// woken so that they panic as well.
+pub(crate) fn poll_unlocked<S, T, R>(
+    state: &Mutex<S>,
+    upstream: fn(&mut S) -> &mut Upstream<T>,
+    notifier: &Arc<Notifier>,
+    f: impl FnOnce(&mut Context<'_>) -> R,
+) -> R {
+    struct Poisoner<'a, S, T> {
+        state: &'a Mutex<S>,
+        upstream: fn(&mut S) -> &mut Upstream<T>,
+        notifier: &'a Notifier,
+    }
+
+    impl<S, T> Drop for Poisoner<'_, S, T> {
+        fn drop(&mut self) {
+            if let Ok(mut state) = self.state.lock() {
+                *(self.upstream)(&mut state) = Upstream::Poisoned;
+            }
+            self.notifier.wake_all_except(NULL_WAKER_KEY);
+        }
+    }
+
+    let poisoner = Poisoner { state, upstream, notifier };
+    let waker = waker_ref(notifier);
+    let result = f(&mut Context::from_waker(&waker));
+    mem::forget(poisoner);
+    result
+}
@@ -0,0 +1,117 @@
+use crate::stream::Fuse;
+use alloc::collections::VecDeque;
+use alloc::sync::Arc;
+use core::pin::Pin;
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+#[cfg(feature = "sink")]
+use futures_sink::Sink;
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// Stream for the [`windows`](super::StreamExt::windows) and
+    /// [`windows_by`](super::StreamExt::windows_by) methods.
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct Windows<St: Stream> {
+        #[pin]
+        stream: Fuse<St>,
+        items: VecDeque<St::Item>,
+        size: usize,
+        step: usize,
+        // The number of items to drop before the next window starts.
+        skip: usize,
+    }
+}
+
+impl<St: Stream> Windows<St>
+where
+    St::Item: Clone,
+{
+    pub(super) fn new(stream: St, step: usize, size: usize) -> Self {
+        assert!(size > 0);
+        assert!(step > 0);
+
+        Self {
+            stream: super::Fuse::new(stream),
+            items: VecDeque::with_capacity(size),
+            size,
+            step,
+            skip: 0,
+        }
+    }
+
+    delegate_access_inner!(stream, St, (.));
+}
+
+impl<St: Stream> Stream for Windows<St>
+where
+    St::Item: Clone,
+{
+    type Item = Arc<[St::Item]>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        loop {
+            match ready!(this.stream.as_mut().poll_next(cx)) {
+                Some(_) if *this.skip > 0 => *this.skip -= 1,
+                Some(item) => {
+                    this.items.push_back(item);
+                    if this.items.len() == *this.size {
+                        // The items are cloned into every window they are part of.
+                        let window: Arc<[_]> = this.items.iter().cloned().collect();
+                        // Move on to the start of the next window.
+                        if *this.step < *this.size {
+                            this.items.drain(..*this.step);
+                        } else {
+                            this.items.clear();
+                            *this.skip = *this.step - *this.size;
+                        }
+                        return Poll::Ready(Some(window));
+                    }
+                }
+
+                // Incomplete windows are dropped.
+                None => {
+                    this.items.clear();
+                    return Poll::Ready(None);
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // The number of windows that end within the given number of items.
+        let windows = |n: usize| {
+            let n = (self.items.len() + n).saturating_sub(self.skip);
+            if n < self.size {
+                0
+            } else {
+                (n - self.size) / self.step + 1
+            }
+        };
+        let (lower, upper) = self.stream.size_hint();
+        (windows(lower), upper.map(windows))
+    }
+}
+
+impl<St: FusedStream> FusedStream for Windows<St>
+where
+    St::Item: Clone,
+{
+    fn is_terminated(&self) -> bool {
+        self.stream.is_terminated()
+    }
+}
+
+// Forwarding impl of Sink from the underlying stream
+#[cfg(feature = "sink")]
+impl<S, Item> Sink<Item> for Windows<S>
+where
+    S: Stream + Sink<Item>,
+{
+    type Error = S::Error;
+
+    delegate_sink!(stream, Item);
+}
@@ -0,0 +1,114 @@
+use crate::stream::Fuse;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+use core::mem;
+use core::pin::Pin;
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+#[cfg(feature = "sink")]
+use futures_sink::Sink;
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// Stream for the [`windows_until`](super::StreamExt::windows_until) method.
+    #[must_use = "streams do nothing unless polled"]
+    pub struct WindowsUntil<St: Stream, F> {
+        #[pin]
+        stream: Fuse<St>,
+        f: F,
+        items: Vec<St::Item>,
+    }
+}
+
+impl<St, F> fmt::Debug for WindowsUntil<St, F>
+where
+    St: Stream + fmt::Debug,
+    St::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WindowsUntil")
+            .field("stream", &self.stream)
+            .field("items", &self.items)
+            .finish()
+    }
+}
+
+impl<St, F> WindowsUntil<St, F>
+where
+    St: Stream,
+    F: FnMut(&St::Item) -> bool,
+{
+    pub(super) fn new(stream: St, f: F) -> Self {
+        Self { stream: super::Fuse::new(stream), f, items: Vec::new() }
+    }
+
+    delegate_access_inner!(stream, St, (.));
+}
+
+impl<St, F> Stream for WindowsUntil<St, F>
+where
+    St: Stream,
+    F: FnMut(&St::Item) -> bool,
+{
+    type Item = Arc<[St::Item]>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        loop {
+            match ready!(this.stream.as_mut().poll_next(cx)) {
+                Some(item) => {
+                    let closes = (this.f)(&item);
+                    this.items.push(item);
+                    if closes {
+                        return Poll::Ready(Some(mem::replace(this.items, Vec::new()).into()));
+                    }
+                }
+
+                // Since the underlying stream ran out of values, return what we
+                // have buffered, if we have anything.
+                None => {
+                    let last = if this.items.is_empty() {
+                        None
+                    } else {
+                        Some(mem::replace(this.items, Vec::new()).into())
+                    };
+                    return Poll::Ready(last);
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let window_len = if self.items.is_empty() { 0 } else { 1 };
+        let (lower, upper) = self.stream.size_hint();
+        let lower = if lower > 0 || window_len > 0 { 1 } else { 0 };
+        let upper = match upper {
+            Some(x) => x.checked_add(window_len),
+            None => None,
+        };
+        (lower, upper)
+    }
+}
+
+impl<St, F> FusedStream for WindowsUntil<St, F>
+where
+    St: Stream,
+    F: FnMut(&St::Item) -> bool,
+{
+    fn is_terminated(&self) -> bool {
+        self.stream.is_terminated() && self.items.is_empty()
+    }
+}
+
+// Forwarding impl of Sink from the underlying stream
+#[cfg(feature = "sink")]
+impl<S, F, Item> Sink<Item> for WindowsUntil<S, F>
+where
+    S: Stream + Sink<Item>,
+{
+    type Error = S::Error;
+
+    delegate_sink!(stream, Item);
+}
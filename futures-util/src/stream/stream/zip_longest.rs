@@ -0,0 +1,189 @@
+use crate::stream::{Fuse, StreamExt};
+use core::cmp;
+use core::pin::Pin;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+
+/// An item of a [`ZipLongest`] stream, which has a value from either or both
+/// of the streams.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EitherOrBoth<A, B> {
+    /// Both streams yielded a value.
+    Both(A, B),
+    /// Only the first stream yielded a value, as the second one has ended.
+    Left(A),
+    /// Only the second stream yielded a value, as the first one has ended.
+    Right(B),
+}
+
+impl<A, B> EitherOrBoth<A, B> {
+    /// Returns the value from the first stream, if any.
+    pub fn left(self) -> Option<A> {
+        match self {
+            EitherOrBoth::Both(a, _) | EitherOrBoth::Left(a) => Some(a),
+            EitherOrBoth::Right(_) => None,
+        }
+    }
+
+    /// Returns the value from the second stream, if any.
+    pub fn right(self) -> Option<B> {
+        match self {
+            EitherOrBoth::Both(_, b) | EitherOrBoth::Right(b) => Some(b),
+            EitherOrBoth::Left(_) => None,
+        }
+    }
+
+    /// Converts this into a pair of the values from both streams.
+    pub fn into_options(self) -> (Option<A>, Option<B>) {
+        match self {
+            EitherOrBoth::Both(a, b) => (Some(a), Some(b)),
+            EitherOrBoth::Left(a) => (Some(a), None),
+            EitherOrBoth::Right(b) => (None, Some(b)),
+        }
+    }
+
+    /// Returns the values from both streams, using the provided defaults for
+    /// a missing one.
+    pub fn or(self, a: A, b: B) -> (A, B) {
+        match self {
+            EitherOrBoth::Both(a, b) => (a, b),
+            EitherOrBoth::Left(a) => (a, b),
+            EitherOrBoth::Right(b) => (a, b),
+        }
+    }
+}
+
+pin_project! {
+    /// Stream for the [`zip_longest`](super::StreamExt::zip_longest) method.
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct ZipLongest<St1: Stream, St2: Stream> {
+        #[pin]
+        stream1: Fuse<St1>,
+        #[pin]
+        stream2: Fuse<St2>,
+        queued1: Option<St1::Item>,
+        queued2: Option<St2::Item>,
+    }
+}
+
+impl<St1: Stream, St2: Stream> ZipLongest<St1, St2> {
+    pub(super) fn new(stream1: St1, stream2: St2) -> Self {
+        Self { stream1: stream1.fuse(), stream2: stream2.fuse(), queued1: None, queued2: None }
+    }
+
+    /// Acquires a reference to the underlying streams that this combinator is
+    /// pulling from.
+    pub fn get_ref(&self) -> (&St1, &St2) {
+        (self.stream1.get_ref(), self.stream2.get_ref())
+    }
+
+    /// Acquires a mutable reference to the underlying streams that this
+    /// combinator is pulling from.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the
+    /// stream which may otherwise confuse this combinator.
+    pub fn get_mut(&mut self) -> (&mut St1, &mut St2) {
+        (self.stream1.get_mut(), self.stream2.get_mut())
+    }
+
+    /// Acquires a pinned mutable reference to the underlying streams that this
+    /// combinator is pulling from.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the
+    /// stream which may otherwise confuse this combinator.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> (Pin<&mut St1>, Pin<&mut St2>) {
+        let this = self.project();
+        (this.stream1.get_pin_mut(), this.stream2.get_pin_mut())
+    }
+
+    /// Consumes this combinator, returning the underlying streams.
+    ///
+    /// Note that this may discard intermediate state of this combinator, so
+    /// care should be taken to avoid losing resources when this is called.
+    pub fn into_inner(self) -> (St1, St2) {
+        (self.stream1.into_inner(), self.stream2.into_inner())
+    }
+}
+
+impl<St1, St2> FusedStream for ZipLongest<St1, St2>
+where
+    St1: Stream,
+    St2: Stream,
+{
+    fn is_terminated(&self) -> bool {
+        self.stream1.is_terminated()
+            && self.stream2.is_terminated()
+            && self.queued1.is_none()
+            && self.queued2.is_none()
+    }
+}
+
+impl<St1, St2> Stream for ZipLongest<St1, St2>
+where
+    St1: Stream,
+    St2: Stream,
+{
+    type Item = EitherOrBoth<St1::Item, St2::Item>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        if this.queued1.is_none() {
+            match this.stream1.as_mut().poll_next(cx) {
+                Poll::Ready(Some(item1)) => *this.queued1 = Some(item1),
+                Poll::Ready(None) | Poll::Pending => {}
+            }
+        }
+        if this.queued2.is_none() {
+            match this.stream2.as_mut().poll_next(cx) {
+                Poll::Ready(Some(item2)) => *this.queued2 = Some(item2),
+                Poll::Ready(None) | Poll::Pending => {}
+            }
+        }
+
+        match (this.queued1.take(), this.queued2.take()) {
+            (Some(item1), Some(item2)) => Poll::Ready(Some(EitherOrBoth::Both(item1, item2))),
+            (Some(item1), None) if this.stream2.is_done() => {
+                Poll::Ready(Some(EitherOrBoth::Left(item1)))
+            }
+            (None, Some(item2)) if this.stream1.is_done() => {
+                Poll::Ready(Some(EitherOrBoth::Right(item2)))
+            }
+            (None, None) if this.stream1.is_done() && this.stream2.is_done() => Poll::Ready(None),
+            // Wait for the other stream to either yield or end.
+            (queued1, queued2) => {
+                *this.queued1 = queued1;
+                *this.queued2 = queued2;
+                Poll::Pending
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let queued1_len = if self.queued1.is_some() { 1 } else { 0 };
+        let queued2_len = if self.queued2.is_some() { 1 } else { 0 };
+        let (stream1_lower, stream1_upper) = self.stream1.size_hint();
+        let (stream2_lower, stream2_upper) = self.stream2.size_hint();
+
+        let stream1_lower = stream1_lower.saturating_add(queued1_len);
+        let stream2_lower = stream2_lower.saturating_add(queued2_len);
+
+        let lower = cmp::max(stream1_lower, stream2_lower);
+
+        let upper = match (stream1_upper, stream2_upper) {
+            (Some(x), Some(y)) => {
+                let x = x.checked_add(queued1_len);
+                let y = y.checked_add(queued2_len);
+                match (x, y) {
+                    (Some(x), Some(y)) => Some(cmp::max(x, y)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+
+        (lower, upper)
+    }
+}
@@ -10,6 +10,8 @@ use crate::fns::{
     IntoFn, MapErrFn, MapOkFn,
 };
 use crate::future::assert_future;
+#[cfg(feature = "std")]
+use crate::future::{RetryPolicy, Sleep};
 use crate::stream::assert_stream;
 use crate::stream::{Inspect, Map};
 #[cfg(feature = "alloc")]
@@ -110,6 +112,56 @@ mod try_take_while;
 #[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
 pub use self::try_take_while::TryTakeWhile;
 
+mod try_count;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::try_count::TryCount;
+
+mod try_any;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::try_any::TryAny;
+
+mod try_all;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::try_all::TryAll;
+
+mod try_find;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::try_find::TryFind;
+
+mod try_find_map;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::try_find_map::TryFindMap;
+
+mod try_position;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::try_position::TryPosition;
+
+mod try_last;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::try_last::TryLast;
+
+mod try_nth;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::try_nth::TryNth;
+
+mod try_min_by_key;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::try_min_by_key::TryMinByKey;
+
+mod try_max_by_key;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::try_max_by_key::TryMaxByKey;
+
+mod try_reduce;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::try_reduce::TryReduce;
+
+#[cfg(feature = "std")]
+mod retry_with;
+#[cfg(feature = "std")]
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::retry_with::RetryWith;
+
 #[cfg(not(futures_no_atomic_cas))]
 #[cfg(feature = "alloc")]
 mod try_buffer_unordered;
@@ -118,6 +170,29 @@ mod try_buffer_unordered;
 #[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
 pub use self::try_buffer_unordered::TryBufferUnordered;
 
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "alloc")]
+mod try_flatten_unordered;
+
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "alloc")]
+delegate_all!(
+    /// Stream for the [`try_flatten_unordered`](super::TryStreamExt::try_flatten_unordered) method.
+    TryFlattenUnordered<St>(
+        try_flatten_unordered::TryFlattenUnordered<St, St::Ok>
+    ): Debug + Sink + Stream + FusedStream + AccessInner[St, (.)] + New[|x: St, limit: Option<usize>| try_flatten_unordered::TryFlattenUnordered::new(x, limit)]
+    where St: TryStream
+);
+
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "alloc")]
+delegate_all!(
+    /// Stream for the [`try_flat_map_unordered`](super::TryStreamExt::try_flat_map_unordered) method.
+    TryFlatMapUnordered<St, U, F>(
+        try_flatten_unordered::TryFlattenUnordered<MapOk<St, F>, U>
+    ): Debug + Sink + Stream + FusedStream + AccessInner[St, (. .)] + New[|x: St, limit: Option<usize>, f: F| try_flatten_unordered::TryFlattenUnordered::new(MapOk::new(x, f), limit)]
+);
+
 #[cfg(not(futures_no_atomic_cas))]
 #[cfg(feature = "alloc")]
 mod try_buffered;
@@ -642,6 +717,96 @@ pub trait TryStreamExt: TryStream {
         )
     }
 
+    /// Flattens a stream of streams into just one continuous stream, polling
+    /// up to `limit` inner streams concurrently and stopping at the first
+    /// error.
+    ///
+    /// This is the fallible version of
+    /// [`StreamExt::flatten_unordered`](crate::stream::StreamExt::flatten_unordered).
+    /// Items are yielded in the order in which they become ready. The first
+    /// error, whether it is produced by this stream or by one of the inner
+    /// streams, is yielded and ends the returned stream; the remaining inner
+    /// streams are dropped.
+    ///
+    /// The limit argument is of type `Into<Option<usize>>`, and so can be
+    /// provided as either `None`, `Some(10)`, or just `10`. Note: a limit of zero is
+    /// interpreted as no limit at all, and will have the same result as passing in `None`.
+    ///
+    /// This method is only available when the `std` or `alloc` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, StreamExt, TryStreamExt};
+    ///
+    /// let pages = stream::iter(vec![
+    ///     Ok(stream::iter(vec![Ok(1), Ok(2)])),
+    ///     Ok(stream::iter(vec![Err("page failed")])),
+    ///     Ok(stream::iter(vec![Ok(3)])),
+    /// ]);
+    /// let mut stream = pages.try_flatten_unordered(1);
+    ///
+    /// assert_eq!(stream.next().await, Some(Ok(1)));
+    /// assert_eq!(stream.next().await, Some(Ok(2)));
+    /// assert_eq!(stream.next().await, Some(Err("page failed")));
+    /// assert_eq!(stream.next().await, None);
+    /// # });
+    /// ```
+    #[cfg(not(futures_no_atomic_cas))]
+    #[cfg(feature = "alloc")]
+    fn try_flatten_unordered(self, limit: impl Into<Option<usize>>) -> TryFlattenUnordered<Self>
+    where
+        Self::Ok: TryStream,
+        <Self::Ok as TryStream>::Error: From<Self::Error>,
+        Self: Sized,
+    {
+        assert_stream::<Result<<Self::Ok as TryStream>::Ok, <Self::Ok as TryStream>::Error>, _>(
+            TryFlattenUnordered::new(self, limit.into()),
+        )
+    }
+
+    /// Maps the success values of this stream to streams, and flattens them
+    /// into one stream, polling up to `limit` of them concurrently and
+    /// stopping at the first error.
+    ///
+    /// This is [`map_ok`](TryStreamExt::map_ok) followed by
+    /// [`try_flatten_unordered`](TryStreamExt::try_flatten_unordered).
+    ///
+    /// This method is only available when the `std` or `alloc` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, TryStreamExt};
+    ///
+    /// let stream = stream::iter(vec![Ok::<_, ()>(1), Ok(2)]);
+    /// let stream = stream.try_flat_map_unordered(None, |x| stream::iter(vec![Ok::<_, ()>(x); x]));
+    ///
+    /// let mut values: Vec<usize> = stream.try_collect().await?;
+    /// values.sort();
+    /// assert_eq!(values, vec![1, 2, 2]);
+    /// # Ok::<(), ()>(()) }).unwrap();
+    /// ```
+    #[cfg(not(futures_no_atomic_cas))]
+    #[cfg(feature = "alloc")]
+    fn try_flat_map_unordered<U, F>(
+        self,
+        limit: impl Into<Option<usize>>,
+        f: F,
+    ) -> TryFlatMapUnordered<Self, U, F>
+    where
+        F: FnMut(Self::Ok) -> U,
+        U: TryStream,
+        U::Error: From<Self::Error>,
+        Self: Sized,
+    {
+        assert_stream::<Result<U::Ok, U::Error>, _>(TryFlatMapUnordered::new(self, limit.into(), f))
+    }
+
     /// Attempt to concatenate all items of a stream into a single
     /// extendable destination, returning a future representing the end result.
     ///
@@ -684,6 +849,340 @@ pub trait TryStreamExt: TryStream {
         assert_future::<Result<Self::Ok, Self::Error>, _>(TryConcat::new(self))
     }
 
+    /// Returns the number of items produced by this stream, consuming it.
+    ///
+    /// If an error is encountered, it is returned immediately and the stream
+    /// is not polled any further.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, TryStreamExt};
+    ///
+    /// let stream = stream::iter(vec![Ok::<i32, i32>(1), Ok(2), Ok(3)]);
+    /// assert_eq!(stream.try_count().await, Ok(3));
+    ///
+    /// let stream = stream::iter(vec![Ok::<i32, i32>(1), Err(2), Ok(3)]);
+    /// assert_eq!(stream.try_count().await, Err(2));
+    /// # });
+    /// ```
+    fn try_count(self) -> TryCount<Self>
+    where
+        Self: Sized,
+    {
+        assert_future::<Result<usize, Self::Error>, _>(TryCount::new(self))
+    }
+
+    /// Checks whether the provided asynchronous predicate holds for any
+    /// successful value of this stream.
+    ///
+    /// This is the fallible version of [`StreamExt::any`](crate::stream::StreamExt::any):
+    /// if an error is encountered before the predicate resolves to `true`, it
+    /// is returned immediately and the stream is not polled any further.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, TryStreamExt};
+    ///
+    /// let stream = stream::iter(vec![Ok::<i32, i32>(1), Ok(2), Err(3)]);
+    /// assert_eq!(stream.try_any(|x| async move { x % 2 == 0 }).await, Ok(true));
+    ///
+    /// let stream = stream::iter(vec![Ok::<i32, i32>(1), Err(2), Ok(4)]);
+    /// assert_eq!(stream.try_any(|x| async move { x % 2 == 0 }).await, Err(2));
+    /// # });
+    /// ```
+    fn try_any<Fut, F>(self, f: F) -> TryAny<Self, Fut, F>
+    where
+        F: FnMut(Self::Ok) -> Fut,
+        Fut: Future<Output = bool>,
+        Self: Sized,
+    {
+        assert_future::<Result<bool, Self::Error>, _>(TryAny::new(self, f))
+    }
+
+    /// Checks whether the provided asynchronous predicate holds for all
+    /// successful values of this stream.
+    ///
+    /// This is the fallible version of [`StreamExt::all`](crate::stream::StreamExt::all):
+    /// if an error is encountered before the predicate resolves to `false`,
+    /// it is returned immediately and the stream is not polled any further.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, TryStreamExt};
+    ///
+    /// let stream = stream::iter(vec![Ok::<i32, i32>(2), Ok(4), Ok(6)]);
+    /// assert_eq!(stream.try_all(|x| async move { x % 2 == 0 }).await, Ok(true));
+    ///
+    /// let stream = stream::iter(vec![Ok::<i32, i32>(2), Err(3), Ok(6)]);
+    /// assert_eq!(stream.try_all(|x| async move { x % 2 == 0 }).await, Err(3));
+    /// # });
+    /// ```
+    fn try_all<Fut, F>(self, f: F) -> TryAll<Self, Fut, F>
+    where
+        F: FnMut(Self::Ok) -> Fut,
+        Fut: Future<Output = bool>,
+        Self: Sized,
+    {
+        assert_future::<Result<bool, Self::Error>, _>(TryAll::new(self, f))
+    }
+
+    /// Returns the first successful value of this stream for which the
+    /// provided asynchronous predicate resolves to `true`.
+    ///
+    /// If an error is encountered first, it is returned immediately and the
+    /// stream is not polled any further. If the stream ends first, the
+    /// returned future resolves to `Ok(None)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::future;
+    /// use futures::stream::{self, TryStreamExt};
+    ///
+    /// let stream = stream::iter(vec![Ok::<i32, i32>(1), Ok(5), Err(7)]);
+    /// assert_eq!(stream.try_find(|x| future::ready(x * x > 20)).await, Ok(Some(5)));
+    ///
+    /// let stream = stream::iter(vec![Ok::<i32, i32>(1), Err(2), Ok(5)]);
+    /// assert_eq!(stream.try_find(|x| future::ready(x * x > 20)).await, Err(2));
+    /// # });
+    /// ```
+    fn try_find<Fut, F>(self, f: F) -> TryFind<Self, Fut, F>
+    where
+        F: FnMut(&Self::Ok) -> Fut,
+        Fut: Future<Output = bool>,
+        Self: Sized,
+    {
+        assert_future::<Result<Option<Self::Ok>, Self::Error>, _>(TryFind::new(self, f))
+    }
+
+    /// Applies the provided fallible asynchronous closure to the successful
+    /// values of this stream and returns the first non-`None` result.
+    ///
+    /// Errors from either the stream or the closure are returned immediately
+    /// and stop the stream from being polled any further. If the stream ends
+    /// first, the returned future resolves to `Ok(None)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, TryStreamExt};
+    ///
+    /// let stream = stream::iter(vec![Ok::<&str, &str>("one"), Ok("2"), Ok("three")]);
+    /// let first_number = stream.try_find_map(|s| async move { Ok(s.parse::<i32>().ok()) });
+    /// assert_eq!(first_number.await, Ok(Some(2)));
+    /// # });
+    /// ```
+    fn try_find_map<Fut, F, T>(self, f: F) -> TryFindMap<Self, Fut, F>
+    where
+        F: FnMut(Self::Ok) -> Fut,
+        Fut: TryFuture<Ok = Option<T>, Error = Self::Error>,
+        Self: Sized,
+    {
+        assert_future::<Result<Option<T>, Self::Error>, _>(TryFindMap::new(self, f))
+    }
+
+    /// Returns the index of the first successful value of this stream for
+    /// which the provided asynchronous predicate resolves to `true`.
+    ///
+    /// If an error is encountered first, it is returned immediately and the
+    /// stream is not polled any further. If the stream ends first, the
+    /// returned future resolves to `Ok(None)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, TryStreamExt};
+    ///
+    /// let stream = stream::iter(vec![Ok::<i32, i32>(1), Ok(3), Ok(4)]);
+    /// assert_eq!(stream.try_position(|x| async move { x % 2 == 0 }).await, Ok(Some(2)));
+    /// # });
+    /// ```
+    fn try_position<Fut, F>(self, f: F) -> TryPosition<Self, Fut, F>
+    where
+        F: FnMut(Self::Ok) -> Fut,
+        Fut: Future<Output = bool>,
+        Self: Sized,
+    {
+        assert_future::<Result<Option<usize>, Self::Error>, _>(TryPosition::new(self, f))
+    }
+
+    /// Returns the last successful value of this stream, consuming it.
+    ///
+    /// If an error is encountered, it is returned immediately and the stream
+    /// is not polled any further. The returned future resolves to `Ok(None)`
+    /// if the stream is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, TryStreamExt};
+    ///
+    /// let stream = stream::iter(vec![Ok::<i32, i32>(1), Ok(2), Ok(3)]);
+    /// assert_eq!(stream.try_last().await, Ok(Some(3)));
+    /// # });
+    /// ```
+    fn try_last(self) -> TryLast<Self>
+    where
+        Self: Sized,
+    {
+        assert_future::<Result<Option<Self::Ok>, Self::Error>, _>(TryLast::new(self))
+    }
+
+    /// Returns the `n`th successful value of this stream, counting from zero.
+    ///
+    /// This borrows the stream like [`try_next`](TryStreamExt::try_next). If
+    /// an error is encountered first, it is returned immediately. The
+    /// returned future resolves to `Ok(None)` if the stream ends first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, TryStreamExt};
+    ///
+    /// let mut stream = stream::iter(vec![Ok::<i32, i32>(1), Ok(2), Err(3), Ok(4)]);
+    /// assert_eq!(stream.try_nth(1).await, Ok(Some(2)));
+    /// assert_eq!(stream.try_nth(1).await, Err(3));
+    /// # });
+    /// ```
+    fn try_nth(&mut self, n: usize) -> TryNth<'_, Self>
+    where
+        Self: Unpin,
+    {
+        assert_future::<Result<Option<Self::Ok>, Self::Error>, _>(TryNth::new(self, n))
+    }
+
+    /// Returns the successful value of this stream that gives the minimum
+    /// value of the provided key function.
+    ///
+    /// If several values are equally minimum, the first one is returned. If
+    /// an error is encountered, it is returned immediately.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, TryStreamExt};
+    ///
+    /// let stream = stream::iter(vec![Ok::<i32, i32>(-3), Ok(1), Ok(-10)]);
+    /// assert_eq!(stream.try_min_by_key(|x| x.abs()).await, Ok(Some(1)));
+    /// # });
+    /// ```
+    fn try_min_by_key<K, F>(self, f: F) -> TryMinByKey<Self, K, F>
+    where
+        F: FnMut(&Self::Ok) -> K,
+        K: Ord,
+        Self: Sized,
+    {
+        assert_future::<Result<Option<Self::Ok>, Self::Error>, _>(TryMinByKey::new(self, f))
+    }
+
+    /// Returns the successful value of this stream that gives the maximum
+    /// value of the provided key function.
+    ///
+    /// If several values are equally maximum, the last one is returned. If
+    /// an error is encountered, it is returned immediately.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, TryStreamExt};
+    ///
+    /// let stream = stream::iter(vec![Ok::<i32, i32>(-3), Ok(1), Ok(-10)]);
+    /// assert_eq!(stream.try_max_by_key(|x| x.abs()).await, Ok(Some(-10)));
+    /// # });
+    /// ```
+    fn try_max_by_key<K, F>(self, f: F) -> TryMaxByKey<Self, K, F>
+    where
+        F: FnMut(&Self::Ok) -> K,
+        K: Ord,
+        Self: Sized,
+    {
+        assert_future::<Result<Option<Self::Ok>, Self::Error>, _>(TryMaxByKey::new(self, f))
+    }
+
+    /// Reduces the successful values of this stream to a single one, by
+    /// repeatedly applying the provided fallible asynchronous closure.
+    ///
+    /// This is the fallible version of [`StreamExt::reduce`](crate::stream::StreamExt::reduce).
+    /// Errors from either the stream or the closure are returned immediately. The returned future resolves to `Ok(None)`
+    /// if the stream is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, TryStreamExt};
+    ///
+    /// let stream = stream::iter(vec![Ok::<i32, i32>(1), Ok(2), Ok(3)]);
+    /// let sum = stream.try_reduce(|acc, x| async move { Ok(acc + x) });
+    /// assert_eq!(sum.await, Ok(Some(6)));
+    /// # });
+    /// ```
+    fn try_reduce<Fut, F>(self, f: F) -> TryReduce<Self, Fut, F>
+    where
+        F: FnMut(Self::Ok, Self::Ok) -> Fut,
+        Fut: TryFuture<Ok = Self::Ok, Error = Self::Error>,
+        Self: Sized,
+    {
+        assert_future::<Result<Option<Self::Ok>, Self::Error>, _>(TryReduce::new(self, f))
+    }
+
+    /// Keeps polling this stream after it yields an error, for as long as
+    /// `policy` says to retry.
+    ///
+    /// Whenever this stream yields an error, `policy` is asked whether to
+    /// retry. If it returns a delay, the error is discarded and this stream
+    /// is polled again once the delay has elapsed, as measured by the `sleep`
+    /// timer. Otherwise, the error is yielded. This suits streams which
+    /// recover from their errors by themselves, e.g. by reconnecting.
+    ///
+    /// The attempts are counted, and the elapsed time is measured, from the
+    /// last item that was yielded.
+    ///
+    /// See [`future::retry`](crate::future::retry) to retry a future, and
+    /// [`Backoff`](crate::future::Backoff) for the usual retry policies.
+    ///
+    /// This method is only available when the `std` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::future::{self, Backoff};
+    /// use futures::stream::{self, StreamExt, TryStreamExt};
+    /// use std::time::Duration;
+    ///
+    /// let stream = stream::iter(vec![Ok(1), Err("a"), Err("b"), Ok(2), Err("c"), Err("d")]);
+    /// let policy = Backoff::constant(Duration::from_millis(10)).max_attempts(2);
+    /// let stream = stream.retry_with(policy, |_| future::ready(()));
+    ///
+    /// let items: Vec<_> = stream.collect().await;
+    /// assert_eq!(items, vec![Ok(1), Err("b"), Ok(2), Err("d")]);
+    /// # });
+    /// ```
+    #[cfg(feature = "std")]
+    fn retry_with<P, S>(self, policy: P, sleep: S) -> RetryWith<Self, P, S>
+    where
+        P: RetryPolicy<Self::Error>,
+        S: Sleep,
+        Self: Sized,
+    {
+        assert_stream::<Result<Self::Ok, Self::Error>, _>(RetryWith::new(self, policy, sleep))
+    }
+
     /// Attempt to execute several futures from a stream concurrently (unordered).
     ///
     /// This stream's `Ok` type must be a [`TryFuture`](futures_core::future::TryFuture) with an `Error` type
@@ -0,0 +1,125 @@
+use crate::future::{RetryPolicy, Sleep};
+use core::fmt;
+use core::pin::Pin;
+use futures_core::future::Future;
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream, TryStream};
+use futures_core::task::{Context, Poll};
+#[cfg(feature = "sink")]
+use futures_sink::Sink;
+use pin_project_lite::pin_project;
+use std::time::Instant;
+
+pin_project! {
+    /// Stream for the [`retry_with`](super::TryStreamExt::retry_with) method.
+    #[must_use = "streams do nothing unless polled"]
+    pub struct RetryWith<St, P, S: Sleep> {
+        #[pin]
+        stream: St,
+        #[pin]
+        delay: Option<S::Future>,
+        policy: P,
+        sleep: S,
+        attempt: usize,
+        start: Option<Instant>,
+    }
+}
+
+impl<St, P, S> fmt::Debug for RetryWith<St, P, S>
+where
+    St: fmt::Debug,
+    P: fmt::Debug,
+    S: Sleep,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryWith")
+            .field("stream", &self.stream)
+            .field("policy", &self.policy)
+            .field("attempt", &self.attempt)
+            .finish()
+    }
+}
+
+impl<St, P, S> RetryWith<St, P, S>
+where
+    St: TryStream,
+    P: RetryPolicy<St::Error>,
+    S: Sleep,
+{
+    pub(super) fn new(stream: St, policy: P, sleep: S) -> Self {
+        Self { stream, delay: None, policy, sleep, attempt: 0, start: None }
+    }
+
+    delegate_access_inner!(stream, St, ());
+}
+
+impl<St, P, S> FusedStream for RetryWith<St, P, S>
+where
+    St: TryStream + FusedStream,
+    P: RetryPolicy<St::Error>,
+    S: Sleep,
+{
+    fn is_terminated(&self) -> bool {
+        self.delay.is_none() && self.stream.is_terminated()
+    }
+}
+
+impl<St, P, S> Stream for RetryWith<St, P, S>
+where
+    St: TryStream,
+    P: RetryPolicy<St::Error>,
+    S: Sleep,
+{
+    type Item = Result<St::Ok, St::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            if let Some(delay) = this.delay.as_mut().as_pin_mut() {
+                ready!(delay.poll(cx));
+                this.delay.set(None);
+            }
+
+            let sleep = &*this.sleep;
+            let start = *this.start.get_or_insert_with(|| sleep.now());
+            let error = match ready!(this.stream.as_mut().try_poll_next(cx)) {
+                Some(Ok(item)) => {
+                    *this.attempt = 0;
+                    *this.start = None;
+                    return Poll::Ready(Some(Ok(item)));
+                }
+                Some(Err(error)) => error,
+                None => return Poll::Ready(None),
+            };
+
+            *this.attempt += 1;
+            let elapsed = this.sleep.now().duration_since(start);
+            match this.policy.retry(&error, *this.attempt, elapsed) {
+                Some(delay) => this.delay.set(Some(this.sleep.sleep(delay))),
+                None => {
+                    *this.attempt = 0;
+                    *this.start = None;
+                    return Poll::Ready(Some(Err(error)));
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (_, upper) = self.stream.size_hint();
+        (0, upper)
+    }
+}
+
+// Forwarding impl of Sink from the underlying stream
+#[cfg(feature = "sink")]
+impl<St, P, S, Item> Sink<Item> for RetryWith<St, P, S>
+where
+    St: Sink<Item>,
+    S: Sleep,
+{
+    type Error = St::Error;
+
+    delegate_sink!(stream, Item);
+}
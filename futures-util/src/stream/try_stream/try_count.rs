@@ -0,0 +1,41 @@
+use core::pin::Pin;
+use futures_core::future::{FusedFuture, Future};
+use futures_core::ready;
+use futures_core::stream::{FusedStream, TryStream};
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// Future for the [`try_count`](super::TryStreamExt::try_count) method.
+    #[derive(Debug)]
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct TryCount<St> {
+        #[pin]
+        stream: St,
+        count: usize,
+    }
+}
+
+impl<St: TryStream> TryCount<St> {
+    pub(super) fn new(stream: St) -> Self {
+        Self { stream, count: 0 }
+    }
+}
+
+impl<St: TryStream + FusedStream> FusedFuture for TryCount<St> {
+    fn is_terminated(&self) -> bool {
+        self.stream.is_terminated()
+    }
+}
+
+impl<St: TryStream> Future for TryCount<St> {
+    type Output = Result<usize, St::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        while ready!(this.stream.as_mut().try_poll_next(cx)?).is_some() {
+            *this.count += 1;
+        }
+        Poll::Ready(Ok(*this.count))
+    }
+}
@@ -0,0 +1,106 @@
+use crate::fns::FnMut1;
+use core::fmt;
+use core::pin::Pin;
+use futures_core::future::{FusedFuture, Future};
+use futures_core::ready;
+use futures_core::stream::TryStream;
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// Future for the [`try_find`](super::TryStreamExt::try_find) method.
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct TryFind<St, Fut, F>
+        where St: TryStream,
+    {
+        #[pin]
+        stream: St,
+        f: F,
+        done: bool,
+        #[pin]
+        pending_fut: Option<Fut>,
+        pending_item: Option<St::Ok>,
+    }
+}
+
+impl<St, Fut, F> fmt::Debug for TryFind<St, Fut, F>
+where
+    St: TryStream + fmt::Debug,
+    St::Ok: fmt::Debug,
+    Fut: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TryFind")
+            .field("stream", &self.stream)
+            .field("done", &self.done)
+            .field("pending_fut", &self.pending_fut)
+            .field("pending_item", &self.pending_item)
+            .finish()
+    }
+}
+
+#[allow(single_use_lifetimes)] // https://github.com/rust-lang/rust/issues/55058
+impl<St, Fut, F> TryFind<St, Fut, F>
+where
+    St: TryStream,
+    F: for<'a> FnMut1<&'a St::Ok, Output = Fut>,
+    Fut: Future<Output = bool>,
+{
+    pub(super) fn new(stream: St, f: F) -> Self {
+        Self { stream, f, done: false, pending_fut: None, pending_item: None }
+    }
+}
+
+#[allow(single_use_lifetimes)] // https://github.com/rust-lang/rust/issues/55058
+impl<St, Fut, F> FusedFuture for TryFind<St, Fut, F>
+where
+    St: TryStream,
+    F: for<'a> FnMut1<&'a St::Ok, Output = Fut>,
+    Fut: Future<Output = bool>,
+{
+    fn is_terminated(&self) -> bool {
+        self.done && self.pending_fut.is_none()
+    }
+}
+
+#[allow(single_use_lifetimes)] // https://github.com/rust-lang/rust/issues/55058
+impl<St, Fut, F> Future for TryFind<St, Fut, F>
+where
+    St: TryStream,
+    F: for<'a> FnMut1<&'a St::Ok, Output = Fut>,
+    Fut: Future<Output = bool>,
+{
+    type Output = Result<Option<St::Ok>, St::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        Poll::Ready(loop {
+            if let Some(fut) = this.pending_fut.as_mut().as_pin_mut() {
+                let res = ready!(fut.poll(cx));
+                this.pending_fut.set(None);
+                if res {
+                    *this.done = true;
+                    break Ok(this.pending_item.take());
+                }
+                *this.pending_item = None;
+            } else if !*this.done {
+                match ready!(this.stream.as_mut().try_poll_next(cx)) {
+                    Some(Ok(item)) => {
+                        this.pending_fut.set(Some(this.f.call_mut(&item)));
+                        *this.pending_item = Some(item);
+                    }
+                    Some(Err(e)) => {
+                        *this.done = true;
+                        break Err(e);
+                    }
+                    None => {
+                        *this.done = true;
+                        break Ok(None);
+                    }
+                }
+            } else {
+                panic!("TryFind polled after completion")
+            }
+        })
+    }
+}
@@ -0,0 +1,138 @@
+use crate::stream::{Fuse, FuturesUnordered, IntoStream, StreamExt, StreamFuture, YIELD_EVERY};
+use alloc::boxed::Box;
+use core::fmt;
+use core::num::NonZeroUsize;
+use core::pin::Pin;
+use futures_core::stream::{FusedStream, Stream, TryStream};
+use futures_core::task::{Context, Poll};
+#[cfg(feature = "sink")]
+use futures_sink::Sink;
+use pin_project_lite::pin_project;
+
+pin_project! {
+    #[must_use = "streams do nothing unless polled"]
+    pub struct TryFlattenUnordered<St, U> {
+        #[pin]
+        stream: Fuse<IntoStream<St>>,
+        inner_streams: FuturesUnordered<StreamFuture<Pin<Box<IntoStream<U>>>>>,
+        limit: Option<NonZeroUsize>,
+        // Set once an error has been yielded.
+        terminated: bool,
+    }
+}
+
+impl<St, U> fmt::Debug for TryFlattenUnordered<St, U>
+where
+    St: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TryFlattenUnordered")
+            .field("stream", &self.stream)
+            .field("inner_streams", &self.inner_streams.len())
+            .field("limit", &self.limit)
+            .field("terminated", &self.terminated)
+            .finish()
+    }
+}
+
+impl<St, U> TryFlattenUnordered<St, U> {
+    pub(super) fn new(stream: St, limit: Option<usize>) -> Self {
+        Self {
+            stream: Fuse::new(IntoStream::new(stream)),
+            inner_streams: FuturesUnordered::new(),
+            limit: limit.and_then(NonZeroUsize::new),
+            terminated: false,
+        }
+    }
+
+    delegate_access_inner!(stream, St, (. .));
+}
+
+impl<St, U> Stream for TryFlattenUnordered<St, U>
+where
+    St: TryStream<Ok = U>,
+    U: TryStream,
+    U::Error: From<St::Error>,
+{
+    type Item = Result<U::Ok, U::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        if *this.terminated {
+            return Poll::Ready(None);
+        }
+
+        let mut pulled = 0;
+        let error = 'poll: loop {
+            // Start polling as many inner streams as the limit allows.
+            while this.limit.map(|limit| this.inner_streams.len() < limit.get()).unwrap_or(true) {
+                if pulled == YIELD_EVERY {
+                    // The stream may always be ready, let other tasks run
+                    // before taking more streams from it.
+                    cx.waker().wake_by_ref();
+                    break;
+                }
+                match this.stream.as_mut().poll_next(cx) {
+                    Poll::Ready(Some(Ok(stream))) => {
+                        pulled += 1;
+                        this.inner_streams.push(Box::pin(IntoStream::new(stream)).into_future())
+                    }
+                    Poll::Ready(Some(Err(e))) => break 'poll e.into(),
+                    Poll::Ready(None) | Poll::Pending => break,
+                }
+            }
+
+            match this.inner_streams.poll_next_unpin(cx) {
+                Poll::Ready(Some((Some(Ok(item)), stream))) => {
+                    this.inner_streams.push(stream.into_future());
+                    return Poll::Ready(Some(Ok(item)));
+                }
+                Poll::Ready(Some((Some(Err(e)), _))) => break e,
+                // An inner stream has ended, which may make room for another
+                // one.
+                Poll::Ready(Some((None, _))) => {}
+                Poll::Ready(None) => {
+                    // If more streams are still coming from the stream, we're
+                    // not done yet
+                    return if this.stream.is_done() { Poll::Ready(None) } else { Poll::Pending };
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        };
+
+        // Short-circuit: drop the remaining streams.
+        *this.terminated = true;
+        this.inner_streams.clear();
+        Poll::Ready(Some(Err(error)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.is_terminated() {
+            (0, Some(0))
+        } else {
+            (0, None)
+        }
+    }
+}
+
+impl<St, U> FusedStream for TryFlattenUnordered<St, U>
+where
+    St: TryStream<Ok = U>,
+    U: TryStream,
+    U::Error: From<St::Error>,
+{
+    fn is_terminated(&self) -> bool {
+        self.terminated || (self.stream.is_terminated() && self.inner_streams.is_empty())
+    }
+}
+
+// Forwarding impl of Sink from the underlying stream
+#[cfg(feature = "sink")]
+impl<S, U, Item> Sink<Item> for TryFlattenUnordered<S, U>
+where
+    S: TryStream + Sink<Item>,
+{
+    type Error = <S as Sink<Item>>::Error;
+
+    delegate_sink!(stream, Item);
+}
@@ -0,0 +1,53 @@
+use core::fmt;
+use core::pin::Pin;
+use futures_core::future::{FusedFuture, Future};
+use futures_core::ready;
+use futures_core::stream::{FusedStream, TryStream};
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// Future for the [`try_last`](super::TryStreamExt::try_last) method.
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct TryLast<St>
+        where St: TryStream,
+    {
+        #[pin]
+        stream: St,
+        last: Option<St::Ok>,
+    }
+}
+
+impl<St> fmt::Debug for TryLast<St>
+where
+    St: TryStream + fmt::Debug,
+    St::Ok: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TryLast").field("stream", &self.stream).field("last", &self.last).finish()
+    }
+}
+
+impl<St: TryStream> TryLast<St> {
+    pub(super) fn new(stream: St) -> Self {
+        Self { stream, last: None }
+    }
+}
+
+impl<St: TryStream + FusedStream> FusedFuture for TryLast<St> {
+    fn is_terminated(&self) -> bool {
+        self.stream.is_terminated()
+    }
+}
+
+impl<St: TryStream> Future for TryLast<St> {
+    type Output = Result<Option<St::Ok>, St::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        while let Some(item) = ready!(this.stream.as_mut().try_poll_next(cx)?) {
+            *this.last = Some(item);
+        }
+        Poll::Ready(Ok(this.last.take()))
+    }
+}
@@ -0,0 +1,74 @@
+use core::fmt;
+use core::pin::Pin;
+use futures_core::future::{FusedFuture, Future};
+use futures_core::ready;
+use futures_core::stream::{FusedStream, TryStream};
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// Future for the [`try_min_by_key`](super::TryStreamExt::try_min_by_key) method.
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct TryMinByKey<St, K, F>
+        where St: TryStream,
+    {
+        #[pin]
+        stream: St,
+        f: F,
+        min: Option<(K, St::Ok)>,
+    }
+}
+
+impl<St, K, F> fmt::Debug for TryMinByKey<St, K, F>
+where
+    St: TryStream + fmt::Debug,
+    St::Ok: fmt::Debug,
+    K: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TryMinByKey").field("stream", &self.stream).field("min", &self.min).finish()
+    }
+}
+
+impl<St, K, F> TryMinByKey<St, K, F>
+where
+    St: TryStream,
+    F: FnMut(&St::Ok) -> K,
+    K: Ord,
+{
+    pub(super) fn new(stream: St, f: F) -> Self {
+        Self { stream, f, min: None }
+    }
+}
+
+impl<St, K, F> FusedFuture for TryMinByKey<St, K, F>
+where
+    St: TryStream + FusedStream,
+    F: FnMut(&St::Ok) -> K,
+    K: Ord,
+{
+    fn is_terminated(&self) -> bool {
+        self.stream.is_terminated()
+    }
+}
+
+impl<St, K, F> Future for TryMinByKey<St, K, F>
+where
+    St: TryStream,
+    F: FnMut(&St::Ok) -> K,
+    K: Ord,
+{
+    type Output = Result<Option<St::Ok>, St::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        while let Some(item) = ready!(this.stream.as_mut().try_poll_next(cx)?) {
+            let key = (this.f)(&item);
+            // the first of several minimal items is kept
+            if this.min.as_ref().map_or(true, |(min, _)| key < *min) {
+                *this.min = Some((key, item));
+            }
+        }
+        Poll::Ready(Ok(this.min.take().map(|(_, item)| item)))
+    }
+}
@@ -0,0 +1,42 @@
+use crate::stream::TryStreamExt;
+use core::pin::Pin;
+use futures_core::future::{FusedFuture, Future};
+use futures_core::ready;
+use futures_core::stream::{FusedStream, TryStream};
+use futures_core::task::{Context, Poll};
+
+/// Future for the [`try_nth`](super::TryStreamExt::try_nth) method.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct TryNth<'a, St: ?Sized> {
+    stream: &'a mut St,
+    n: usize,
+}
+
+impl<St: ?Sized + Unpin> Unpin for TryNth<'_, St> {}
+
+impl<'a, St: ?Sized + TryStream + Unpin> TryNth<'a, St> {
+    pub(super) fn new(stream: &'a mut St, n: usize) -> Self {
+        Self { stream, n }
+    }
+}
+
+impl<St: ?Sized + TryStream + Unpin + FusedStream> FusedFuture for TryNth<'_, St> {
+    fn is_terminated(&self) -> bool {
+        self.stream.is_terminated()
+    }
+}
+
+impl<St: ?Sized + TryStream + Unpin> Future for TryNth<'_, St> {
+    type Output = Result<Option<St::Ok>, St::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            match ready!(self.stream.try_poll_next_unpin(cx)?) {
+                Some(item) if self.n == 0 => return Poll::Ready(Ok(Some(item))),
+                Some(_) => self.n -= 1,
+                None => return Poll::Ready(Ok(None)),
+            }
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use core::fmt;
+use core::pin::Pin;
+use futures_core::future::{FusedFuture, Future, TryFuture};
+use futures_core::ready;
+use futures_core::stream::TryStream;
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// Future for the [`try_reduce`](super::TryStreamExt::try_reduce) method.
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct TryReduce<St, Fut, F>
+        where St: TryStream,
+    {
+        #[pin]
+        stream: St,
+        f: F,
+        accum: Option<St::Ok>,
+        done: bool,
+        #[pin]
+        future: Option<Fut>,
+    }
+}
+
+impl<St, Fut, F> fmt::Debug for TryReduce<St, Fut, F>
+where
+    St: TryStream + fmt::Debug,
+    St::Ok: fmt::Debug,
+    Fut: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TryReduce")
+            .field("stream", &self.stream)
+            .field("accum", &self.accum)
+            .field("done", &self.done)
+            .field("future", &self.future)
+            .finish()
+    }
+}
+
+impl<St, Fut, F> TryReduce<St, Fut, F>
+where
+    St: TryStream,
+    F: FnMut(St::Ok, St::Ok) -> Fut,
+    Fut: TryFuture<Ok = St::Ok, Error = St::Error>,
+{
+    pub(super) fn new(stream: St, f: F) -> Self {
+        Self { stream, f, accum: None, done: false, future: None }
+    }
+}
+
+impl<St, Fut, F> FusedFuture for TryReduce<St, Fut, F>
+where
+    St: TryStream,
+    F: FnMut(St::Ok, St::Ok) -> Fut,
+    Fut: TryFuture<Ok = St::Ok, Error = St::Error>,
+{
+    fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
+
+impl<St, Fut, F> Future for TryReduce<St, Fut, F>
+where
+    St: TryStream,
+    F: FnMut(St::Ok, St::Ok) -> Fut,
+    Fut: TryFuture<Ok = St::Ok, Error = St::Error>,
+{
+    type Output = Result<Option<St::Ok>, St::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        Poll::Ready(loop {
+            if let Some(fut) = this.future.as_mut().as_pin_mut() {
+                // we're currently processing a future to produce a new accum value
+                let res = ready!(fut.try_poll(cx));
+                this.future.set(None);
+                match res {
+                    Ok(accum) => *this.accum = Some(accum),
+                    Err(e) => {
+                        *this.done = true;
+                        break Err(e);
+                    }
+                }
+            } else if !*this.done {
+                // we're waiting on a new item from the stream
+                match ready!(this.stream.as_mut().try_poll_next(cx)) {
+                    Some(Ok(item)) => match this.accum.take() {
+                        Some(accum) => this.future.set(Some((this.f)(accum, item))),
+                        None => *this.accum = Some(item),
+                    },
+                    Some(Err(e)) => {
+                        *this.done = true;
+                        break Err(e);
+                    }
+                    None => {
+                        *this.done = true;
+                        break Ok(this.accum.take());
+                    }
+                }
+            } else {
+                panic!("TryReduce polled after completion")
+            }
+        })
+    }
+}
@@ -0,0 +1,154 @@
+//! Zipping of any number of streams
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::pin::Pin;
+
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+
+use super::assert_stream;
+use crate::stream::StreamExt;
+
+/// Stream for the [`zip_all`] function.
+#[must_use = "streams do nothing unless polled"]
+pub struct ZipAll<St: Stream> {
+    streams: Vec<St>,
+    queued: Vec<Option<St::Item>>,
+    done: bool,
+}
+
+// The streams are required to be `Unpin`, and nothing else is pinned.
+impl<St: Stream> Unpin for ZipAll<St> {}
+
+impl<St> fmt::Debug for ZipAll<St>
+where
+    St: Stream + fmt::Debug,
+    St::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ZipAll")
+            .field("streams", &self.streams)
+            .field("queued", &self.queued)
+            .field("done", &self.done)
+            .finish()
+    }
+}
+
+impl<St: Stream + Unpin> ZipAll<St> {
+    /// Acquires a reference to the underlying streams that this combinator is
+    /// pulling from.
+    pub fn get_ref(&self) -> &[St] {
+        &self.streams
+    }
+
+    /// Acquires a mutable reference to the underlying streams that this
+    /// combinator is pulling from.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the
+    /// stream which may otherwise confuse this combinator.
+    pub fn get_mut(&mut self) -> &mut [St] {
+        &mut self.streams
+    }
+
+    /// Consumes this combinator, returning the underlying streams.
+    ///
+    /// Note that this may discard intermediate state of this combinator, so
+    /// care should be taken to avoid losing resources when this is called.
+    pub fn into_inner(self) -> Vec<St> {
+        self.streams
+    }
+}
+
+impl<St: Stream + Unpin> Stream for ZipAll<St> {
+    type Item = Vec<St::Item>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        if this.done {
+            return Poll::Ready(None);
+        }
+
+        let mut pending = false;
+        for (stream, queued) in this.streams.iter_mut().zip(this.queued.iter_mut()) {
+            if queued.is_none() {
+                match stream.poll_next_unpin(cx) {
+                    Poll::Ready(Some(item)) => *queued = Some(item),
+                    Poll::Ready(None) => {
+                        // This stream can't complete the current round.
+                        this.done = true;
+                        this.queued.clear();
+                        return Poll::Ready(None);
+                    }
+                    Poll::Pending => pending = true,
+                }
+            }
+        }
+
+        if pending {
+            Poll::Pending
+        } else {
+            Poll::Ready(Some(this.queued.iter_mut().map(|queued| queued.take().unwrap()).collect()))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.done {
+            return (0, Some(0));
+        }
+
+        self.streams.iter().zip(&self.queued).fold(
+            (usize::max_value(), None),
+            |(lower, upper), (stream, queued)| {
+                let queued_len = if queued.is_some() { 1 } else { 0 };
+                let (l, u) = stream.size_hint();
+                let l = l.saturating_add(queued_len);
+                let u = u.and_then(|u| u.checked_add(queued_len));
+                let upper = match (upper, u) {
+                    (Some(x), Some(y)) => Some(x.min(y)),
+                    (x, y) => x.or(y),
+                };
+                (lower.min(l), upper)
+            },
+        )
+    }
+}
+
+impl<St: Stream + Unpin> FusedStream for ZipAll<St> {
+    fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
+
+/// Zips any number of streams together.
+///
+/// The returned stream polls all the streams concurrently, waits for each of
+/// them to produce an item, and then returns the items in a vector, in the
+/// order of the streams. If any stream ends, the returned stream ends as
+/// well, and so does it right away if there are no streams at all.
+///
+/// This function is only available when the `std` or `alloc` feature of this
+/// library is activated, and it is activated by default.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::stream::{self, StreamExt};
+///
+/// let streams = vec![stream::iter(1..=3), stream::iter(4..=6), stream::iter(7..=8)];
+/// let rows = stream::zip_all(streams).collect::<Vec<_>>().await;
+/// assert_eq!(rows, vec![vec![1, 4, 7], vec![2, 5, 8]]);
+/// # });
+/// ```
+pub fn zip_all<I>(streams: I) -> ZipAll<I::Item>
+where
+    I: IntoIterator,
+    I::Item: Stream + Unpin,
+{
+    let streams: Vec<_> = streams.into_iter().collect();
+    let mut queued = Vec::new();
+    queued.resize_with(streams.len(), || None);
+    let done = streams.is_empty();
+    assert_stream::<Vec<<I::Item as Stream>::Item>, _>(ZipAll { streams, queued, done })
+}
@@ -13,7 +13,13 @@
 #[doc(no_inline)]
 pub use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
 
-pub use futures_task::{FutureObj, LocalFutureObj, LocalSpawn, Spawn, SpawnError, UnsafeFutureObj};
+pub use futures_task::{
+    FutureObj, LocalFutureObj, LocalSpawn, Spawn, SpawnError, SpawnOptions, TaskId, UnsafeFutureObj,
+};
+
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "std")]
+pub use futures_task::{current, set_current, TaskInfo};
 
 pub use futures_task::noop_waker;
 #[cfg(feature = "std")]
@@ -11,7 +11,7 @@ use alloc::boxed::Box;
 #[cfg(feature = "alloc")]
 use futures_core::future::Future;
 #[cfg(feature = "alloc")]
-use futures_task::{FutureObj, LocalFutureObj, SpawnError};
+use futures_task::{FutureObj, LocalFutureObj, SpawnError, SpawnOptions};
 
 impl<Sp: ?Sized> SpawnExt for Sp where Sp: Spawn {}
 impl<Sp: ?Sized> LocalSpawnExt for Sp where Sp: LocalSpawn {}
@@ -43,11 +43,38 @@ pub trait SpawnExt: Spawn {
     /// executor.spawn(future).unwrap();
     /// ```
     #[cfg(feature = "alloc")]
+    #[cfg_attr(track_caller, track_caller)]
     fn spawn<Fut>(&self, future: Fut) -> Result<(), SpawnError>
     where
         Fut: Future<Output = ()> + Send + 'static,
     {
-        self.spawn_obj(FutureObj::new(Box::new(future)))
+        self.spawn_obj_with(FutureObj::new(Box::new(future)), SpawnOptions::new().at_caller())
+    }
+
+    /// Spawns a task that polls the given future with output `()` to
+    /// completion, configured by the given [`SpawnOptions`].
+    ///
+    /// This method returns a [`Result`] that contains a [`SpawnError`] if
+    /// spawning fails.
+    ///
+    /// ```
+    /// use futures::executor::ThreadPool;
+    /// use futures::task::{self, SpawnExt, SpawnOptions};
+    ///
+    /// let executor = ThreadPool::new().unwrap();
+    ///
+    /// let future = async {
+    ///     assert_eq!(task::current().unwrap().name(), Some("worker"));
+    /// };
+    /// executor.spawn_with(SpawnOptions::new().name("worker"), future).unwrap();
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(track_caller, track_caller)]
+    fn spawn_with<Fut>(&self, options: SpawnOptions<'_>, future: Fut) -> Result<(), SpawnError>
+    where
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.spawn_obj_with(FutureObj::new(Box::new(future)), options.at_caller())
     }
 
     /// Spawns a task that polls the given future to completion and returns a
@@ -71,6 +98,7 @@ pub trait SpawnExt: Spawn {
     #[cfg(feature = "channel")]
     #[cfg_attr(docsrs, doc(cfg(feature = "channel")))]
     #[cfg(feature = "std")]
+    #[cfg_attr(track_caller, track_caller)]
     fn spawn_with_handle<Fut>(&self, future: Fut) -> Result<RemoteHandle<Fut::Output>, SpawnError>
     where
         Fut: Future + Send + 'static,
@@ -121,11 +149,47 @@ pub trait LocalSpawnExt: LocalSpawn {
     /// spawner.spawn_local(future).unwrap();
     /// ```
     #[cfg(feature = "alloc")]
+    #[cfg_attr(track_caller, track_caller)]
     fn spawn_local<Fut>(&self, future: Fut) -> Result<(), SpawnError>
     where
         Fut: Future<Output = ()> + 'static,
     {
-        self.spawn_local_obj(LocalFutureObj::new(Box::new(future)))
+        self.spawn_local_obj_with(
+            LocalFutureObj::new(Box::new(future)),
+            SpawnOptions::new().at_caller(),
+        )
+    }
+
+    /// Spawns a task that polls the given future with output `()` to
+    /// completion, configured by the given [`SpawnOptions`].
+    ///
+    /// This method returns a [`Result`] that contains a [`SpawnError`] if
+    /// spawning fails.
+    ///
+    /// ```
+    /// use futures::executor::LocalPool;
+    /// use futures::task::{self, LocalSpawnExt, SpawnOptions};
+    ///
+    /// let mut executor = LocalPool::new();
+    /// let spawner = executor.spawner();
+    ///
+    /// let future = async {
+    ///     assert_eq!(task::current().unwrap().name(), Some("worker"));
+    /// };
+    /// spawner.spawn_local_with(SpawnOptions::new().name("worker"), future).unwrap();
+    /// executor.run();
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(track_caller, track_caller)]
+    fn spawn_local_with<Fut>(
+        &self,
+        options: SpawnOptions<'_>,
+        future: Fut,
+    ) -> Result<(), SpawnError>
+    where
+        Fut: Future<Output = ()> + 'static,
+    {
+        self.spawn_local_obj_with(LocalFutureObj::new(Box::new(future)), options.at_caller())
     }
 
     /// Spawns a task that polls the given future to completion and returns a
@@ -149,6 +213,7 @@ pub trait LocalSpawnExt: LocalSpawn {
     #[cfg(feature = "channel")]
     #[cfg_attr(docsrs, doc(cfg(feature = "channel")))]
     #[cfg(feature = "std")]
+    #[cfg_attr(track_caller, track_caller)]
     fn spawn_local_with_handle<Fut>(
         &self,
         future: Fut,
@@ -6,8 +6,9 @@
     feature = "io-compat",
     feature = "executor",
     feature = "thread-pool",
+    feature = "net",
 )))]
 compile_error!(
     "`futures` tests must have all stable features activated: \
-    use `--all-features` or `--features default,thread-pool,io-compat`"
+    use `--all-features` or `--features default,thread-pool,io-compat,net`"
 );
@@ -189,6 +189,10 @@ pub mod executor {
     // BlockingStream requires `S: Unpin`
     // assert_not_impl!(BlockingStream<PinnedStream>: Unpin);
 
+    assert_impl!(Elapsed: Send);
+    assert_impl!(Elapsed: Sync);
+    assert_impl!(Elapsed: Unpin);
+
     assert_impl!(Enter: Send);
     assert_impl!(Enter: Sync);
     assert_impl!(Enter: Unpin);
@@ -205,6 +209,22 @@ pub mod executor {
     assert_not_impl!(LocalSpawner: Sync);
     assert_impl!(LocalSpawner: Unpin);
 
+    assert_impl!(LocalPoolGroup: Send);
+    assert_impl!(LocalPoolGroup: Sync);
+    assert_impl!(LocalPoolGroup: Unpin);
+
+    assert_impl!(Priority: Send);
+    assert_impl!(Priority: Sync);
+    assert_impl!(Priority: Unpin);
+
+    assert_impl!(RemoteSpawner: Send);
+    assert_impl!(RemoteSpawner: Sync);
+    assert_impl!(RemoteSpawner: Unpin);
+
+    assert_impl!(Stalled: Send);
+    assert_impl!(Stalled: Sync);
+    assert_impl!(Stalled: Unpin);
+
     assert_impl!(ThreadPool: Send);
     assert_impl!(ThreadPool: Sync);
     assert_impl!(ThreadPool: Unpin);
@@ -1857,6 +1877,18 @@ pub mod task {
     assert_impl!(SpawnError: Sync);
     assert_impl!(SpawnError: Unpin);
 
+    assert_impl!(SpawnOptions<'_>: Send);
+    assert_impl!(SpawnOptions<'_>: Sync);
+    assert_impl!(SpawnOptions<'_>: Unpin);
+
+    assert_impl!(TaskId: Send);
+    assert_impl!(TaskId: Sync);
+    assert_impl!(TaskId: Unpin);
+
+    assert_impl!(TaskInfo: Send);
+    assert_impl!(TaskInfo: Sync);
+    assert_impl!(TaskInfo: Unpin);
+
     assert_impl!(WakerRef<'_>: Send);
     assert_impl!(WakerRef<'_>: Sync);
     assert_impl!(WakerRef<'_>: Unpin);
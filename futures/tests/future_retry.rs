@@ -0,0 +1,91 @@
+use futures::executor::block_on;
+use futures::future::{self, Backoff, RetryPolicy};
+use futures::stream::{self, StreamExt, TryStreamExt};
+use futures_test::sim::{self, SimExecutor, Timer};
+use std::cell::RefCell;
+use std::time::Duration;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+fn delays(mut policy: impl RetryPolicy<()>, attempts: usize) -> Vec<Option<Duration>> {
+    (1..=attempts).map(|attempt| policy.retry(&(), attempt, Duration::from_secs(0))).collect()
+}
+
+#[test]
+fn backoff() {
+    assert_eq!(delays(Backoff::constant(ms(10)), 3), [Some(ms(10)); 3]);
+    assert_eq!(
+        delays(Backoff::exponential(ms(10), 3).max_attempts(4), 4),
+        [Some(ms(10)), Some(ms(30)), Some(ms(90)), None]
+    );
+    assert_eq!(
+        delays(Backoff::exponential(ms(10), 10).max_delay(ms(500)), 4),
+        [Some(ms(10)), Some(ms(100)), Some(ms(500)), Some(ms(500))]
+    );
+    // overflowing delays are capped as well
+    assert_eq!(delays(Backoff::exponential(ms(10), 10).max_delay(ms(500)), 100)[99], Some(ms(500)));
+
+    let mut policy = Backoff::constant(ms(10)).max_elapsed(ms(100));
+    assert_eq!(policy.retry(&(), 1, ms(99)), Some(ms(10)));
+    assert_eq!(policy.retry(&(), 2, ms(100)), None);
+}
+
+#[test]
+fn backoff_jitter() {
+    let delays = delays(Backoff::exponential(ms(10), 2).jitter(), 100);
+    for (attempt, delay) in delays.into_iter().enumerate().take(10) {
+        assert!(delay.unwrap() <= ms(10) * 2u32.pow(attempt as u32));
+    }
+}
+
+#[test]
+fn retry() {
+    let mut executor = SimExecutor::new(0);
+    let attempts = RefCell::new(Vec::new());
+    let future = future::retry(
+        || {
+            attempts.borrow_mut().push(sim::now());
+            future::ready(if attempts.borrow().len() < 4 { Err(()) } else { Ok(4) })
+        },
+        Backoff::exponential(ms(100), 2),
+        Timer,
+    );
+
+    assert_eq!(executor.run_until(future), Ok(4));
+    assert_eq!(*attempts.borrow(), [ms(0), ms(100), ms(300), ms(700)]);
+}
+
+#[test]
+fn retry_gives_up() {
+    // on some errors
+    let mut attempts = 0;
+    let future = future::retry(
+        || {
+            attempts += 1;
+            future::err::<(), _>(attempts)
+        },
+        |error: &i32, _, _| if *error < 3 { Some(Duration::from_secs(0)) } else { None },
+        |_| future::ready(()),
+    );
+    assert_eq!(block_on(future), Err(3));
+
+    // once enough time has elapsed
+    let mut executor = SimExecutor::new(0);
+    let policy = Backoff::constant(ms(40)).max_elapsed(ms(100));
+    let future = future::retry(|| future::err::<(), _>(sim::now()), policy, Timer);
+    assert_eq!(executor.run_until(future), Err(ms(120)));
+}
+
+#[test]
+fn retry_with() {
+    let mut executor = SimExecutor::new(0);
+    let stream = stream::iter(vec![Err(1), Ok(2), Err(3), Err(4), Err(5), Ok(6)]);
+    let stream = stream.map_err(|e| (e, sim::now()));
+    let policy = Backoff::constant(ms(10)).max_attempts(2);
+
+    let items = executor.run_until(stream.retry_with(policy, Timer).collect::<Vec<_>>());
+    assert_eq!(items, [Ok(2), Err((4, ms(20))), Ok(6)]);
+    assert_eq!(executor.now(), ms(30));
+}
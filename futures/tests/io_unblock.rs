@@ -0,0 +1,194 @@
+use futures::executor::{block_on, LocalPool};
+use futures::future::poll_fn;
+use futures::io::{
+    AsyncBufReadExt, AsyncReadExt, AsyncSeek, AsyncSeekExt, AsyncWriteExt, SeekFrom, Unblock,
+};
+use futures::stream::StreamExt;
+use futures::task::LocalSpawnExt;
+use futures_test::task::noop_context;
+use std::io::{self, Cursor, Read, Write};
+use std::pin::Pin;
+use std::sync::mpsc;
+
+#[test]
+fn read_write_seek() {
+    block_on(async {
+        let mut io = Unblock::with_capacity(4, Cursor::new(Vec::new()));
+        io.write_all(b"hello world").await.unwrap();
+        assert_eq!(io.seek(SeekFrom::Start(0)).await.unwrap(), 0);
+
+        let mut buf = [0; 2];
+        io.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"he");
+        // the data read ahead is taken into account
+        assert_eq!(io.seek(SeekFrom::Current(0)).await.unwrap(), 2);
+
+        io.write_all(b"LLO").await.unwrap();
+        assert_eq!(io.seek(SeekFrom::End(-5)).await.unwrap(), 6);
+        let mut rest = String::new();
+        io.read_to_string(&mut rest).await.unwrap();
+        assert_eq!(rest, "world");
+
+        assert_eq!(io.into_inner().into_inner(), b"heLLO world");
+    });
+}
+
+#[test]
+fn write_after_read_ahead_needs_a_seek() {
+    block_on(async {
+        let mut io = Unblock::with_capacity(4, Cursor::new(b"hello world".to_vec()));
+        let mut buf = [0; 2];
+        io.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"he");
+
+        let err = io.write_all(b"__").await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        // reading goes on where it stopped
+        let mut buf = [0; 3];
+        io.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"llo");
+
+        assert_eq!(io.seek(SeekFrom::Current(0)).await.unwrap(), 5);
+        io.write_all(b"_W").await.unwrap();
+        assert_eq!(io.into_inner().into_inner(), b"hello_World");
+    });
+}
+
+#[test]
+fn write_after_reading_everything() {
+    block_on(async {
+        let mut io = Unblock::with_capacity(4, Cursor::new(b"abc".to_vec()));
+        let mut buf = Vec::new();
+        io.read_to_end(&mut buf).await.unwrap();
+        io.write_all(b"def").await.unwrap();
+        assert_eq!(io.into_inner().into_inner(), b"abcdef");
+    });
+}
+
+#[test]
+fn read_larger_than_capacity() {
+    let data: Vec<u8> = (0..10_000).map(|i| i as u8).collect();
+    let mut io = Unblock::with_capacity(16, Cursor::new(data.clone()));
+    let mut buf = Vec::new();
+    assert_eq!(block_on(io.read_to_end(&mut buf)).unwrap(), data.len());
+    assert!(buf == data);
+}
+
+#[test]
+fn buf_read_lines() {
+    let io = Unblock::with_capacity(3, Cursor::new(b"one\ntwo\nthree".to_vec()));
+    let lines = block_on(io.lines().map(|line| line.unwrap()).collect::<Vec<_>>());
+    assert_eq!(lines, ["one", "two", "three"]);
+}
+
+// Blocks on reads until the test sends data.
+struct ChannelReader(mpsc::Receiver<Vec<u8>>);
+
+impl Read for ChannelReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let data = self.0.recv().unwrap_or_default();
+        buf[..data.len()].copy_from_slice(&data);
+        Ok(data.len())
+    }
+}
+
+#[test]
+fn blocking_read_does_not_block_executor() {
+    let (tx, rx) = mpsc::channel();
+    let mut io = Unblock::new(ChannelReader(rx));
+    let (done_tx, mut done_rx) = futures::channel::oneshot::channel();
+
+    let mut pool = LocalPool::new();
+    pool.spawner()
+        .spawn_local(async move {
+            let mut buf = [0; 5];
+            io.read_exact(&mut buf).await.unwrap();
+            done_tx.send(buf).unwrap();
+        })
+        .unwrap();
+    pool.run_until_stalled();
+    assert_eq!(done_rx.try_recv(), Ok(None));
+
+    tx.send(b"hel".to_vec()).unwrap();
+    tx.send(b"lo".to_vec()).unwrap();
+    assert_eq!(pool.run_until(done_rx), Ok(*b"hello"));
+}
+
+struct FailingWriter;
+
+impl Write for FailingWriter {
+    fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+        Err(io::Error::new(io::ErrorKind::Other, "disk full"))
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn write_error_is_reported() {
+    block_on(async {
+        let mut io = Unblock::new(FailingWriter);
+        // the write completes once the data is buffered
+        io.write_all(b"data").await.unwrap();
+        let err = io.flush().await.unwrap_err();
+        assert_eq!(err.to_string(), "disk full");
+    });
+}
+
+struct PanickingWriter;
+
+impl Write for PanickingWriter {
+    fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+        panic!("oops")
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn helper_panic_is_reported() {
+    block_on(async {
+        let mut io = Unblock::new(PanickingWriter);
+        io.write_all(b"data").await.unwrap();
+        assert!(io.close().await.is_err());
+        // The helper thread is gone. Depending on whether it has finished
+        // unwinding, the write fails at once or once it is flushed.
+        let res = match io.write_all(b"data").await {
+            Ok(()) => io.flush().await,
+            Err(e) => Err(e),
+        };
+        assert_eq!(res.unwrap_err().to_string(), "the I/O helper thread panicked");
+    });
+}
+
+// Blocks on seeks until the test lets them through.
+struct GatedSeeker {
+    gate: mpsc::Receiver<()>,
+    pos: u64,
+}
+
+impl io::Seek for GatedSeeker {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.gate.recv().unwrap();
+        match pos {
+            SeekFrom::Start(n) => self.pos = n,
+            SeekFrom::Current(n) | SeekFrom::End(n) => self.pos = (self.pos as i64 + n) as u64,
+        }
+        Ok(self.pos)
+    }
+}
+
+#[test]
+fn abandoned_seek_is_followed_by_the_new_one() {
+    let (tx, rx) = mpsc::channel();
+    let mut io = Unblock::new(GatedSeeker { gate: rx, pos: 0 });
+    let mut cx = noop_context();
+    assert!(Pin::new(&mut io).poll_seek(&mut cx, SeekFrom::Start(6)).is_pending());
+
+    tx.send(()).unwrap();
+    tx.send(()).unwrap();
+    let pos = block_on(poll_fn(|cx| Pin::new(&mut io).poll_seek(cx, SeekFrom::Start(2))));
+    assert_eq!(pos.unwrap(), 2);
+}
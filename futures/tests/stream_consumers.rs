@@ -0,0 +1,144 @@
+use futures::executor::block_on;
+use futures::future;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use std::cell::Cell;
+
+#[test]
+fn count_and_last() {
+    block_on(async {
+        assert_eq!(stream::iter(0..10).count().await, 10);
+        assert_eq!(stream::empty::<i32>().count().await, 0);
+        assert_eq!(stream::iter(0..10).last().await, Some(9));
+        assert_eq!(stream::empty::<i32>().last().await, None);
+    });
+}
+
+#[test]
+fn any_all_short_circuit() {
+    block_on(async {
+        let polled = Cell::new(0);
+        let stream = stream::iter(1..=10).inspect(|_| polled.set(polled.get() + 1));
+        assert!(stream.any(|x| async move { x == 3 }).await);
+        assert_eq!(polled.get(), 3);
+
+        polled.set(0);
+        let stream = stream::iter(1..=10).inspect(|_| polled.set(polled.get() + 1));
+        assert!(!stream.all(|x| async move { x < 4 }).await);
+        assert_eq!(polled.get(), 4);
+
+        assert!(!stream::empty::<i32>().any(|_| async { true }).await);
+        assert!(stream::empty::<i32>().all(|_| async { false }).await);
+    });
+}
+
+#[test]
+fn find_and_position() {
+    block_on(async {
+        let mut stream = stream::iter(1..=10);
+        assert_eq!(stream.by_ref().find(|x| future::ready(x % 4 == 0)).await, Some(4));
+        assert_eq!(stream.next().await, Some(5));
+        assert_eq!(stream.find(|x| future::ready(*x > 10)).await, None);
+
+        let stream = stream::iter(vec!["a", "1", "b"]);
+        assert_eq!(stream.find_map(|s| async move { s.parse::<u8>().ok() }).await, Some(1));
+
+        let stream = stream::iter(vec![5, 7, 8, 9]);
+        assert_eq!(stream.position(|x| async move { x % 2 == 0 }).await, Some(2));
+        assert_eq!(stream::iter(1..3).position(|_| async { false }).await, None);
+    });
+}
+
+#[test]
+fn nth_leaves_remaining_items() {
+    block_on(async {
+        let mut stream = stream::iter(0..5);
+        assert_eq!(stream.nth(0).await, Some(0));
+        assert_eq!(stream.nth(2).await, Some(3));
+        assert_eq!(stream.next().await, Some(4));
+        assert_eq!(stream.nth(0).await, None);
+    });
+}
+
+#[test]
+fn min_max_by_key_ties() {
+    block_on(async {
+        let items = vec![(1, 'a'), (0, 'b'), (1, 'c'), (0, 'd')];
+        assert_eq!(stream::iter(items.clone()).min_by_key(|x| x.0).await, Some((0, 'b')));
+        assert_eq!(stream::iter(items).max_by_key(|x| x.0).await, Some((1, 'c')));
+        assert_eq!(stream::empty::<i32>().min_by_key(|x| *x).await, None);
+    });
+}
+
+#[test]
+fn reduce() {
+    block_on(async {
+        let product = stream::iter(1..=5).reduce(|acc, x| async move { acc * x });
+        assert_eq!(product.await, Some(120));
+        assert_eq!(stream::iter(vec![7]).reduce(|_, _| async { unreachable!() }).await, Some(7));
+    });
+}
+
+#[test]
+fn try_consumers_stop_at_first_error() {
+    block_on(async {
+        let polled = Cell::new(0);
+        let items = || {
+            stream::iter(vec![Ok::<i32, &str>(1), Err("boom"), Ok(2)])
+                .inspect(|_| polled.set(polled.get() + 1))
+        };
+
+        assert_eq!(items().try_count().await, Err("boom"));
+        assert_eq!(polled.replace(0), 2);
+        assert_eq!(items().try_any(|x| async move { x == 2 }).await, Err("boom"));
+        assert_eq!(polled.replace(0), 2);
+        assert_eq!(items().try_all(|_| async { true }).await, Err("boom"));
+        assert_eq!(items().try_find(|x| future::ready(*x == 2)).await, Err("boom"));
+        assert_eq!(items().try_position(|x| async move { x == 2 }).await, Err("boom"));
+        assert_eq!(items().try_last().await, Err("boom"));
+        assert_eq!(items().try_min_by_key(|x| *x).await, Err("boom"));
+        assert_eq!(items().try_max_by_key(|x| *x).await, Err("boom"));
+        assert_eq!(items().try_reduce(|a, b| async move { Ok(a + b) }).await, Err("boom"));
+        assert_eq!(
+            items().try_find_map(|x| async move { Ok(Some(x).filter(|x| *x == 2)) }).await,
+            Err("boom")
+        );
+        assert_eq!(polled.get(), 2 * 8);
+    });
+}
+
+#[test]
+fn try_consumers_short_circuit_on_success() {
+    block_on(async {
+        let items = || stream::iter(vec![Ok::<i32, &str>(1), Ok(2), Err("boom")]);
+
+        assert_eq!(items().try_any(|x| async move { x == 1 }).await, Ok(true));
+        assert_eq!(items().try_all(|x| async move { x == 2 }).await, Ok(false));
+        assert_eq!(items().try_find(|x| future::ready(*x == 2)).await, Ok(Some(2)));
+        assert_eq!(items().try_position(|x| async move { x == 2 }).await, Ok(Some(1)));
+        assert_eq!(items().try_find_map(|x| async move { Ok(Some(x * 10)) }).await, Ok(Some(10)));
+
+        let mut stream = items();
+        assert_eq!(stream.try_nth(1).await, Ok(Some(2)));
+        assert_eq!(stream.try_nth(0).await, Err("boom"));
+        assert_eq!(stream.try_nth(0).await, Ok(None));
+    });
+}
+
+#[test]
+fn try_reduce_closure_error() {
+    block_on(async {
+        let stream = stream::iter(vec![Ok::<i32, i32>(1), Ok(2), Ok(3)]);
+        let res = stream.try_reduce(|acc, x| async move {
+            if x == 2 {
+                Err(x)
+            } else {
+                Ok(acc + x)
+            }
+        });
+        assert_eq!(res.await, Err(2));
+
+        let stream = stream::iter(vec![Ok::<i32, i32>(1), Ok(2), Ok(3)]);
+        assert_eq!(stream.try_reduce(|acc, x| async move { Ok(acc + x) }).await, Ok(Some(6)));
+        assert_eq!(stream::empty::<Result<i32, i32>>().try_last().await, Ok(None));
+    });
+}
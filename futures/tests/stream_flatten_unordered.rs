@@ -0,0 +1,122 @@
+use futures::channel::mpsc;
+use futures::executor::block_on;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use futures::task::{Context, Poll};
+use futures_test::task::{new_count_waker, noop_context};
+use std::cell::Cell;
+use std::rc::Rc;
+
+#[test]
+fn yields_items_as_they_become_ready() {
+    let (tx1, rx1) = mpsc::unbounded();
+    let (tx2, rx2) = mpsc::unbounded();
+    let mut stream = stream::iter(vec![rx1, rx2]).flatten_unordered(None);
+    let mut cx = noop_context();
+
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    tx2.unbounded_send(2).unwrap();
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some(2)));
+    tx1.unbounded_send(1).unwrap();
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some(1)));
+
+    drop(tx1);
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    drop(tx2);
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(None));
+}
+
+#[test]
+fn respects_limit() {
+    let active = Rc::new(Cell::new(0));
+    let max_active = Rc::new(Cell::new(0));
+    let (a, m) = (active.clone(), max_active.clone());
+    let stream = stream::iter(0..10).flat_map_unordered(3, move |i| {
+        let (a, m) = (a.clone(), m.clone());
+        // not `Unpin`
+        stream::unfold(0, move |n| {
+            let (a, m) = (a.clone(), m.clone());
+            async move {
+                if n == 0 {
+                    a.set(a.get() + 1);
+                    m.set(m.get().max(a.get()));
+                }
+                if n == 2 {
+                    a.set(a.get() - 1);
+                    return None;
+                }
+                Some((i, n + 1))
+            }
+        })
+    });
+
+    let mut items = block_on(stream.collect::<Vec<_>>());
+    items.sort();
+    assert_eq!(items, (0..10).flat_map(|i| vec![i, i]).collect::<Vec<_>>());
+    assert_eq!(max_active.get(), 3);
+    assert_eq!(active.get(), 0);
+}
+
+#[test]
+fn zero_limit_is_unlimited() {
+    let (txs, rxs): (Vec<_>, Vec<_>) = (0..5).map(|_| mpsc::unbounded::<i32>()).unzip();
+    let mut stream = stream::iter(rxs).flatten_unordered(0);
+    let mut cx = noop_context();
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    txs[4].unbounded_send(4).unwrap();
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some(4)));
+}
+
+#[test]
+fn yields_when_the_stream_is_always_ready() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut stream = stream::repeat_with(stream::pending::<i32>).flatten_unordered(None);
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    // the stream is polled again
+    let wakeups = counter.get();
+    assert!(wakeups > 0);
+
+    // inner streams that end at once
+    let mut stream = stream::repeat_with(stream::empty::<i32>).flatten_unordered(None);
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    assert!(counter.get() > wakeups);
+    let wakeups = counter.get();
+
+    let mut stream = stream::repeat_with(|| Ok::<_, ()>(stream::pending::<Result<i32, ()>>()))
+        .try_flatten_unordered(None);
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    assert!(counter.get() > wakeups);
+}
+
+#[test]
+fn try_flatten_unordered_short_circuits() {
+    let (tx1, rx1) = mpsc::unbounded::<Result<i32, &str>>();
+    let (tx2, rx2) = mpsc::unbounded();
+    let mut stream = stream::iter(vec![Ok(rx1), Ok(rx2)]).try_flatten_unordered(None);
+    let mut cx = noop_context();
+
+    tx1.unbounded_send(Ok(1)).unwrap();
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some(Ok(1))));
+    tx2.unbounded_send(Err("boom")).unwrap();
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some(Err("boom"))));
+    // the remaining streams are dropped
+    assert!(tx1.is_closed());
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(None));
+}
+
+#[test]
+fn try_flatten_unordered_outer_error() {
+    let pages = stream::iter(vec![Ok(stream::iter(vec![Ok(1)])), Err("no more pages")]);
+    let items = block_on(pages.try_flatten_unordered(1).collect::<Vec<_>>());
+    assert_eq!(items, [Ok(1), Err("no more pages")]);
+}
+
+#[test]
+fn try_flat_map_unordered() {
+    let stream = stream::iter(vec![Ok::<_, ()>(1), Ok(2), Ok(3)])
+        .try_flat_map_unordered(2, |x| stream::iter(vec![Ok::<_, ()>(x); x]));
+    let mut items = block_on(stream.try_collect::<Vec<_>>()).unwrap();
+    items.sort();
+    assert_eq!(items, [1, 2, 2, 3, 3, 3]);
+}
@@ -0,0 +1,172 @@
+use futures::channel::mpsc;
+use futures::executor::{block_on, LocalPool};
+use futures::stream::{self, LocalBoxStream, StreamExt};
+use futures::task::{Context, LocalSpawnExt, Poll};
+use futures_test::task::{new_count_waker, noop_context};
+use std::cell::{Cell, RefCell};
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+
+#[test]
+fn dedup() {
+    block_on(async {
+        let stream = stream::iter(vec!["a", "a", "b", "a", "a"]);
+        assert_eq!(stream.dedup().collect::<Vec<_>>().await, ["a", "b", "a"]);
+
+        let stream = stream::iter(vec![(1, 'a'), (1, 'b'), (2, 'c'), (1, 'd')]);
+        let deduped = stream.dedup_by_key(|x| x.0).collect::<Vec<_>>().await;
+        assert_eq!(deduped, [(1, 'a'), (2, 'c'), (1, 'd')]);
+    });
+}
+
+#[test]
+fn chunk_by_waits_for_the_end_of_a_run() {
+    let (tx, rx) = mpsc::unbounded();
+    let mut stream = rx.chunk_by(|x: &(u8, char)| x.0);
+    let mut cx = noop_context();
+
+    tx.unbounded_send((1, 'a')).unwrap();
+    tx.unbounded_send((1, 'b')).unwrap();
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    tx.unbounded_send((2, 'c')).unwrap();
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some((1, vec![(1, 'a'), (1, 'b')]))));
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    drop(tx);
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some((2, vec![(2, 'c')]))));
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(None));
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(None));
+}
+
+#[test]
+fn group_by_key_routes_items() {
+    let (tx, rx) = mpsc::unbounded();
+    let groups = rx.group_by_key(4, |s: &String| s.len());
+    let results = Rc::new(RefCell::new(Vec::new()));
+
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    let r = results.clone();
+    pool.spawner()
+        .spawn_local(groups.for_each(move |(len, group)| {
+            let r = r.clone();
+            spawner
+                .spawn_local(async move {
+                    let items = group.collect::<Vec<_>>().await;
+                    r.borrow_mut().push((len, items));
+                })
+                .unwrap();
+            async {}
+        }))
+        .unwrap();
+
+    for word in &["one", "three", "two", "four", "five", "six"] {
+        tx.unbounded_send(word.to_string()).unwrap();
+        pool.run_until_stalled();
+    }
+    drop(tx);
+    pool.run();
+
+    let mut results = results.borrow().clone();
+    results.sort();
+    assert_eq!(
+        results,
+        [
+            (3, vec!["one".to_string(), "two".to_string(), "six".to_string()]),
+            (4, vec!["four".to_string(), "five".to_string()]),
+            (5, vec!["three".to_string()]),
+        ]
+    );
+}
+
+#[test]
+fn group_by_key_applies_backpressure() {
+    let polled = Rc::new(Cell::new(0));
+    let p = polled.clone();
+    let stream = stream::iter(0..10).inspect(move |_| p.set(p.get() + 1));
+    let mut groups = stream.group_by_key(2, |x| x % 2);
+    let mut cx = noop_context();
+
+    let (_, mut even) = match groups.poll_next_unpin(&mut cx) {
+        Poll::Ready(Some(group)) => group,
+        _ => panic!(),
+    };
+    let (_, mut odd) = match groups.poll_next_unpin(&mut cx) {
+        Poll::Ready(Some(group)) => group,
+        _ => panic!(),
+    };
+    assert_eq!((*even.key(), *odd.key()), (0, 1));
+
+    // The even group's buffer fills up before it is polled.
+    assert_eq!(odd.poll_next_unpin(&mut cx), Poll::Ready(Some(1)));
+    assert_eq!(odd.poll_next_unpin(&mut cx), Poll::Pending);
+    assert_eq!(polled.get(), 3);
+
+    assert_eq!(even.poll_next_unpin(&mut cx), Poll::Ready(Some(0)));
+    assert_eq!(odd.poll_next_unpin(&mut cx), Poll::Ready(Some(3)));
+    assert_eq!(odd.poll_next_unpin(&mut cx), Poll::Pending);
+    assert_eq!(polled.get(), 5);
+
+    // Dropping a group discards its items and releases the stream.
+    drop(even);
+    assert_eq!(block_on(odd.collect::<Vec<_>>()), [5, 7, 9]);
+    assert_eq!(block_on(groups.next()).map(|(key, _)| key), None);
+}
+
+#[test]
+fn group_by_key_outer_dropped() {
+    let mut groups = stream::iter(vec![1, 2, 3, 4, 5]).group_by_key(8, |x| *x < 3);
+    let (_, small) = block_on(groups.next()).unwrap();
+    drop(groups);
+    // Items of groups that were never yielded are discarded.
+    assert_eq!(block_on(small.collect::<Vec<_>>()), [1, 2]);
+}
+
+#[test]
+fn group_by_key_yields_when_items_go_to_dropped_groups() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let mut groups = stream::repeat(1).group_by_key(1, |x| *x);
+    let (_, group) = block_on(groups.next()).unwrap();
+    drop(group);
+
+    assert!(groups.poll_next_unpin(&mut cx).is_pending());
+    assert_eq!(counter, 1);
+}
+
+#[test]
+fn group_by_key_panic_in_closure_poisons_every_group() {
+    let mut groups = stream::iter(1..=2).group_by_key(2, |x| {
+        if *x == 2 {
+            panic!("boom")
+        }
+        *x
+    });
+    let mut cx = noop_context();
+
+    let (_, mut group) = block_on(groups.next()).unwrap();
+    assert_eq!(group.poll_next_unpin(&mut cx), Poll::Ready(Some(1)));
+    assert!(panic::catch_unwind(AssertUnwindSafe(|| group.poll_next_unpin(&mut cx))).is_err());
+    let err = panic::catch_unwind(AssertUnwindSafe(|| groups.poll_next_unpin(&mut cx)));
+    assert_eq!(*err.unwrap_err().downcast::<&str>().unwrap(), "inner stream panicked during poll");
+}
+
+#[test]
+fn group_by_key_upstream_can_poll_the_groups() {
+    type Groups = LocalBoxStream<'static, (i32, LocalBoxStream<'static, i32>)>;
+    let groups = Rc::new(RefCell::new(None::<Groups>));
+    let groups2 = groups.clone();
+    let upstream = stream::poll_fn(move |cx| {
+        if let Some(groups) = groups2.borrow_mut().as_mut() {
+            assert!(groups.poll_next_unpin(cx).is_pending());
+        }
+        Poll::Ready(Some(1))
+    });
+    let mut outer = upstream.group_by_key(2, |x| *x);
+    let mut cx = noop_context();
+
+    let (_, mut group) = block_on(outer.next()).unwrap();
+    *groups.borrow_mut() = Some(outer.map(|(key, group)| (key, group.boxed_local())).boxed_local());
+    assert_eq!(group.poll_next_unpin(&mut cx), Poll::Ready(Some(1)));
+    assert_eq!(group.poll_next_unpin(&mut cx), Poll::Ready(Some(1)));
+    groups.borrow_mut().take();
+}
@@ -0,0 +1,124 @@
+use futures::channel::oneshot;
+use futures::executor::block_on;
+use futures::future;
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
+use futures::task::Poll;
+use futures_test::task::noop_context;
+use std::cell::Cell;
+
+fn assert_send<T: Send>(_: &T) {}
+
+#[test]
+fn stream() {
+    let stream = stream::stream! {
+        yield 1;
+        for i in 2..4 {
+            yield future::ready(i).await;
+        }
+        let mut v = [3, 4];
+        for x in v.iter_mut() {
+            *x += 1;
+            yield *x;
+        }
+    };
+    assert_send(&stream);
+    assert_eq!(block_on(stream.collect::<Vec<_>>()), [1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn stream_is_lazy() {
+    let ran = &Cell::new(0);
+    let mut stream = Box::pin(stream::stream! {
+        ran.set(1);
+        yield ();
+        ran.set(2);
+    });
+    let mut cx = noop_context();
+
+    assert_eq!(ran.get(), 0);
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some(())));
+    assert_eq!(ran.get(), 1);
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(None));
+    assert_eq!(ran.get(), 2);
+    assert!(futures::stream::FusedStream::is_terminated(&stream));
+}
+
+#[test]
+fn stream_waits_for_futures() {
+    let (tx, rx) = oneshot::channel();
+    let mut stream = Box::pin(stream::stream! {
+        yield 1;
+        yield rx.await.unwrap();
+    });
+    let mut cx = noop_context();
+
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some(1)));
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    tx.send(2).unwrap();
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some(2)));
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(None));
+}
+
+#[test]
+fn nested_stream() {
+    let stream = stream::stream! {
+        for i in 0..3 {
+            let inner = stream::stream! {
+                for j in 0..i {
+                    yield j;
+                }
+            };
+            let inner: Vec<_> = inner.collect().await;
+            yield inner;
+        }
+    };
+    let items = block_on(stream.collect::<Vec<_>>());
+    assert_eq!(items, [vec![], vec![0], vec![0, 1]]);
+}
+
+fn numbers(input: &[i64]) -> impl Stream<Item = Result<u8, String>> + '_ {
+    stream::try_stream! {
+        for &x in input {
+            let x: Result<u8, std::num::TryFromIntError> = std::convert::TryFrom::try_from(x);
+            yield x.map_err(|e| e.to_string())?;
+        }
+    }
+}
+
+#[test]
+fn try_stream() {
+    let items = block_on(numbers(&[1, 2, 3]).collect::<Vec<_>>());
+    assert_eq!(items, [Ok(1), Ok(2), Ok(3)]);
+
+    // The stream ends after the first error.
+    let items = block_on(numbers(&[1, -1, 3]).collect::<Vec<_>>());
+    assert_eq!(items.len(), 2);
+    assert!(items[1].is_err());
+}
+
+#[derive(Debug, PartialEq)]
+struct Error(&'static str);
+
+impl From<&'static str> for Error {
+    fn from(s: &'static str) -> Self {
+        Error(s)
+    }
+}
+
+#[test]
+fn try_stream_converts_errors() {
+    let stream = stream::try_stream! {
+        yield 1;
+        let x: u8 = Err("oops")?;
+        yield x;
+    };
+    let items: Vec<Result<u8, Error>> = block_on(stream.collect());
+    assert_eq!(items, [Ok(1), Err(Error("oops"))]);
+
+    let stream = stream::try_stream! {
+        let ok: Result<u8, Error> = Ok(1);
+        yield ok?;
+    };
+    let items: Result<Vec<u8>, Error> = block_on(stream.try_collect());
+    assert_eq!(items, Ok(vec![1]));
+}
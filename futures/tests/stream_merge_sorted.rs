@@ -0,0 +1,70 @@
+use futures::channel::mpsc;
+use futures::executor::block_on;
+use futures::stream::{self, FusedStream, Stream, StreamExt, TryStreamExt};
+use futures::task::Poll;
+use futures_test::task::noop_context;
+
+#[test]
+fn merges_in_order() {
+    let streams = vec![
+        stream::iter(vec![1, 5, 9, 10]),
+        stream::iter(vec![]),
+        stream::iter(vec![2, 3, 11]),
+        stream::iter(vec![4]),
+    ];
+    let merged = stream::merge_sorted(streams);
+    assert_eq!(merged.size_hint(), (8, Some(8)));
+    assert_eq!(block_on(merged.collect::<Vec<_>>()), [1, 2, 3, 4, 5, 9, 10, 11]);
+}
+
+#[test]
+fn waits_for_pending_streams() {
+    let (tx1, rx1) = mpsc::unbounded();
+    let (tx2, rx2) = mpsc::unbounded();
+    let mut merged = stream::merge_sorted(vec![rx1, rx2]);
+    let mut cx = noop_context();
+
+    tx1.unbounded_send(5).unwrap();
+    assert_eq!(merged.poll_next_unpin(&mut cx), Poll::Pending);
+    tx2.unbounded_send(1).unwrap();
+    assert_eq!(merged.poll_next_unpin(&mut cx), Poll::Ready(Some(1)));
+    // The second stream might still yield something smaller than 5.
+    assert_eq!(merged.poll_next_unpin(&mut cx), Poll::Pending);
+    drop(tx2);
+    assert_eq!(merged.poll_next_unpin(&mut cx), Poll::Ready(Some(5)));
+    assert_eq!(merged.poll_next_unpin(&mut cx), Poll::Pending);
+    drop(tx1);
+    assert_eq!(merged.poll_next_unpin(&mut cx), Poll::Ready(None));
+    assert!(merged.is_terminated());
+}
+
+#[test]
+fn ties_are_stable() {
+    let a = stream::iter(vec![(1, 'a'), (2, 'a')]);
+    let b = stream::iter(vec![(1, 'b'), (2, 'b')]);
+    let merged = stream::merge_sorted_by_key(vec![b, a], |x| x.0);
+    assert_eq!(block_on(merged.collect::<Vec<_>>()), [(1, 'b'), (1, 'a'), (2, 'b'), (2, 'a')]);
+}
+
+#[test]
+fn try_merge_sorted_yields_errors_immediately() {
+    let (tx, rx) = mpsc::unbounded();
+    let other = stream::iter(vec![Ok(1), Ok(4)]);
+    let mut merged = stream::try_merge_sorted(vec![rx.boxed(), other.boxed()]);
+    let mut cx = noop_context();
+
+    assert_eq!(merged.poll_next_unpin(&mut cx), Poll::Pending);
+    tx.unbounded_send(Err("oops")).unwrap();
+    assert_eq!(merged.poll_next_unpin(&mut cx), Poll::Ready(Some(Err("oops"))));
+    tx.unbounded_send(Ok(2)).unwrap();
+    drop(tx);
+    assert_eq!(block_on(merged.collect::<Vec<_>>()), [Ok(1), Ok(2), Ok(4)]);
+}
+
+#[test]
+fn try_merge_sorted_by_key() {
+    let a = stream::iter(vec![Ok::<_, ()>("a"), Ok("ccc")]);
+    let b = stream::iter(vec![Ok("bb"), Ok("dddd")]);
+    let merged = stream::try_merge_sorted_by_key(vec![a, b], |s| s.len());
+    assert_eq!(block_on(merged.try_collect::<Vec<_>>()), Ok(vec!["a", "bb", "ccc", "dddd"]));
+}
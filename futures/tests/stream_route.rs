@@ -0,0 +1,104 @@
+use futures::executor::block_on;
+use futures::future;
+use futures::stream::{self, LocalBoxStream, StreamExt};
+use futures::task::{Context, Poll};
+use futures_test::task::{new_count_waker, noop_context};
+use std::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+use std::thread;
+
+#[test]
+fn partition() {
+    let (evens, odds) = stream::iter(1..=10).partition(10, |x| x % 2 == 0);
+    assert_eq!(block_on(evens.collect::<Vec<_>>()), [2, 4, 6, 8, 10]);
+    assert_eq!(block_on(odds.collect::<Vec<_>>()), [1, 3, 5, 7, 9]);
+}
+
+#[test]
+fn partition_backpressure() {
+    let (mut evens, mut odds) = stream::iter(vec![1, 3, 5, 2, 7]).partition(2, |x| x % 2 == 0);
+    let mut cx = noop_context();
+
+    // The buffer of `odds` fills up before an even item is found.
+    assert_eq!(evens.poll_next_unpin(&mut cx), Poll::Pending);
+    assert_eq!(odds.poll_next_unpin(&mut cx), Poll::Ready(Some(1)));
+    assert_eq!(evens.poll_next_unpin(&mut cx), Poll::Pending);
+    assert_eq!(odds.poll_next_unpin(&mut cx), Poll::Ready(Some(3)));
+    assert_eq!(odds.poll_next_unpin(&mut cx), Poll::Ready(Some(5)));
+    assert_eq!(evens.poll_next_unpin(&mut cx), Poll::Ready(Some(2)));
+    assert_eq!(evens.poll_next_unpin(&mut cx), Poll::Ready(None));
+    assert_eq!(odds.poll_next_unpin(&mut cx), Poll::Ready(Some(7)));
+    assert_eq!(odds.poll_next_unpin(&mut cx), Poll::Ready(None));
+}
+
+#[test]
+fn partition_dropped_branch() {
+    let (evens, odds) = stream::iter(1..=10).partition(1, |x| x % 2 == 0);
+    drop(odds);
+    assert_eq!(block_on(evens.collect::<Vec<_>>()), [2, 4, 6, 8, 10]);
+}
+
+#[test]
+fn route() {
+    let shards = stream::iter(0..20).route(4, 2, |x| *x);
+    assert_eq!(shards.iter().map(|s| s.index()).collect::<Vec<_>>(), [0, 1, 2, 3]);
+
+    let shards = block_on(future::join_all(shards.into_iter().map(|s| s.collect::<Vec<_>>())));
+    for (i, shard) in shards.into_iter().enumerate() {
+        assert_eq!(shard, (i..20).step_by(4).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn route_across_threads() {
+    let shards = stream::iter(0..1000).route(3, 4, |x| *x as usize);
+    let handles = shards
+        .into_iter()
+        .map(|s| thread::spawn(move || block_on(s.collect::<Vec<_>>())))
+        .collect::<Vec<_>>();
+
+    let mut items = handles.into_iter().flat_map(|h| h.join().unwrap()).collect::<Vec<_>>();
+    items.sort_unstable();
+    assert_eq!(items, (0..1000).collect::<Vec<_>>());
+}
+
+#[test]
+fn yields_when_items_go_to_dropped_branches() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (mut accepted, rejected) = stream::repeat(1).partition(1, |_| false);
+    drop(rejected);
+
+    assert_eq!(accepted.poll_next_unpin(&mut cx), Poll::Pending);
+    assert_eq!(counter, 1);
+}
+
+#[test]
+fn panic_in_closure_poisons_every_branch() {
+    let (mut accepted, mut rejected) =
+        stream::iter(1..=2).partition(1, |_| -> bool { panic!("boom") });
+    let mut cx = noop_context();
+
+    assert!(panic::catch_unwind(AssertUnwindSafe(|| accepted.poll_next_unpin(&mut cx))).is_err());
+    let err = panic::catch_unwind(AssertUnwindSafe(|| rejected.poll_next_unpin(&mut cx)));
+    assert_eq!(*err.unwrap_err().downcast::<&str>().unwrap(), "inner stream panicked during poll");
+}
+
+#[test]
+fn upstream_can_poll_a_branch() {
+    let sibling = Rc::new(RefCell::new(None::<LocalBoxStream<'static, i32>>));
+    let sibling2 = sibling.clone();
+    let upstream = stream::poll_fn(move |cx| {
+        if let Some(sibling) = sibling2.borrow_mut().as_mut() {
+            assert_eq!(sibling.poll_next_unpin(cx), Poll::Pending);
+        }
+        Poll::Ready(Some(1))
+    });
+    let (mut accepted, rejected) = upstream.partition(1, |_| true);
+    *sibling.borrow_mut() = Some(rejected.boxed_local());
+
+    let mut cx = noop_context();
+    assert_eq!(accepted.poll_next_unpin(&mut cx), Poll::Ready(Some(1)));
+    sibling.borrow_mut().take();
+}
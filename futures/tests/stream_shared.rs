@@ -0,0 +1,175 @@
+use futures::channel::mpsc;
+use futures::executor::{block_on, LocalPool};
+use futures::future;
+use futures::stream::{self, LocalBoxStream, StreamExt};
+use futures::task::{LocalSpawnExt, Poll};
+use futures_test::task::{new_count_waker, noop_context};
+use std::cell::{Cell, RefCell};
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+use std::thread;
+
+#[test]
+fn clones_see_items_from_subscription() {
+    let (tx, rx) = mpsc::unbounded();
+    let mut first = rx.shared(4);
+    let mut cx = noop_context();
+
+    tx.unbounded_send(1).unwrap();
+    assert_eq!(first.poll_next_unpin(&mut cx), Poll::Ready(Some(1)));
+    let mut second = first.clone();
+    assert_eq!(first.subscriber_count(), Some(2));
+
+    tx.unbounded_send(2).unwrap();
+    drop(tx);
+    assert_eq!(second.poll_next_unpin(&mut cx), Poll::Ready(Some(2)));
+    assert_eq!(second.poll_next_unpin(&mut cx), Poll::Ready(None));
+    assert_eq!(first.poll_next_unpin(&mut cx), Poll::Ready(Some(2)));
+    assert_eq!(first.poll_next_unpin(&mut cx), Poll::Ready(None));
+    assert_eq!(first.poll_next_unpin(&mut cx), Poll::Ready(None));
+    assert_eq!(first.subscriber_count(), None);
+}
+
+#[test]
+fn slowest_handle_applies_backpressure() {
+    let polled = Rc::new(Cell::new(0));
+    let p = polled.clone();
+    let mut fast = stream::iter(0..10).inspect(move |_| p.set(p.get() + 1)).shared(2);
+    let mut slow = fast.clone();
+    let (waker, count) = new_count_waker();
+    let mut cx = futures::task::Context::from_waker(&waker);
+
+    assert_eq!(fast.poll_next_unpin(&mut cx), Poll::Ready(Some(0)));
+    assert_eq!(fast.poll_next_unpin(&mut cx), Poll::Ready(Some(1)));
+    assert_eq!(fast.poll_next_unpin(&mut cx), Poll::Pending);
+    assert_eq!(polled.get(), 2);
+
+    // Catching up makes room and wakes the fast handle.
+    assert_eq!(slow.poll_next_unpin(&mut noop_context()), Poll::Ready(Some(0)));
+    assert_eq!(count, 1);
+    assert_eq!(fast.poll_next_unpin(&mut cx), Poll::Ready(Some(2)));
+    assert_eq!(fast.poll_next_unpin(&mut cx), Poll::Pending);
+    assert_eq!(polled.get(), 3);
+
+    // Dropping the slow handle releases the upstream.
+    drop(slow);
+    assert_eq!(count, 2);
+    assert_eq!(block_on(fast.collect::<Vec<_>>()), (3..10).collect::<Vec<_>>());
+}
+
+#[test]
+fn lagging_handle_skips_items() {
+    let mut fast = stream::iter(0..10).shared(2);
+    let mut lagging = fast.clone().lagging();
+    let mut cx = noop_context();
+
+    for i in 0..6 {
+        assert_eq!(fast.poll_next_unpin(&mut cx), Poll::Ready(Some(i)));
+    }
+    // Only the last two items are still buffered.
+    assert_eq!(lagging.poll_next_unpin(&mut cx), Poll::Ready(Some(4)));
+    assert_eq!(block_on(fast.collect::<Vec<_>>()), (6..10).collect::<Vec<_>>());
+    assert_eq!(block_on(lagging.collect::<Vec<_>>()), [8, 9]);
+}
+
+#[test]
+fn pending_upstream_wakes_every_handle() {
+    let (tx, rx) = mpsc::unbounded();
+    let shared = rx.shared(1);
+    let results = Rc::new(RefCell::new(Vec::new()));
+
+    let mut pool = LocalPool::new();
+    for (i, stream) in vec![shared.clone(), shared.clone(), shared].into_iter().enumerate() {
+        let results = results.clone();
+        pool.spawner()
+            .spawn_local(async move {
+                let items = stream.collect::<Vec<_>>().await;
+                results.borrow_mut().push((i, items));
+            })
+            .unwrap();
+    }
+
+    pool.run_until_stalled();
+    for i in 0..5 {
+        tx.unbounded_send(i).unwrap();
+        pool.run_until_stalled();
+    }
+    drop(tx);
+    pool.run();
+
+    let mut results = results.borrow().clone();
+    results.sort();
+    assert_eq!(results, (0..3).map(|i| (i, (0..5).collect())).collect::<Vec<_>>());
+}
+
+#[test]
+fn shared_across_threads() {
+    let shared = stream::iter(0..1000).shared(8);
+    // Subscribe before any of the threads starts consuming.
+    let streams = (0..4).map(|_| shared.clone()).collect::<Vec<_>>();
+    let handles = streams
+        .into_iter()
+        .map(|stream| {
+            thread::spawn(move || block_on(stream.fold(0, |acc, x| future::ready(acc + x))))
+        })
+        .collect::<Vec<_>>();
+    drop(shared);
+
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), (0..1000).sum::<i32>());
+    }
+}
+
+#[test]
+fn upstream_is_polled_without_holding_the_lock() {
+    let (entered_tx, entered_rx) = std::sync::mpsc::channel();
+    let (gate_tx, gate_rx) = std::sync::mpsc::channel::<()>();
+    let blocking_end = stream::poll_fn(move |_| {
+        entered_tx.send(()).unwrap();
+        gate_rx.recv().unwrap();
+        Poll::Ready(None)
+    });
+    let mut first = stream::iter(vec![1]).chain(blocking_end).shared(2);
+    let mut second = first.clone();
+    let mut cx = noop_context();
+    assert_eq!(first.poll_next_unpin(&mut cx), Poll::Ready(Some(1)));
+
+    let handle = thread::spawn(move || block_on(first.next()));
+    entered_rx.recv().unwrap();
+    // the buffered item can be taken while the other thread polls the upstream
+    assert_eq!(second.poll_next_unpin(&mut cx), Poll::Ready(Some(1)));
+    assert_eq!(second.poll_next_unpin(&mut cx), Poll::Pending);
+
+    gate_tx.send(()).unwrap();
+    assert_eq!(handle.join().unwrap(), None);
+    assert_eq!(second.poll_next_unpin(&mut cx), Poll::Ready(None));
+}
+
+#[test]
+fn reentrant_poll_is_pending() {
+    let other = Rc::new(RefCell::new(None::<LocalBoxStream<'static, i32>>));
+    let other2 = other.clone();
+    let mut first = stream::poll_fn(move |cx| {
+        let mut other = other2.borrow_mut();
+        assert_eq!(other.as_mut().unwrap().poll_next_unpin(cx), Poll::Pending);
+        Poll::Ready(Some(1))
+    })
+    .shared(1);
+    *other.borrow_mut() = Some(first.clone().boxed_local());
+
+    let mut cx = noop_context();
+    assert_eq!(first.poll_next_unpin(&mut cx), Poll::Ready(Some(1)));
+    let mut other = other.borrow_mut().take().unwrap();
+    assert_eq!(other.poll_next_unpin(&mut cx), Poll::Ready(Some(1)));
+}
+
+#[test]
+fn upstream_panic_poisons_every_handle() {
+    let mut first = stream::poll_fn(|_| -> Poll<Option<i32>> { panic!("boom") }).shared(1);
+    let mut second = first.clone();
+    let mut cx = noop_context();
+
+    assert!(panic::catch_unwind(AssertUnwindSafe(|| first.poll_next_unpin(&mut cx))).is_err());
+    let err = panic::catch_unwind(AssertUnwindSafe(|| second.poll_next_unpin(&mut cx)));
+    assert_eq!(*err.unwrap_err().downcast::<&str>().unwrap(), "inner stream panicked during poll");
+}
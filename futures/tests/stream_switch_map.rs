@@ -0,0 +1,91 @@
+use futures::channel::{mpsc, oneshot};
+use futures::executor::block_on;
+use futures::future;
+use futures::stream::{self, StreamExt};
+use futures::task::{Context, Poll};
+use futures_test::task::{new_count_waker, noop_context};
+use std::cell::Cell;
+
+#[test]
+fn switch_map() {
+    let (tx, rx) = mpsc::unbounded();
+    let mut stream = rx.switch_map(|x| stream::iter(x..x + 3));
+    let mut cx = noop_context();
+
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    tx.unbounded_send(10).unwrap();
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some(10)));
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some(11)));
+    tx.unbounded_send(20).unwrap();
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some(20)));
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some(21)));
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some(22)));
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    drop(tx);
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(None));
+}
+
+#[test]
+fn switch_map_waits_for_latest_inner_stream() {
+    let (tx, rx) = mpsc::unbounded();
+    let (inner_tx, inner_rx) = mpsc::unbounded();
+    tx.unbounded_send(inner_rx).unwrap();
+    drop(tx);
+
+    let mut stream = rx.switch_map(|s| s);
+    let mut cx = noop_context();
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    inner_tx.unbounded_send(1).unwrap();
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some(1)));
+    drop(inner_tx);
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(None));
+}
+
+#[test]
+fn switch_map_only_maps_the_latest_ready_item() {
+    let calls = Cell::new(0);
+    let stream = stream::iter(0..10).switch_map(|x| {
+        calls.set(calls.get() + 1);
+        stream::iter(vec![x])
+    });
+    assert_eq!(block_on(stream.collect::<Vec<_>>()), [9]);
+    assert_eq!(calls.get(), 1);
+}
+
+#[test]
+fn yields_when_the_stream_is_always_ready() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut stream = stream::repeat(1).switch_map(|_| stream::pending::<i32>());
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    assert_eq!(counter, 1);
+
+    let mut stream = stream::repeat(1).then_latest(|_| future::pending::<i32>());
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    assert_eq!(counter, 2);
+}
+
+#[test]
+fn then_latest() {
+    let (tx, rx) = mpsc::unbounded();
+    let mut senders = Vec::new();
+    let mut stream = rx.then_latest(|rx: oneshot::Receiver<i32>| rx);
+    let mut cx = noop_context();
+
+    for _ in 0..2 {
+        let (otx, orx) = oneshot::channel();
+        tx.unbounded_send(orx).unwrap();
+        senders.push(otx);
+    }
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    // The future of the first item has been dropped.
+    assert!(senders[0].is_canceled());
+    senders.remove(1).send(2).unwrap();
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some(Ok(2))));
+    drop(tx);
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(None));
+
+    let stream = stream::iter(1..=3).then_latest(future::ready);
+    assert_eq!(block_on(stream.collect::<Vec<_>>()), [3]);
+}
@@ -0,0 +1,102 @@
+use futures::future;
+use futures::stream::{self, Stream, StreamExt};
+use futures::task::{Context, Poll};
+use futures_test::sim::{self, SimExecutor, Timer};
+use futures_test::task::new_count_waker;
+use std::time::Duration;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+// Yields every item after its delay since the previous one.
+fn delayed<T>(items: Vec<(u64, T)>) -> impl Stream<Item = T> {
+    stream::iter(items).then(|(delay, item)| async move {
+        sim::sleep(ms(delay)).await;
+        item
+    })
+}
+
+// Collects the items of a stream along with the time they were yielded at.
+fn run<St: Stream>(stream: St) -> Vec<(St::Item, u64)> {
+    let stream = stream.map(|item| (item, sim::now().as_millis() as u64));
+    SimExecutor::new(0).run_until(stream.collect())
+}
+
+#[test]
+fn throttle() {
+    let stream = delayed(vec![(0, 1), (50, 2), (60, 3), (90, 4), (200, 5)]);
+    assert_eq!(run(stream.throttle(ms(100), Timer)), [(1, 0), (3, 110), (5, 400)]);
+
+    let stream = stream::iter(1..=3).throttle(ms(100), Timer);
+    assert_eq!(run(stream), [(1, 0)]);
+}
+
+#[test]
+fn debounce() {
+    let stream = delayed(vec![(0, 1), (50, 2), (200, 3), (99, 4), (300, 5)]);
+    assert_eq!(run(stream.debounce(ms(100), Timer)), [(2, 150), (4, 449), (5, 649)]);
+
+    assert_eq!(run(stream::iter(1..=3).debounce(ms(100), Timer)), [(3, 0)]);
+    assert_eq!(run(stream::empty::<()>().debounce(ms(100), Timer)), []);
+}
+
+#[test]
+fn sample() {
+    let stream = delayed(vec![(10, 1), (20, 2), (250, 3), (10, 4), (100, 5)]);
+    assert_eq!(run(stream.sample(ms(100), Timer)), [(2, 100), (4, 300), (5, 390)]);
+
+    assert_eq!(run(stream::iter(1..=3).sample(ms(100), Timer)), [(3, 0)]);
+}
+
+#[test]
+fn always_ready_stream_or_timer_does_not_spin() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let ready_timer = |_: Duration| future::ready(());
+    let never_timer = |_: Duration| future::pending::<()>();
+
+    let mut stream = stream::pending::<i32>().sample(ms(0), ready_timer);
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    assert_eq!(counter, 1);
+
+    let mut stream = stream::repeat(1).sample(ms(100), never_timer);
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    assert_eq!(counter, 2);
+
+    let mut stream = stream::repeat(1).throttle(ms(100), never_timer);
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some(1)));
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    assert_eq!(counter, 3);
+
+    let mut stream = stream::repeat(1).debounce(ms(100), never_timer);
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    assert_eq!(counter, 4);
+}
+
+#[test]
+fn rate_limit() {
+    // The stream isn't polled while there is no token left.
+    let stream = delayed(vec![(0, 1), (0, 2), (0, 3), (0, 4), (1000, 5), (0, 6), (0, 7), (0, 8)]);
+    assert_eq!(
+        run(stream.rate_limit(3, ms(100), Timer)),
+        [(1, 0), (2, 0), (3, 0), (4, 100), (5, 1200), (6, 1200), (7, 1200), (8, 1300)]
+    );
+}
+
+#[test]
+fn rate_limit_refill() {
+    // The bucket is full again at 200, and doesn't keep refilling after
+    // that.
+    let stream = delayed(vec![(0, 1), (0, 2), (150, 3), (0, 4), (0, 5)]);
+    assert_eq!(
+        run(stream.rate_limit(2, ms(100), Timer)),
+        [(1, 0), (2, 0), (3, 250), (4, 250), (5, 350)]
+    );
+}
+
+#[test]
+#[should_panic]
+fn rate_limit_zero_burst() {
+    let _ = stream::empty::<()>().rate_limit(0, ms(100), Timer);
+}
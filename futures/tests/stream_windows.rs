@@ -0,0 +1,57 @@
+use futures::channel::mpsc;
+use futures::executor::block_on;
+use futures::stream::{self, Stream, StreamExt};
+use futures::task::Poll;
+use futures_test::task::noop_context;
+
+#[test]
+fn windows() {
+    let windows = stream::iter(1..=5).windows(2);
+    assert_eq!(windows.size_hint(), (4, Some(4)));
+    let windows = block_on(windows.map(|w| w.to_vec()).collect::<Vec<_>>());
+    assert_eq!(windows, [[1, 2], [2, 3], [3, 4], [4, 5]]);
+
+    let windows = block_on(stream::iter(1..=2).windows(3).collect::<Vec<_>>());
+    assert!(windows.is_empty());
+}
+
+#[test]
+fn windows_yield_as_items_arrive() {
+    let (tx, rx) = mpsc::unbounded();
+    let mut windows = rx.windows(2);
+    let mut cx = noop_context();
+
+    tx.unbounded_send(1).unwrap();
+    assert_eq!(windows.poll_next_unpin(&mut cx), Poll::Pending);
+    tx.unbounded_send(2).unwrap();
+    assert_eq!(windows.poll_next_unpin(&mut cx), Poll::Ready(Some(vec![1, 2].into())));
+    tx.unbounded_send(3).unwrap();
+    assert_eq!(windows.poll_next_unpin(&mut cx), Poll::Ready(Some(vec![2, 3].into())));
+    drop(tx);
+    assert_eq!(windows.poll_next_unpin(&mut cx), Poll::Ready(None));
+}
+
+#[test]
+fn windows_by() {
+    // tumbling
+    let windows = stream::iter(1..=7).windows_by(3, 3);
+    assert_eq!(windows.size_hint(), (2, Some(2)));
+    let windows = block_on(windows.map(|w| w.to_vec()).collect::<Vec<_>>());
+    assert_eq!(windows, [[1, 2, 3], [4, 5, 6]]);
+
+    // hopping, skipping items in between
+    let windows = stream::iter(1..=10).windows_by(4, 2);
+    assert_eq!(windows.size_hint(), (3, Some(3)));
+    let windows = block_on(windows.map(|w| w.to_vec()).collect::<Vec<_>>());
+    assert_eq!(windows, [[1, 2], [5, 6], [9, 10]]);
+}
+
+#[test]
+fn windows_until() {
+    let windows = stream::iter(vec![1, 2, 0, 3, 0, 0, 4]).windows_until(|x| *x == 0);
+    let windows = block_on(windows.map(|w| w.to_vec()).collect::<Vec<_>>());
+    assert_eq!(windows, [vec![1, 2, 0], vec![3, 0], vec![0], vec![4]]);
+
+    let windows = stream::iter(vec![1, 0]).windows_until(|x| *x == 0);
+    assert_eq!(block_on(windows.count()), 1);
+}
@@ -0,0 +1,73 @@
+use futures::channel::mpsc;
+use futures::executor::block_on;
+use futures::stream::{self, EitherOrBoth, FusedStream, Stream, StreamExt};
+use futures::task::Poll;
+use futures_test::task::noop_context;
+
+#[test]
+fn zip_longest_continues_until_both_end() {
+    let stream = stream::iter(vec![1]).zip_longest(stream::iter(vec!['a', 'b', 'c']));
+    assert_eq!(stream.size_hint(), (3, Some(3)));
+    assert_eq!(
+        block_on(stream.collect::<Vec<_>>()),
+        [EitherOrBoth::Both(1, 'a'), EitherOrBoth::Right('b'), EitherOrBoth::Right('c')]
+    );
+}
+
+#[test]
+fn zip_longest_waits_for_the_other_stream() {
+    let (tx1, rx1) = mpsc::unbounded();
+    let (tx2, rx2) = mpsc::unbounded::<i32>();
+    let mut stream = rx1.zip_longest(rx2);
+    let mut cx = noop_context();
+
+    tx1.unbounded_send(1).unwrap();
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    drop(tx2);
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some(EitherOrBoth::Left(1))));
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    drop(tx1);
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(None));
+    assert!(stream.is_terminated());
+}
+
+#[test]
+fn either_or_both_accessors() {
+    let both = EitherOrBoth::<_, char>::Both(1, 'a');
+    assert_eq!(both.left(), Some(1));
+    assert_eq!(both.right(), Some('a'));
+    assert_eq!(EitherOrBoth::<i32, char>::Right('b').into_options(), (None, Some('b')));
+    assert_eq!(EitherOrBoth::<i32, char>::Left(2).or(0, 'z'), (2, 'z'));
+}
+
+#[test]
+fn zip_all_polls_every_stream() {
+    let (txs, rxs): (Vec<_>, Vec<_>) = (0..3).map(|_| mpsc::unbounded()).unzip();
+    let mut stream = stream::zip_all(rxs);
+    let mut cx = noop_context();
+
+    txs[2].unbounded_send(20).unwrap();
+    txs[0].unbounded_send(0).unwrap();
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    txs[1].unbounded_send(10).unwrap();
+    txs[1].unbounded_send(11).unwrap();
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some(vec![0, 10, 20])));
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+
+    // One of the streams ending ends the round.
+    txs[0].unbounded_send(1).unwrap();
+    drop(txs);
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(None));
+    assert!(stream.is_terminated());
+}
+
+#[test]
+fn zip_all_size_hint() {
+    let stream = stream::zip_all(vec![stream::iter(0..5), stream::iter(0..3)]);
+    assert_eq!(stream.size_hint(), (3, Some(3)));
+    assert_eq!(block_on(stream.count()), 3);
+
+    let mut empty = stream::zip_all(Vec::<stream::Iter<std::ops::Range<i32>>>::new());
+    assert_eq!(empty.size_hint(), (0, Some(0)));
+    assert_eq!(block_on(empty.next()), None);
+}
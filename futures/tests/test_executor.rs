@@ -0,0 +1,114 @@
+use futures::channel::oneshot;
+use futures::future::{self, pending};
+use futures::task::{LocalSpawnExt, Poll, SpawnExt};
+use futures_test::task::{RecordSpawner, TestExecutor};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[test]
+fn polls_in_ready_order() {
+    let executor = TestExecutor::new();
+    let (tx0, rx0) = oneshot::channel::<()>();
+    let (tx1, rx1) = oneshot::channel::<()>();
+    executor.spawn(async { rx0.await.unwrap() }).unwrap();
+    executor.spawn(async { rx1.await.unwrap() }).unwrap();
+    assert_eq!(executor.ready_task_count(), 2);
+
+    assert_eq!(executor.poll_one(), Some(0));
+    assert_eq!(executor.poll_one(), Some(1));
+    assert_eq!(executor.poll_one(), None);
+    assert_eq!(executor.task_count(), 2);
+
+    tx1.send(()).unwrap();
+    tx0.send(()).unwrap();
+    assert_eq!(executor.wake_log(), [1, 0]);
+    assert_eq!(executor.ready_task_count(), 2);
+    assert_eq!(executor.poll_one(), Some(1));
+    assert_eq!(executor.poll_one(), Some(0));
+    assert_eq!(executor.task_count(), 0);
+}
+
+#[test]
+fn run_until_stalled() {
+    let executor = TestExecutor::new();
+    executor.spawn_local(pending()).unwrap();
+    executor.spawn_local(future::ready(())).unwrap();
+    assert_eq!(executor.run_until_stalled(), Poll::Pending);
+    assert_eq!(executor.task_count(), 1);
+    assert_eq!(executor.ready_task_count(), 0);
+}
+
+#[test]
+fn wake_log_records_repeated_wakes() {
+    let executor = TestExecutor::new();
+    let waker = Rc::new(RefCell::new(None));
+    let w = waker.clone();
+    executor
+        .spawn_local(future::poll_fn(move |cx| {
+            *w.borrow_mut() = Some(cx.waker().clone());
+            Poll::<()>::Pending
+        }))
+        .unwrap();
+    assert_eq!(executor.run_until_stalled(), Poll::Pending);
+
+    let waker = waker.borrow_mut().take().unwrap();
+    waker.wake_by_ref();
+    waker.wake_by_ref();
+    assert_eq!(executor.wake_log(), [0, 0]);
+    assert!(executor.wake_log().is_empty());
+    // repeated wakes make the task ready only once
+    assert_eq!(executor.ready_task_count(), 1);
+}
+
+#[test]
+fn tasks_can_spawn_tasks() {
+    let executor = TestExecutor::new();
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let (ex, l) = (executor.spawner(), log.clone());
+    executor
+        .spawn_local(async move {
+            let l2 = l.clone();
+            ex.spawn_local(async move { l2.borrow_mut().push("child") }).unwrap();
+            l.borrow_mut().push("parent");
+        })
+        .unwrap();
+
+    assert_eq!(executor.poll_one(), Some(0));
+    assert_eq!(*log.borrow(), ["parent"]);
+    assert_eq!(executor.ready_task_count(), 1);
+    assert_eq!(executor.poll_one(), Some(1));
+    assert_eq!(*log.borrow(), ["parent", "child"]);
+}
+
+#[test]
+fn from_record_spawner() {
+    let recorder = RecordSpawner::new();
+    let (tx, mut rx) = oneshot::channel();
+    recorder.spawn(async move { tx.send(1).unwrap() }).unwrap();
+    assert_eq!(recorder.spawned().len(), 1);
+
+    let executor = TestExecutor::from(recorder);
+    assert_eq!(executor.run_until_stalled(), Poll::Ready(()));
+    assert_eq!(rx.try_recv(), Ok(Some(1)));
+}
+
+#[test]
+fn dropping_executor_drops_tasks_holding_spawner() {
+    let executor = TestExecutor::new();
+    let alive = Rc::new(());
+    let (ex, a) = (executor.spawner(), alive.clone());
+    executor
+        .spawn_local(async move {
+            let _a = a;
+            future::pending::<()>().await;
+            drop(ex);
+        })
+        .unwrap();
+    assert_eq!(executor.run_until_stalled(), Poll::Pending);
+    assert_eq!(Rc::strong_count(&alive), 2);
+
+    let spawner = executor.spawner();
+    drop(executor);
+    assert_eq!(Rc::strong_count(&alive), 1);
+    assert!(spawner.spawn_local(async {}).is_err());
+}
@@ -0,0 +1,134 @@
+use futures::channel::oneshot;
+use futures::future::{self, pending};
+use futures::task::LocalSpawnExt;
+use futures_test::sim::{self, Schedule, SimExecutor};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+// Spawns tasks that log their id at every yield point and returns the log.
+fn run_interleaved(executor: &mut SimExecutor) -> Vec<usize> {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    for id in 0..4 {
+        let log = log.clone();
+        executor
+            .spawner()
+            .spawn_local(async move {
+                for _ in 0..4 {
+                    log.borrow_mut().push(id);
+                    yield_now().await;
+                }
+            })
+            .unwrap();
+    }
+    executor.run();
+    let log = log.borrow().clone();
+    log
+}
+
+async fn yield_now() {
+    let mut yielded = false;
+    future::poll_fn(|cx| {
+        if yielded {
+            return std::task::Poll::Ready(());
+        }
+        yielded = true;
+        cx.waker().wake_by_ref();
+        std::task::Poll::Pending
+    })
+    .await
+}
+
+#[test]
+fn same_seed_same_interleaving() {
+    let a = run_interleaved(&mut SimExecutor::new(7));
+    let b = run_interleaved(&mut SimExecutor::new(7));
+    assert_eq!(a, b);
+    assert_eq!(a.len(), 16);
+
+    // some seed interleaves the tasks differently
+    assert!((0..16).any(|seed| run_interleaved(&mut SimExecutor::new(seed)) != a));
+}
+
+#[test]
+fn replay_recorded_schedule() {
+    let mut executor = SimExecutor::new(3);
+    let log = run_interleaved(&mut executor);
+    let schedule = executor.schedule();
+    assert_eq!(schedule.steps().len(), 20);
+
+    let mut replayed = SimExecutor::replay(schedule.to_string().parse().unwrap());
+    assert_eq!(replayed.seed(), None);
+    assert_eq!(run_interleaved(&mut replayed), log);
+    assert_eq!(replayed.schedule(), schedule);
+}
+
+#[test]
+#[should_panic(expected = "schedule diverged at step 0")]
+fn replay_diverged() {
+    let mut executor = SimExecutor::replay(Schedule::new(vec![5]));
+    executor.spawner().spawn_local(async {}).unwrap();
+    executor.run();
+}
+
+#[test]
+fn virtual_clock() {
+    let mut executor = SimExecutor::new(0);
+    let log = Rc::new(RefCell::new(Vec::new()));
+    for secs in [3, 1, 2].iter().copied() {
+        let log = log.clone();
+        executor
+            .spawner()
+            .spawn_local(async move {
+                sim::sleep(Duration::from_secs(secs)).await;
+                log.borrow_mut().push((secs, sim::now()));
+            })
+            .unwrap();
+    }
+    executor.run();
+    assert_eq!(executor.now(), Duration::from_secs(3));
+    assert_eq!(
+        *log.borrow(),
+        [(1, Duration::from_secs(1)), (2, Duration::from_secs(2)), (3, Duration::from_secs(3))]
+    );
+}
+
+#[test]
+fn dropped_sleep_does_not_advance_clock() {
+    let mut executor = SimExecutor::new(0);
+    executor.run_until(async {
+        let long = sim::sleep(Duration::from_secs(60));
+        let short = sim::sleep(Duration::from_secs(1));
+        futures::pin_mut!(long, short);
+        future::select(long, short).await;
+    });
+    executor.run();
+    assert_eq!(executor.now(), Duration::from_secs(1));
+}
+
+#[test]
+fn spawn_from_task() {
+    let mut executor = SimExecutor::new(1);
+    let (tx, rx) = oneshot::channel();
+    executor.run_until(async move {
+        sim::spawner().spawn_local(async move { tx.send(1).unwrap() }).unwrap();
+        assert_eq!(rx.await, Ok(1));
+    });
+}
+
+#[test]
+#[should_panic(expected = "simulation deadlocked")]
+fn deadlock_panics() {
+    SimExecutor::new(0).run_until(pending::<()>());
+}
+
+#[futures_test::test(seed = 42)]
+async fn test_attribute_with_seed() {
+    sim::sleep(Duration::from_millis(1500)).await;
+    assert_eq!(sim::now(), Duration::from_millis(1500));
+}
+
+#[futures_test::test(seed = 7 * 6)]
+async fn test_attribute_with_seed_expr() -> Result<(), ()> {
+    Ok(())
+}
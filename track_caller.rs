@@ -0,0 +1,12 @@
+// Shared by the build scripts of the crates whose spawn methods record the
+// location of their caller.
+
+// `#[track_caller]` stabilized in Rust 1.46:
+// https://blog.rust-lang.org/2020/08/27/Rust-1.46.0.html#track_caller
+fn probe_track_caller(cfg: &autocfg::AutoCfg) {
+    if cfg.probe_rustc_version(1, 46) {
+        println!("cargo:rustc-cfg=track_caller");
+    }
+    println!("cargo:rustc-check-cfg=cfg(track_caller)");
+    println!("cargo:rerun-if-changed=track_caller.rs");
+}